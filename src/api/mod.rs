@@ -13,9 +13,9 @@ pub struct MLflowAPI {
 
 impl MLflowAPI {
     /// New `MLflowAPI`, validating the `uri`.
-    pub fn new(uri: &str) -> Result<MLflowAPI, crate::errors::SetupError> {
-        match reqwest::Url::parse(&uri) {
-            Err(_) => Err(crate::errors::SetupError::InvalidUrl(uri.to_string())),
+    pub fn new(uri: &str) -> Result<MLflowAPI, errors::SetupError> {
+        match reqwest::Url::parse(uri) {
+            Err(_) => Err(errors::SetupError::InvalidUrl(uri.to_string())),
             Ok(_) => Ok(MLflowAPI {
                 uri: uri.to_string(),
                 client: reqwest::Client::new(),
@@ -38,15 +38,16 @@ struct EmptyResponse {}
 pub(crate) fn send_and_return_field<
     Resp,
     ExtractedResp,
-    ErrorCode: crate::errors::ErrorCode + std::fmt::Debug + serde::ser::Serialize,
+    ErrorCode,
     Extractor: FnOnce(Resp) -> ExtractedResp,
 >(
     request: reqwest::RequestBuilder,
     extract_response: Extractor,
-) -> Result<ExtractedResp, crate::errors::ClientError<ErrorCode>>
+) -> Result<ExtractedResp, errors::ClientError<ErrorCode>>
 where
     for<'de> Resp: serde::de::Deserialize<'de>,
-    for<'de> ErrorCode: serde::de::Deserialize<'de>,
+    for<'de> ErrorCode:
+        errors::ErrorCode + std::fmt::Debug + serde::ser::Serialize + serde::de::Deserialize<'de>,
 {
     match request.send()?.json::<Response<Resp, ErrorCode>>()? {
         Response::Success(resp) => Ok(extract_response(resp)),
@@ -57,7 +58,6 @@ where
 #[cfg(test)]
 mod tests {
 
-    use reqwest;
     use serde::{Deserialize, Serialize};
     use spectral::prelude::*;
 
@@ -85,7 +85,7 @@ mod tests {
     fn can_cast_reqwest_error() {
         #[derive(Serialize, Deserialize, Debug)]
         struct CustomError;
-        impl crate::errors::ErrorCode for CustomError {};
+        impl crate::errors::ErrorCode for CustomError {}
 
         fn test() -> Result<(), crate::errors::ClientError<CustomError>> {
             let req = reqwest::Client::new().get("http://ghghghgh");
@@ -1,6 +1,6 @@
 use crate::api::{send_and_return_field, EmptyResponse};
 use crate::errors::{ClientError, GetExperimentErrorCode};
-use crate::{FileInfo, MLflowAPI, Metric, Param, RunTag};
+use crate::{DatasetInput, FileInfo, MLflowAPI, Metric, MetricWithRunId, Param, RunTag};
 
 #[derive(serde::Serialize, Debug)]
 struct SetRunTagQuery<'a, 'b, 'c> {
@@ -26,7 +26,15 @@ struct LogMetricQuery<'a, 'b> {
 
 #[derive(serde::Deserialize, Debug)]
 struct GetMetricHistoryResponse {
+    #[serde(default)]
     metrics: Vec<Metric>,
+    next_page_token: Option<String>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct GetMetricHistoryBulkIntervalResponse {
+    #[serde(default)]
+    metrics: Vec<MetricWithRunId>,
 }
 
 #[derive(serde::Serialize, Debug)]
@@ -44,6 +52,12 @@ struct LogBatchQuery<'a, 'b, 'c, 'd, 'e, 'f, 'g> {
     tags: Option<&'f [&'g RunTag]>,
 }
 
+#[derive(serde::Serialize, Debug)]
+struct LogInputsQuery<'a, 'b> {
+    run_id: &'a str,
+    datasets: Option<&'b [DatasetInput]>,
+}
+
 #[derive(serde::Deserialize, Debug)]
 struct ListArtifactsResponse {
     root_uri: String,
@@ -102,17 +116,63 @@ impl MLflowAPI {
         send_and_return_field(req, |_: EmptyResponse| ())
     }
 
-    /// Get a list of all values for the specified metric for a given run.
+    /// Get a list of all values for the specified metric for a given run. Results are paginated on servers supporting
+    /// it, use the returned page token to get the next page.
     pub fn get_metric_history(
         &self,
         run_id: &str,
         metric_key: &str,
-    ) -> Result<Vec<Metric>, ClientError<GetExperimentErrorCode>> {
-        let req = self
+        max_results: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<(Vec<Metric>, Option<String>), ClientError<GetExperimentErrorCode>> {
+        let mut req = self
             .client
             .get(&format!("{}/api/2.0/mlflow/metrics/get-history", self.uri))
             .query(&[("run_id", run_id), ("metric_key", metric_key)]);
-        send_and_return_field(req, |resp: GetMetricHistoryResponse| resp.metrics)
+        if let Some(max_results) = max_results {
+            req = req.query(&[("max_results", max_results)]);
+        }
+        if let Some(page_token) = page_token {
+            req = req.query(&[("page_token", page_token)]);
+        }
+        send_and_return_field(req, |resp: GetMetricHistoryResponse| {
+            (resp.metrics, resp.next_page_token)
+        })
+    }
+
+    /// Get the values of a metric for several runs, between `start_step` and `end_step` if specified. When there are
+    /// more than `max_results` steps, the server samples them evenly, keeping the first and last ones. At most 100
+    /// runs can be requested at once.
+    pub fn get_metric_history_bulk_interval(
+        &self,
+        run_ids: &[&str],
+        metric_key: &str,
+        start_step: Option<u64>,
+        end_step: Option<u64>,
+        max_results: Option<u32>,
+    ) -> Result<Vec<MetricWithRunId>, ClientError<GetExperimentErrorCode>> {
+        let mut req = self
+            .client
+            .get(&format!(
+                "{}/api/2.0/mlflow/metrics/get-history-bulk-interval",
+                self.uri
+            ))
+            .query(&[("metric_key", metric_key)]);
+        for run_id in run_ids {
+            req = req.query(&[("run_ids", run_id)]);
+        }
+        if let Some(start_step) = start_step {
+            req = req.query(&[("start_step", start_step)]);
+        }
+        if let Some(end_step) = end_step {
+            req = req.query(&[("end_step", end_step)]);
+        }
+        if let Some(max_results) = max_results {
+            req = req.query(&[("max_results", max_results)]);
+        }
+        send_and_return_field(req, |resp: GetMetricHistoryBulkIntervalResponse| {
+            resp.metrics
+        })
     }
 
     /// Log a param used for a run. A param is a key-value pair (string key, string value). Examples include
@@ -154,6 +214,20 @@ impl MLflowAPI {
         send_and_return_field(req, |_: EmptyResponse| ())
     }
 
+    /// Log the datasets used by a run. A dataset logged several times in the same run, with the same name, digest and
+    /// tags, is only recorded once.
+    pub fn log_inputs(
+        &self,
+        run_id: &str,
+        datasets: Option<&[DatasetInput]>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let req = self
+            .client
+            .post(&format!("{}/api/2.0/mlflow/runs/log-inputs", self.uri))
+            .json(&LogInputsQuery { run_id, datasets });
+        send_and_return_field(req, |_: EmptyResponse| ())
+    }
+
     /// List artifacts for a run. Takes an optional artifact_path prefix which if specified, the response contains only
     /// artifacts with the specified prefix.
     pub fn list_artifacts(
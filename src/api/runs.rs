@@ -3,10 +3,12 @@ use crate::errors::{ClientError, GetExperimentErrorCode};
 use crate::{MLflowAPI, Run, RunInfo, RunStatus, RunTag, ViewType};
 
 #[derive(serde::Serialize, Debug)]
-struct CreateRunQuery<'a> {
+struct CreateRunQuery<'a, 'b> {
     experiment_id: &'a str,
     start_time: Option<u64>,
     tags: Option<Vec<RunTag>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_name: Option<&'b str>,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -30,10 +32,12 @@ struct RestoreRunQuery<'a> {
 }
 
 #[derive(serde::Serialize, Debug)]
-struct UpdateRunQuery<'a> {
+struct UpdateRunQuery<'a, 'b> {
     run_id: &'a str,
     status: RunStatus,
     end_time: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_name: Option<&'b str>,
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -59,12 +63,14 @@ struct SearchRunsResponse {
 
 impl MLflowAPI {
     /// Create a new run within an experiment. A run is usually a single execution of a machine learning or data ETL
-    /// pipeline. MLflow uses runs to track `Param`, `Metric`, and `RunTag` associated with a single execution.
+    /// pipeline. MLflow uses runs to track `Param`, `Metric`, and `RunTag` associated with a single execution. The
+    /// `run_name` is ignored by servers older than MLflow 1.28, use the `mlflow.runName` tag with them.
     pub fn create_run(
         &self,
         experiment_id: &str,
         start_time: Option<u64>,
         tags: Option<Vec<RunTag>>,
+        run_name: Option<&str>,
     ) -> Result<Run, ClientError<GetExperimentErrorCode>> {
         let req = self
             .client
@@ -73,6 +79,7 @@ impl MLflowAPI {
                 experiment_id,
                 start_time,
                 tags,
+                run_name,
             });
         send_and_return_field(req, |resp: CreateRunResponse| resp.run)
     }
@@ -106,12 +113,13 @@ impl MLflowAPI {
         send_and_return_field(req, |resp: GetRunResponse| resp.run)
     }
 
-    /// Restore a deleted run.
+    /// Update run metadata: its status, end time, and name if specified.
     pub fn update_run(
         &self,
         run_id: &str,
         status: RunStatus,
         end_time: Option<u64>,
+        run_name: Option<&str>,
     ) -> Result<RunInfo, ClientError<GetExperimentErrorCode>> {
         let req = self
             .client
@@ -120,6 +128,7 @@ impl MLflowAPI {
                 run_id,
                 status,
                 end_time,
+                run_name,
             });
         send_and_return_field(req, |resp: UpdateRunResponse| resp.run_info)
     }
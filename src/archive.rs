@@ -0,0 +1,823 @@
+//! Portable archives of experiments and runs, to move them between tracking servers.
+//!
+//! An archive uses the layout written by the `export-experiment` command of the community `mlflow-export-import`
+//! tool:
+//!
+//! ```text
+//! experiment.json          experiment metadata and list of runs
+//! <run_id>/run.json        run metadata, params, tags and full metric histories
+//! <run_id>/artifacts/...   artifacts of the run, when exported
+//! ```
+//!
+//! It is either a directory or a gzipped tarball of that directory, for paths ending in `.tar.gz` or `.tgz`. Archives
+//! are written by an [`Exporter`] and read by an [`Importer`].
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ArchiveError;
+use crate::store::TrackingStore;
+use crate::{LifecycleStage, Metric, Param, Run, RunInputs, RunStatus, RunTag, ViewType};
+
+/// Version of the `mlflow-export-import` format written in the manifests of the archives.
+pub const FORMAT_VERSION: &str = "1.2.0";
+
+const EXPERIMENT_MANIFEST: &str = "experiment.json";
+const RUN_MANIFEST: &str = "run.json";
+const ARTIFACTS_DIR: &str = "artifacts";
+
+/// Tag holding the ID of the run in the archive on the imported run.
+pub const SOURCE_RUN_ID_TAG: &str = "mlflow.source.run_id";
+
+/// Maximum number of params in a single `log_batch` request.
+const LOG_BATCH_MAX_PARAMS: usize = 100;
+/// Maximum number of metrics in a single `log_batch` request.
+const LOG_BATCH_MAX_METRICS: usize = 1000;
+
+/// Where and when an archive was written, at the top of each manifest.
+#[derive(Debug, Serialize, Deserialize)]
+struct System {
+    package_version: String,
+    #[serde(default)]
+    script: String,
+    #[serde(default)]
+    export_time: u64,
+    #[serde(default, rename = "_export_time")]
+    export_time_readable: String,
+    #[serde(default)]
+    mlflow_tracking_uri: String,
+    #[serde(default)]
+    user: String,
+}
+
+impl System {
+    fn new(script: &str, tracking_uri: &str) -> System {
+        let now = chrono::Utc::now();
+        System {
+            package_version: FORMAT_VERSION.to_string(),
+            script: script.to_string(),
+            export_time: now.timestamp() as u64,
+            export_time_readable: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+            mlflow_tracking_uri: tracking_uri.to_string(),
+            user: whoami::username(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExperimentManifest {
+    system: System,
+    #[serde(default)]
+    info: ExperimentExportInfo,
+    mlflow: ExperimentContent,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExperimentExportInfo {
+    num_total_runs: usize,
+    num_ok_runs: usize,
+    num_failed_runs: usize,
+    failed_runs: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExperimentContent {
+    experiment: ExportedExperiment,
+    runs: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportedExperiment {
+    experiment_id: String,
+    name: String,
+    #[serde(default)]
+    artifact_location: String,
+    lifecycle_stage: LifecycleStage,
+    #[serde(default)]
+    creation_time: Option<u64>,
+    #[serde(default)]
+    last_update_time: Option<u64>,
+    #[serde(default)]
+    tags: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RunManifest {
+    system: System,
+    mlflow: RunContent,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RunContent {
+    info: ExportedRunInfo,
+    #[serde(default)]
+    params: BTreeMap<String, String>,
+    #[serde(default)]
+    metrics: BTreeMap<String, Vec<MetricPoint>>,
+    #[serde(default)]
+    tags: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inputs: Option<RunInputs>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportedRunInfo {
+    run_id: String,
+    #[serde(default)]
+    run_uuid: String,
+    #[serde(default)]
+    run_name: Option<String>,
+    experiment_id: String,
+    #[serde(default)]
+    user_id: String,
+    status: RunStatus,
+    start_time: u64,
+    #[serde(default)]
+    end_time: Option<u64>,
+    lifecycle_stage: LifecycleStage,
+    #[serde(default)]
+    artifact_uri: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MetricPoint {
+    value: f32,
+    timestamp: u64,
+    step: u64,
+}
+
+/// Outcome of an export.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportReport {
+    /// ID of the exported experiment.
+    pub experiment_id: String,
+    /// IDs of the exported runs.
+    pub run_ids: Vec<String>,
+    /// Number of exported artifact files.
+    pub artifacts: usize,
+}
+
+/// Exporter of experiments from a tracking store to archives.
+#[derive(Debug)]
+pub struct Exporter<'a> {
+    store: &'a dyn TrackingStore,
+    tracking_uri: String,
+    artifacts: bool,
+}
+
+impl<'a> Exporter<'a> {
+    /// New `Exporter` reading experiments from `store`, without their artifacts.
+    pub fn new(store: &'a dyn TrackingStore) -> Exporter<'a> {
+        Exporter {
+            store,
+            tracking_uri: String::new(),
+            artifacts: false,
+        }
+    }
+
+    /// Also export the artifacts of the runs. `tracking_uri` is used for artifacts proxied by the tracking server.
+    pub fn with_artifacts(mut self, tracking_uri: &str) -> Exporter<'a> {
+        self.tracking_uri = tracking_uri.to_string();
+        self.artifacts = true;
+        self
+    }
+
+    /// Export an experiment with all its runs, deleted ones included, to `destination`, a directory or a `.tar.gz`
+    /// tarball.
+    pub fn export_experiment(
+        &self,
+        experiment_id: &str,
+        destination: &Path,
+    ) -> Result<ExportReport, ArchiveError> {
+        if is_tarball(destination) {
+            let dir = staging_dir(destination)?;
+            fs::create_dir_all(&dir)?;
+            let report = self
+                .export_experiment_dir(experiment_id, &dir)
+                .and_then(|report| {
+                    write_tarball(&dir, destination)?;
+                    Ok(report)
+                });
+            fs::remove_dir_all(&dir)?;
+            report
+        } else {
+            fs::create_dir_all(destination)?;
+            self.export_experiment_dir(experiment_id, destination)
+        }
+    }
+
+    fn export_experiment_dir(
+        &self,
+        experiment_id: &str,
+        dir: &Path,
+    ) -> Result<ExportReport, ArchiveError> {
+        let experiment = self.store.get_experiment(experiment_id)?;
+        let runs = self.search_all_runs(experiment_id)?;
+        let mut artifacts = 0;
+        for run in &runs {
+            artifacts += self.export_run(run, &dir.join(&run.info.run_id))?;
+        }
+        let run_ids: Vec<String> = runs.into_iter().map(|run| run.info.run_id).collect();
+        let manifest = ExperimentManifest {
+            system: System::new("export_experiment", &self.tracking_uri),
+            info: ExperimentExportInfo {
+                num_total_runs: run_ids.len(),
+                num_ok_runs: run_ids.len(),
+                ..Default::default()
+            },
+            mlflow: ExperimentContent {
+                experiment: ExportedExperiment {
+                    experiment_id: experiment.experiment_id,
+                    name: experiment.name,
+                    artifact_location: experiment.artifact_location,
+                    lifecycle_stage: experiment.lifecycle_stage,
+                    creation_time: experiment.creation_time,
+                    last_update_time: experiment.last_update_time,
+                    tags: experiment
+                        .tags
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|tag| (tag.key, tag.value))
+                        .collect(),
+                },
+                runs: run_ids.clone(),
+            },
+        };
+        write_json(&dir.join(EXPERIMENT_MANIFEST), &manifest)?;
+
+        Ok(ExportReport {
+            experiment_id: experiment_id.to_string(),
+            run_ids,
+            artifacts,
+        })
+    }
+
+    fn search_all_runs(&self, experiment_id: &str) -> Result<Vec<Run>, ArchiveError> {
+        let mut runs = vec![];
+        let mut page_token = None;
+        loop {
+            let (page, next_page_token) = self.store.search_runs(
+                &[experiment_id],
+                None,
+                Some(ViewType::All),
+                None,
+                None,
+                page_token.as_deref(),
+            )?;
+            runs.extend(page);
+            match next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(runs),
+            }
+        }
+    }
+
+    /// Export a run to `run_dir`, returning the number of exported artifact files.
+    fn export_run(&self, run: &Run, run_dir: &Path) -> Result<usize, ArchiveError> {
+        fs::create_dir_all(run_dir)?;
+        let data = run.data.clone().unwrap_or_default();
+        let mut metrics = BTreeMap::new();
+        for metric in &data.metrics {
+            if !metrics.contains_key(&metric.key) {
+                let history = self.metric_history(&run.info.run_id, &metric.key)?;
+                metrics.insert(metric.key.clone(), history);
+            }
+        }
+        let tags: BTreeMap<String, String> = data
+            .tags
+            .into_iter()
+            .map(|tag| (tag.key, tag.value))
+            .collect();
+        let info = &run.info;
+        let manifest = RunManifest {
+            system: System::new("export_run", &self.tracking_uri),
+            mlflow: RunContent {
+                info: ExportedRunInfo {
+                    run_id: info.run_id.clone(),
+                    run_uuid: info.run_id.clone(),
+                    run_name: info.run_name.clone(),
+                    experiment_id: info.experiment_id.clone(),
+                    user_id: tags.get("mlflow.user").cloned().unwrap_or_default(),
+                    status: info.status,
+                    start_time: info.start_time,
+                    end_time: info.end_time,
+                    lifecycle_stage: info.lifecycle_stage,
+                    artifact_uri: info.artifact_uri.clone(),
+                },
+                params: data
+                    .params
+                    .into_iter()
+                    .map(|param| (param.key, param.value))
+                    .collect(),
+                metrics,
+                tags,
+                inputs: run.inputs.clone(),
+            },
+        };
+        write_json(&run_dir.join(RUN_MANIFEST), &manifest)?;
+
+        if self.artifacts {
+            let repository =
+                crate::artifacts::get_artifact_repository(&info.artifact_uri, &self.tracking_uri)?;
+            self.export_artifacts(
+                &info.run_id,
+                None,
+                &*repository,
+                &run_dir.join(ARTIFACTS_DIR),
+            )
+        } else {
+            Ok(0)
+        }
+    }
+
+    fn metric_history(&self, run_id: &str, key: &str) -> Result<Vec<MetricPoint>, ArchiveError> {
+        let mut history = vec![];
+        let mut page_token = None;
+        loop {
+            let (page, next_page_token) =
+                self.store
+                    .get_metric_history(run_id, key, None, page_token.as_deref())?;
+            history.extend(page.into_iter().map(|metric| MetricPoint {
+                value: metric.value,
+                timestamp: metric.timestamp,
+                step: metric.step,
+            }));
+            match next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(history),
+            }
+        }
+    }
+
+    fn export_artifacts(
+        &self,
+        run_id: &str,
+        path: Option<&str>,
+        repository: &dyn crate::artifacts::ArtifactRepository,
+        artifacts_dir: &Path,
+    ) -> Result<usize, ArchiveError> {
+        let (_, files) = self.store.list_artifacts(run_id, path)?;
+        let mut count = 0;
+        for file in files {
+            if file.is_dir {
+                count +=
+                    self.export_artifacts(run_id, Some(&file.path), repository, artifacts_dir)?;
+            } else {
+                let local_file = artifacts_dir.join(&file.path);
+                if let Some(parent) = local_file.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                repository.download_artifact(&file.path, &local_file)?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Outcome of an import.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportReport {
+    /// ID of the experiment the runs were imported into.
+    pub experiment_id: String,
+    /// IDs of the imported runs, by ID of the run in the archive.
+    pub run_ids: BTreeMap<String, String>,
+    /// IDs in the archive of the runs skipped because they were already imported.
+    pub skipped: Vec<String>,
+    /// Number of imported artifact files.
+    pub artifacts: usize,
+}
+
+/// Importer of archives written by an [`Exporter`] or `mlflow-export-import` into a tracking store.
+///
+/// Runs are recreated with their original start and end times, status, params, tags and full metric histories. Each
+/// imported run is tagged with [`SOURCE_RUN_ID_TAG`] once complete, and runs already carrying the tag in the target
+/// experiment are skipped, so that importing the same archive again only imports what is missing.
+#[derive(Debug)]
+pub struct Importer<'a> {
+    store: &'a dyn TrackingStore,
+    tracking_uri: String,
+    artifacts: bool,
+}
+
+impl<'a> Importer<'a> {
+    /// New `Importer` writing to `store`, without the artifacts of the runs.
+    pub fn new(store: &'a dyn TrackingStore) -> Importer<'a> {
+        Importer {
+            store,
+            tracking_uri: String::new(),
+            artifacts: false,
+        }
+    }
+
+    /// Also upload the artifacts of the runs. `tracking_uri` is used for artifacts proxied by the tracking server.
+    pub fn with_artifacts(mut self, tracking_uri: &str) -> Importer<'a> {
+        self.tracking_uri = tracking_uri.to_string();
+        self.artifacts = true;
+        self
+    }
+
+    /// Import the experiment in `source`, a directory or a `.tar.gz` tarball, into the experiment named
+    /// `experiment_name`, or named as in the archive if `None`. The experiment is created if it does not exist.
+    pub fn import_experiment(
+        &self,
+        source: &Path,
+        experiment_name: Option<&str>,
+    ) -> Result<ImportReport, ArchiveError> {
+        if is_tarball(source) {
+            let dir = staging_dir(source)?;
+            let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(File::open(source)?));
+            archive.unpack(&dir)?;
+            let report = self.import_experiment_dir(&dir, experiment_name);
+            fs::remove_dir_all(&dir)?;
+            report
+        } else {
+            self.import_experiment_dir(source, experiment_name)
+        }
+    }
+
+    fn import_experiment_dir(
+        &self,
+        dir: &Path,
+        experiment_name: Option<&str>,
+    ) -> Result<ImportReport, ArchiveError> {
+        let manifest: ExperimentManifest = read_json(&dir.join(EXPERIMENT_MANIFEST))?;
+        let exported = manifest.mlflow.experiment;
+        let name = experiment_name.unwrap_or(&exported.name);
+        let experiment_id = match self.store.get_experiment_by_name(name) {
+            Ok(experiment) => experiment.experiment_id,
+            Err(_) => self.store.create_experiment(name, None)?,
+        };
+        for (key, value) in &exported.tags {
+            self.store.set_experiment_tag(&experiment_id, key, value)?;
+        }
+
+        let imported = self.imported_runs(&experiment_id)?;
+        let mut report = ImportReport {
+            experiment_id,
+            run_ids: BTreeMap::new(),
+            skipped: vec![],
+            artifacts: 0,
+        };
+        for source_run_id in manifest.mlflow.runs {
+            if imported.contains_key(&source_run_id) {
+                report.skipped.push(source_run_id);
+                continue;
+            }
+            let (run_id, artifacts) =
+                self.import_run(&report.experiment_id, &dir.join(&source_run_id))?;
+            report.artifacts += artifacts;
+            report.run_ids.insert(source_run_id, run_id);
+        }
+        Ok(report)
+    }
+
+    /// Runs of an experiment already imported, by ID of the run in the archive.
+    fn imported_runs(&self, experiment_id: &str) -> Result<BTreeMap<String, String>, ArchiveError> {
+        let mut imported = BTreeMap::new();
+        let mut page_token = None;
+        loop {
+            let (runs, next_page_token) = self.store.search_runs(
+                &[experiment_id],
+                None,
+                Some(ViewType::All),
+                None,
+                None,
+                page_token.as_deref(),
+            )?;
+            for run in runs {
+                let data = run.data.unwrap_or_default();
+                if let Some(tag) = data
+                    .tags
+                    .into_iter()
+                    .find(|tag| tag.key == SOURCE_RUN_ID_TAG)
+                {
+                    imported.insert(tag.value, run.info.run_id);
+                }
+            }
+            match next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(imported),
+            }
+        }
+    }
+
+    /// Import the run in `run_dir`, returning the ID of the new run and the number of imported artifact files.
+    fn import_run(
+        &self,
+        experiment_id: &str,
+        run_dir: &Path,
+    ) -> Result<(String, usize), ArchiveError> {
+        let content = read_json::<RunManifest>(&run_dir.join(RUN_MANIFEST))?.mlflow;
+        let info = content.info;
+        let tags: Vec<RunTag> = content
+            .tags
+            .into_iter()
+            .filter(|(key, _)| key != SOURCE_RUN_ID_TAG)
+            .map(|(key, value)| RunTag { key, value })
+            .collect();
+        let run = self.store.create_run(
+            experiment_id,
+            Some(info.start_time),
+            Some(tags),
+            info.run_name.as_deref(),
+        )?;
+        let run_id = run.info.run_id;
+
+        let params: Vec<Param> = content
+            .params
+            .into_iter()
+            .map(|(key, value)| Param { key, value })
+            .collect();
+        for chunk in params.chunks(LOG_BATCH_MAX_PARAMS) {
+            let chunk: Vec<&Param> = chunk.iter().collect();
+            self.store.log_batch(&run_id, None, Some(&chunk), None)?;
+        }
+        let metrics: Vec<Metric> = content
+            .metrics
+            .into_iter()
+            .flat_map(|(key, history)| {
+                history.into_iter().map(move |point| Metric {
+                    key: key.clone(),
+                    value: point.value,
+                    timestamp: point.timestamp,
+                    step: point.step,
+                })
+            })
+            .collect();
+        for chunk in metrics.chunks(LOG_BATCH_MAX_METRICS) {
+            let chunk: Vec<&Metric> = chunk.iter().collect();
+            self.store.log_batch(&run_id, Some(&chunk), None, None)?;
+        }
+        if let Some(inputs) = content
+            .inputs
+            .filter(|inputs| !inputs.dataset_inputs.is_empty())
+        {
+            self.store
+                .log_inputs(&run_id, Some(&inputs.dataset_inputs))?;
+        }
+
+        let artifacts_dir = run_dir.join(ARTIFACTS_DIR);
+        let artifacts = if self.artifacts && artifacts_dir.is_dir() {
+            let repository = crate::artifacts::get_artifact_repository(
+                &run.info.artifact_uri,
+                &self.tracking_uri,
+            )?;
+            repository.log_artifacts(&artifacts_dir, None)?;
+            count_files(&artifacts_dir)?
+        } else {
+            0
+        };
+
+        if info.status != RunStatus::Running {
+            self.store
+                .update_run(&run_id, info.status, info.end_time, None)?;
+        }
+        // tagged last, a run interrupted while being imported is imported again
+        self.store
+            .set_run_tag(&run_id, SOURCE_RUN_ID_TAG, &info.run_id)?;
+        if info.lifecycle_stage == LifecycleStage::Deleted {
+            self.store.delete_run(&run_id)?;
+        }
+        Ok((run_id, artifacts))
+    }
+}
+
+fn count_files(dir: &Path) -> Result<usize, ArchiveError> {
+    let mut count = 0;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        count += if path.is_dir() {
+            count_files(&path)?
+        } else {
+            1
+        };
+    }
+    Ok(count)
+}
+
+fn is_tarball(path: &Path) -> bool {
+    let name = path.to_string_lossy();
+    name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// Directory next to a tarball where its content is written before being packed, or unpacked before being read.
+fn staging_dir(tarball: &Path) -> Result<PathBuf, ArchiveError> {
+    let mut dir = tarball.as_os_str().to_owned();
+    dir.push(".staging");
+    let dir = PathBuf::from(dir);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    Ok(dir)
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), ArchiveError> {
+    fs::write(path, serde_json::to_vec_pretty(value)?)?;
+    Ok(())
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, ArchiveError> {
+    let content = fs::read(path).map_err(|error| {
+        ArchiveError::Invalid(format!("can not read '{}': {}", path.display(), error))
+    })?;
+    Ok(serde_json::from_slice(&content)?)
+}
+
+fn write_tarball(dir: &Path, tarball: &Path) -> Result<(), ArchiveError> {
+    if let Some(parent) = tarball.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let encoder =
+        flate2::write::GzEncoder::new(File::create(tarball)?, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    builder.append_dir_all(".", dir)?;
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use spectral::prelude::*;
+
+    use super::{Exporter, Importer, SOURCE_RUN_ID_TAG};
+    use crate::{FileStore, MemoryStore, RunStatus};
+
+    #[test]
+    fn can_export_experiment_to_directory() {
+        let store = MemoryStore::new();
+        let experiment_id = store.create_experiment("exported", None).unwrap();
+        store
+            .set_experiment_tag(&experiment_id, "team", "core")
+            .unwrap();
+        let run = store
+            .create_run(&experiment_id, Some(1), None, Some("run"))
+            .unwrap();
+        let run_id = &run.info.run_id;
+        for step in 0..3 {
+            store
+                .log_metric(run_id, "loss", 1. / (step + 1) as f32, step, Some(step))
+                .unwrap();
+        }
+        store.log_param(run_id, "alpha", "0.5").unwrap();
+        store
+            .update_run(run_id, RunStatus::Finished, Some(2), None)
+            .unwrap();
+        let deleted = store
+            .create_run(&experiment_id, Some(3), None, None)
+            .unwrap();
+        store.delete_run(&deleted.info.run_id).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let report = Exporter::new(&store)
+            .export_experiment(&experiment_id, dir.path())
+            .unwrap();
+        assert_that!(report.run_ids).contains_all_of(&vec![run_id, &deleted.info.run_id]);
+        assert_that!(report.run_ids).has_length(2);
+
+        let experiment: serde_json::Value =
+            serde_json::from_slice(&fs::read(dir.path().join("experiment.json")).unwrap()).unwrap();
+        assert_that!(experiment["system"]["package_version"].as_str()).is_equal_to(Some("1.2.0"));
+        assert_that!(experiment["mlflow"]["experiment"]["tags"]["team"].as_str())
+            .is_equal_to(Some("core"));
+        assert_that!(experiment["mlflow"]["runs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(serde_json::Value::as_str)
+            .collect::<Vec<_>>())
+        .contains(run_id.as_str());
+        let run: serde_json::Value =
+            serde_json::from_slice(&fs::read(dir.path().join(run_id).join("run.json")).unwrap())
+                .unwrap();
+        assert_that!(run["mlflow"]["info"]["status"].as_str()).is_equal_to(Some("FINISHED"));
+        assert_that!(run["mlflow"]["params"]["alpha"].as_str()).is_equal_to(Some("0.5"));
+        assert_that!(run["mlflow"]["metrics"]["loss"].as_array().map(Vec::len))
+            .is_equal_to(Some(3));
+    }
+
+    #[test]
+    fn can_export_experiment_with_artifacts_to_tarball() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(&dir.path().join("mlruns").display().to_string()).unwrap();
+        let run = store.create_run("0", None, None, None).unwrap();
+        let local_file = dir.path().join("model.txt");
+        fs::write(&local_file, "weights").unwrap();
+        crate::artifacts::get_artifact_repository(&run.info.artifact_uri, "")
+            .unwrap()
+            .log_artifact(&local_file, Some("model"))
+            .unwrap();
+
+        let tarball = dir.path().join("export.tar.gz");
+        let report = Exporter::new(&store)
+            .with_artifacts("")
+            .export_experiment("0", &tarball)
+            .unwrap();
+        assert_that!(report.artifacts).is_equal_to(1);
+
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(
+            fs::File::open(&tarball).unwrap(),
+        ));
+        let paths: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect();
+        assert_that!(paths).contains("experiment.json".to_string());
+        assert_that!(paths).contains(format!("{}/artifacts/model/model.txt", run.info.run_id));
+        assert_that!(dir.path().join("export.tar.gz.staging").exists()).is_false();
+
+        let failed = dir.path().join("failed.tar.gz");
+        assert_that!(Exporter::new(&store).export_experiment("42", &failed)).is_err();
+        assert_that!(dir.path().join("failed.tar.gz.staging").exists()).is_false();
+        assert_that!(failed.exists()).is_false();
+    }
+
+    #[test]
+    fn can_import_exported_experiment_once() {
+        let source = MemoryStore::new();
+        let run = source
+            .create_run("0", Some(10), None, Some("trained"))
+            .unwrap();
+        let run_id = &run.info.run_id;
+        source.log_metric(run_id, "loss", 0.5, 11, Some(0)).unwrap();
+        source
+            .log_metric(run_id, "loss", 0.25, 12, Some(1))
+            .unwrap();
+        source.log_param(run_id, "alpha", "0.5").unwrap();
+        source
+            .update_run(run_id, RunStatus::Failed, Some(20), None)
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let tarball = dir.path().join("export.tgz");
+        Exporter::new(&source)
+            .export_experiment("0", &tarball)
+            .unwrap();
+
+        let target = MemoryStore::new();
+        let report = Importer::new(&target)
+            .import_experiment(&tarball, Some("imported"))
+            .unwrap();
+        let imported_run_id = &report.run_ids[run_id];
+        let imported = target.get_run(imported_run_id).unwrap();
+        assert_that!(imported.info.experiment_id).is_equal_to(&report.experiment_id);
+        assert_that!(imported.info.start_time).is_equal_to(10);
+        assert_that!(imported.info.end_time).is_equal_to(Some(20));
+        assert_that!(imported.info.status).is_equal_to(RunStatus::Failed);
+        assert_that!(imported.info.run_name.as_deref()).is_equal_to(Some("trained"));
+        assert_that!(target.metric_history_of(imported_run_id, "loss")).has_length(2);
+        assert_that!(target.params_of(imported_run_id).get("alpha").cloned())
+            .is_equal_to(Some("0.5".to_string()));
+        assert_that!(target
+            .tags_of(imported_run_id)
+            .get(SOURCE_RUN_ID_TAG)
+            .cloned())
+        .is_equal_to(Some(run_id.clone()));
+
+        let report = Importer::new(&target)
+            .import_experiment(&tarball, Some("imported"))
+            .unwrap();
+        assert_that!(report.run_ids.len()).is_equal_to(0);
+        assert_that!(report.skipped).is_equal_to(vec![run_id.clone()]);
+        assert_that!(target.runs()).has_length(1);
+    }
+
+    #[test]
+    fn can_import_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = FileStore::new(&dir.path().join("source").display().to_string()).unwrap();
+        let run = source.create_run("0", None, None, None).unwrap();
+        let local_file = dir.path().join("model.txt");
+        fs::write(&local_file, "weights").unwrap();
+        crate::artifacts::get_artifact_repository(&run.info.artifact_uri, "")
+            .unwrap()
+            .log_artifact(&local_file, Some("model"))
+            .unwrap();
+        let export_dir = dir.path().join("export");
+        Exporter::new(&source)
+            .with_artifacts("")
+            .export_experiment("0", &export_dir)
+            .unwrap();
+
+        let target = FileStore::new(&dir.path().join("target").display().to_string()).unwrap();
+        let report = Importer::new(&target)
+            .with_artifacts("")
+            .import_experiment(&export_dir, None)
+            .unwrap();
+        assert_that!(report.experiment_id).is_equal_to("0".to_string());
+        assert_that!(report.artifacts).is_equal_to(1);
+        let (_, files) = target
+            .list_artifacts(&report.run_ids[&run.info.run_id], Some("model"))
+            .unwrap();
+        assert_that!(files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect::<Vec<_>>())
+        .is_equal_to(vec!["model/model.txt"]);
+    }
+}
@@ -0,0 +1,321 @@
+//! Artifact storage.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::errors::ArtifactError;
+use crate::FileInfo;
+
+/// A place where run artifacts are stored.
+pub trait ArtifactRepository: std::fmt::Debug {
+    /// Upload a local file, under `artifact_path` if specified.
+    fn log_artifact(
+        &self,
+        local_file: &Path,
+        artifact_path: Option<&str>,
+    ) -> Result<(), ArtifactError>;
+
+    /// Download the artifact at `artifact_path` to `local_file`.
+    fn download_artifact(
+        &self,
+        artifact_path: &str,
+        local_file: &Path,
+    ) -> Result<(), ArtifactError>;
+
+    /// Delete the artifact or directory of artifacts at `artifact_path`, or all the artifacts if not specified.
+    /// Deleting artifacts that don't exist is not an error.
+    fn delete_artifacts(&self, artifact_path: Option<&str>) -> Result<(), ArtifactError>;
+
+    /// Upload the content of a local directory, under `artifact_path` if specified.
+    fn log_artifacts(
+        &self,
+        local_dir: &Path,
+        artifact_path: Option<&str>,
+    ) -> Result<(), ArtifactError> {
+        for entry in fs::read_dir(local_dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                let name = file_name(&path)?;
+                let sub_path = join_artifact_path(artifact_path, &name);
+                self.log_artifacts(&path, Some(&sub_path))?;
+            } else {
+                self.log_artifact(&path, artifact_path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Get the `ArtifactRepository` for an artifact URI. `tracking_uri` is used for artifacts proxied by the tracking
+/// server (`mlflow-artifacts:` scheme).
+pub fn get_artifact_repository(
+    artifact_uri: &str,
+    tracking_uri: &str,
+) -> Result<Box<dyn ArtifactRepository>, ArtifactError> {
+    if artifact_uri.starts_with("mlflow-artifacts:") {
+        Ok(Box::new(HttpArtifactRepository::new(
+            &resolve_artifact_uri(artifact_uri, tracking_uri),
+        )))
+    } else if artifact_uri.starts_with("http://") || artifact_uri.starts_with("https://") {
+        Ok(Box::new(HttpArtifactRepository::new(artifact_uri)))
+    } else if artifact_uri.starts_with("file://") {
+        Ok(Box::new(LocalArtifactRepository::new(
+            artifact_uri.trim_start_matches("file://"),
+        )))
+    } else if artifact_uri.starts_with("file:") {
+        Ok(Box::new(LocalArtifactRepository::new(
+            artifact_uri.trim_start_matches("file:"),
+        )))
+    } else if !artifact_uri.contains(':') || Path::new(artifact_uri).is_absolute() {
+        Ok(Box::new(LocalArtifactRepository::new(artifact_uri)))
+    } else {
+        Err(ArtifactError::UnsupportedUri(artifact_uri.to_string()))
+    }
+}
+
+/// The URI the artifacts at `artifact_uri` are actually read from: the HTTP URL of the artifacts proxied by the
+/// tracking server at `tracking_uri` for the `mlflow-artifacts:` scheme, `artifact_uri` itself otherwise.
+pub fn resolve_artifact_uri(artifact_uri: &str, tracking_uri: &str) -> String {
+    let path = match artifact_uri.strip_prefix("mlflow-artifacts:") {
+        Some(path) => path,
+        None => return artifact_uri.to_string(),
+    };
+    // `mlflow-artifacts://host:port/path` points to a specific server, `mlflow-artifacts:/path` to the tracking one
+    let (server, path) = if path.starts_with("//") {
+        let path = path.trim_start_matches("//");
+        match path.find('/') {
+            Some(index) => (format!("http://{}", &path[..index]), &path[index..]),
+            None => (format!("http://{}", path), ""),
+        }
+    } else {
+        (tracking_uri.trim_end_matches('/').to_string(), path)
+    };
+    format!("{}/api/2.0/mlflow-artifacts/artifacts{}", server, path)
+}
+
+/// Artifacts stored on a local file system.
+#[derive(Debug, Clone)]
+pub struct LocalArtifactRepository {
+    root: PathBuf,
+}
+
+impl LocalArtifactRepository {
+    /// New `LocalArtifactRepository` storing artifacts under `root`.
+    pub fn new<P: AsRef<Path>>(root: P) -> LocalArtifactRepository {
+        LocalArtifactRepository {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl ArtifactRepository for LocalArtifactRepository {
+    fn log_artifact(
+        &self,
+        local_file: &Path,
+        artifact_path: Option<&str>,
+    ) -> Result<(), ArtifactError> {
+        let target_dir = match artifact_path {
+            Some(artifact_path) => self.root.join(artifact_path),
+            None => self.root.clone(),
+        };
+        fs::create_dir_all(&target_dir)?;
+        fs::copy(local_file, target_dir.join(file_name(local_file)?))?;
+        Ok(())
+    }
+
+    fn download_artifact(
+        &self,
+        artifact_path: &str,
+        local_file: &Path,
+    ) -> Result<(), ArtifactError> {
+        fs::copy(self.root.join(artifact_path), local_file)?;
+        Ok(())
+    }
+
+    fn delete_artifacts(&self, artifact_path: Option<&str>) -> Result<(), ArtifactError> {
+        let path = match artifact_path {
+            Some(artifact_path) => self.root.join(artifact_path),
+            None => self.root.clone(),
+        };
+        let deleted = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        match deleted {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Artifacts stored behind an HTTP server accepting `PUT` requests, like the MLflow artifacts proxy.
+#[derive(Debug)]
+pub struct HttpArtifactRepository {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl HttpArtifactRepository {
+    /// New `HttpArtifactRepository` storing artifacts under `base_url`.
+    pub fn new(base_url: &str) -> HttpArtifactRepository {
+        HttpArtifactRepository {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl ArtifactRepository for HttpArtifactRepository {
+    fn log_artifact(
+        &self,
+        local_file: &Path,
+        artifact_path: Option<&str>,
+    ) -> Result<(), ArtifactError> {
+        let path = join_artifact_path(artifact_path, &file_name(local_file)?);
+        self.client
+            .put(&format!("{}/{}", self.base_url, path))
+            .body(fs::File::open(local_file)?)
+            .send()?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    fn download_artifact(
+        &self,
+        artifact_path: &str,
+        local_file: &Path,
+    ) -> Result<(), ArtifactError> {
+        let mut file = fs::File::create(local_file)?;
+        self.client
+            .get(&format!("{}/{}", self.base_url, artifact_path))
+            .send()?
+            .error_for_status()?
+            .copy_to(&mut file)?;
+        Ok(())
+    }
+
+    fn delete_artifacts(&self, artifact_path: Option<&str>) -> Result<(), ArtifactError> {
+        let url = match artifact_path {
+            Some(artifact_path) => format!("{}/{}", self.base_url, artifact_path),
+            None => self.base_url.clone(),
+        };
+        let response = self.client.delete(&url).send()?;
+        if response.status() != reqwest::StatusCode::NOT_FOUND {
+            response.error_for_status()?;
+        }
+        Ok(())
+    }
+}
+
+fn file_name(path: &Path) -> Result<String, ArtifactError> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            ArtifactError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("'{}' has no file name", path.display()),
+            ))
+        })
+}
+
+/// List the artifacts in the local directory `dir`, the directory of the artifacts at `artifact_path` if specified,
+/// sorted by path. A missing directory has no artifacts.
+pub(crate) fn list_local_artifacts(
+    dir: &Path,
+    artifact_path: Option<&str>,
+) -> std::io::Result<Vec<FileInfo>> {
+    let mut files = vec![];
+    if dir.is_dir() {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.path().is_dir();
+            files.push(FileInfo {
+                path: join_artifact_path(artifact_path, &name),
+                is_dir,
+                file_size: if is_dir {
+                    None
+                } else {
+                    Some(entry.metadata()?.len())
+                },
+            });
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+pub(crate) fn join_artifact_path(artifact_path: Option<&str>, name: &str) -> String {
+    match artifact_path {
+        Some(artifact_path) if !artifact_path.is_empty() => {
+            format!("{}/{}", artifact_path.trim_end_matches('/'), name)
+        }
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use spectral::prelude::*;
+
+    use super::{get_artifact_repository, join_artifact_path};
+
+    #[test]
+    fn can_join_artifact_path() {
+        assert_that!(join_artifact_path(None, "file")).is_equal_to("file".to_string());
+        assert_that!(join_artifact_path(Some(""), "file")).is_equal_to("file".to_string());
+        assert_that!(join_artifact_path(Some("model/"), "file"))
+            .is_equal_to("model/file".to_string());
+    }
+
+    #[test]
+    fn can_pick_repository_from_uri() {
+        assert_that!(get_artifact_repository(
+            "/mlruns/0/abc/artifacts",
+            "http://localhost:5000"
+        ))
+        .is_ok();
+        assert_that!(get_artifact_repository(
+            "file:///mlruns/0/abc/artifacts",
+            "http://localhost:5000"
+        ))
+        .is_ok();
+        let proxied =
+            get_artifact_repository("mlflow-artifacts:/0/abc/artifacts", "http://localhost:5000");
+        assert_that!(proxied).is_ok();
+        assert_that!(format!("{:?}", proxied.unwrap()))
+            .contains("http://localhost:5000/api/2.0/mlflow-artifacts/artifacts/0/abc/artifacts");
+        assert_that!(get_artifact_repository(
+            "s3://bucket/0/abc/artifacts",
+            "http://localhost:5000"
+        ))
+        .is_err();
+    }
+
+    #[test]
+    fn can_copy_directory_to_local_repository() {
+        let source = tempfile::tempdir().unwrap();
+        let target = tempfile::tempdir().unwrap();
+        fs::create_dir(source.path().join("data")).unwrap();
+        fs::write(source.path().join("MLmodel"), "flavors: {}").unwrap();
+        fs::write(source.path().join("data").join("weights"), "42").unwrap();
+
+        let repository =
+            get_artifact_repository(&format!("file://{}", target.path().display()), "").unwrap();
+        assert_that!(repository.log_artifacts(source.path(), Some("model"))).is_ok();
+
+        assert_that!(fs::read_to_string(
+            target.path().join("model").join("MLmodel")
+        ))
+        .is_ok()
+        .is_equal_to("flavors: {}".to_string());
+        assert_that!(fs::read_to_string(
+            target.path().join("model").join("data").join("weights")
+        ))
+        .is_ok()
+        .is_equal_to("42".to_string());
+    }
+}
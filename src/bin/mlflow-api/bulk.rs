@@ -0,0 +1,215 @@
+//! Operations on all the runs matching a search.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use mlflow_api::store::TrackingStore;
+use mlflow_api::{Run, RunStatus, ViewType};
+use serde::Serialize;
+
+use crate::error::CliError;
+
+/// Runs to operate on.
+#[derive(Debug)]
+pub struct Selection {
+    /// IDs of the experiments to search in.
+    pub experiment_ids: Vec<String>,
+    /// Search filter, all the runs if not set.
+    pub filter: Option<String>,
+    /// Lifecycle stage of the runs.
+    pub view_type: ViewType,
+}
+
+/// Operation applied to each run.
+#[derive(Debug, Clone)]
+pub enum Action {
+    /// Set a tag.
+    SetTag {
+        /// Key of the tag.
+        key: String,
+        /// Value of the tag.
+        value: String,
+    },
+    /// Delete the run.
+    Delete,
+    /// Restore the deleted run.
+    Restore,
+    /// Update the status of the run.
+    Update {
+        /// New status.
+        status: RunStatus,
+        /// End time, now for statuses other than running and scheduled if not set.
+        end_time: Option<u64>,
+    },
+}
+
+/// Line of the preview of the matching runs.
+#[derive(Debug, Serialize)]
+pub struct MatchedRun {
+    run_id: String,
+    run_name: Option<String>,
+    experiment_id: String,
+    status: RunStatus,
+    start_time: u64,
+}
+
+impl From<&Run> for MatchedRun {
+    fn from(run: &Run) -> MatchedRun {
+        MatchedRun {
+            run_id: run.info.run_id.clone(),
+            run_name: run.run_name().map(str::to_string),
+            experiment_id: run.info.experiment_id.clone(),
+            status: run.info.status,
+            start_time: run.info.start_time,
+        }
+    }
+}
+
+/// Error of the operation on one run.
+#[derive(Debug, Serialize)]
+pub struct RunFailure {
+    run_id: String,
+    error: String,
+}
+
+/// Outcome of a bulk operation.
+#[derive(Debug, Serialize)]
+pub struct BulkReport {
+    /// Number of runs matching the search.
+    pub matched: usize,
+    /// IDs of the runs the operation succeeded on.
+    pub succeeded: Vec<String>,
+    /// Runs the operation failed on, with the error.
+    pub failed: Vec<RunFailure>,
+}
+
+/// All the runs matching the selection, from every page of the search.
+pub fn find_runs(store: &dyn TrackingStore, selection: &Selection) -> Result<Vec<Run>, CliError> {
+    let experiment_ids: Vec<&str> = selection
+        .experiment_ids
+        .iter()
+        .map(String::as_str)
+        .collect();
+    let mut runs = vec![];
+    let mut page_token = None;
+    loop {
+        let (page, next_page_token) = store.search_runs(
+            &experiment_ids,
+            selection.filter.as_deref(),
+            Some(selection.view_type),
+            None,
+            None,
+            page_token.as_deref(),
+        )?;
+        runs.extend(page);
+        match next_page_token {
+            Some(token) if !token.is_empty() => page_token = Some(token),
+            _ => return Ok(runs),
+        }
+    }
+}
+
+/// Apply `action` to `runs` with `concurrency` requests at a time, reporting the progress on the standard error.
+pub fn apply(
+    store: &dyn TrackingStore,
+    runs: &[Run],
+    action: &Action,
+    concurrency: usize,
+) -> BulkReport {
+    let next = AtomicUsize::new(0);
+    let outcomes = Mutex::new(Vec::with_capacity(runs.len()));
+    std::thread::scope(|scope| {
+        for _ in 0..concurrency.clamp(1, runs.len().max(1)) {
+            scope.spawn(|| loop {
+                let run = match runs.get(next.fetch_add(1, Ordering::SeqCst)) {
+                    Some(run) => run,
+                    None => return,
+                };
+                let outcome = apply_to_run(store, &run.info.run_id, action)
+                    .map_err(|error| error.to_string());
+                let mut outcomes = outcomes.lock().expect("a worker panicked");
+                outcomes.push((run.info.run_id.clone(), outcome));
+                eprint!("\r{}/{} runs", outcomes.len(), runs.len());
+                let _ = std::io::stderr().flush();
+            });
+        }
+    });
+    if !runs.is_empty() {
+        eprintln!();
+    }
+
+    let mut report = BulkReport {
+        matched: runs.len(),
+        succeeded: vec![],
+        failed: vec![],
+    };
+    for (run_id, outcome) in outcomes.into_inner().expect("a worker panicked") {
+        match outcome {
+            Ok(()) => report.succeeded.push(run_id),
+            Err(error) => report.failed.push(RunFailure { run_id, error }),
+        }
+    }
+    report
+}
+
+fn apply_to_run(store: &dyn TrackingStore, run_id: &str, action: &Action) -> Result<(), CliError> {
+    match action {
+        Action::SetTag { key, value } => store.set_run_tag(run_id, key, value)?,
+        Action::Delete => store.delete_run(run_id)?,
+        Action::Restore => store.restore_run(run_id)?,
+        Action::Update { status, end_time } => {
+            let end_time = match status {
+                RunStatus::Running | RunStatus::Scheduled => *end_time,
+                _ => end_time.or_else(|| Some(crate::now())),
+            };
+            store.update_run(run_id, *status, end_time, None)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use mlflow_api::{MemoryStore, RunStatus, ViewType};
+    use spectral::prelude::*;
+
+    use super::{apply, find_runs, Action, Selection};
+
+    #[test]
+    fn can_kill_running_runs() {
+        let store = MemoryStore::new();
+        for _ in 0..5 {
+            store.create_run("0", Some(1), None, None).unwrap();
+        }
+        let done = store.create_run("0", Some(1), None, None).unwrap();
+        store
+            .update_run(&done.info.run_id, RunStatus::Finished, Some(2), None)
+            .unwrap();
+
+        let selection = Selection {
+            experiment_ids: vec!["0".to_string()],
+            filter: Some("attributes.status = 'RUNNING'".to_string()),
+            view_type: ViewType::ActiveOnly,
+        };
+        let runs = find_runs(&store, &selection).unwrap();
+        assert_that!(runs).has_length(5);
+
+        let action = Action::Update {
+            status: RunStatus::Killed,
+            end_time: None,
+        };
+        let report = apply(&store, &runs, &action, 3);
+        assert_that!(report.succeeded).has_length(5);
+        assert_that!(report.failed).is_empty();
+        assert_that!(find_runs(&store, &selection).unwrap()).is_empty();
+
+        store.delete_run(&done.info.run_id).unwrap();
+        let action = Action::SetTag {
+            key: "stale".to_string(),
+            value: "true".to_string(),
+        };
+        let report = apply(&store, &[done], &action, 3);
+        assert_that!(report.failed).has_length(1);
+    }
+}
@@ -0,0 +1,266 @@
+//! Shell completion scripts, with optional dynamic completion of experiments and runs.
+
+use std::io::Write;
+
+use mlflow_api::store::TrackingStore;
+use mlflow_api::ViewType;
+use structopt::clap::Shell;
+use structopt::StructOpt;
+
+use crate::error::CliError;
+
+/// Number of recent runs offered for completion.
+const RECENT_RUNS: u32 = 50;
+
+/// Commands whose first argument is a run ID.
+const RUN_ID_COMMANDS: &str =
+    "get delete restore update set-tag delete-tag log-param log-metric log-batch \
+     log-inputs history list-artifacts tail";
+/// Commands whose first argument is an experiment ID.
+const EXPERIMENT_ID_COMMANDS: &str =
+    "experiment:get experiment:delete experiment:restore experiment:update \
+     experiment:set-tag run:create run:search export-experiment";
+
+/// What to complete dynamically.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Candidates {
+    /// IDs of the recent runs.
+    RunIds,
+    /// IDs of the active experiments.
+    ExperimentIds,
+    /// Names of the active experiments.
+    ExperimentNames,
+}
+
+impl std::str::FromStr for Candidates {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Candidates, String> {
+        match value {
+            "run-ids" => Ok(Candidates::RunIds),
+            "experiment-ids" => Ok(Candidates::ExperimentIds),
+            "experiment-names" => Ok(Candidates::ExperimentNames),
+            _ => Err(format!(
+                "'{}' is not one of run-ids, experiment-ids, experiment-names",
+                value
+            )),
+        }
+    }
+}
+
+/// Write the completion script for `shell`. With `dynamic`, the script also completes experiment names and IDs and
+/// recent run IDs by calling the hidden `complete` command.
+pub fn generate<W: Write>(shell: Shell, dynamic: bool, output: &mut W) -> Result<(), CliError> {
+    let bin_name = "mlflow-api";
+    if !dynamic {
+        crate::Opt::clap().gen_completions_to(bin_name, shell, output);
+        return Ok(());
+    }
+    match shell {
+        Shell::Bash => {
+            crate::Opt::clap().gen_completions_to(bin_name, Shell::Bash, output);
+            write!(output, "{}", bash_dynamic())?;
+        }
+        Shell::Zsh => {
+            // zsh runs the bash completion through its compatibility layer, to share the dynamic part
+            writeln!(output, "autoload -U +X bashcompinit && bashcompinit")?;
+            crate::Opt::clap().gen_completions_to(bin_name, Shell::Bash, output);
+            write!(output, "{}", bash_dynamic())?;
+        }
+        Shell::Fish => {
+            crate::Opt::clap().gen_completions_to(bin_name, Shell::Fish, output);
+            write!(output, "{}", fish_dynamic())?;
+        }
+        shell => {
+            return Err(CliError::Usage(format!(
+                "Dynamic completion is not available for {}, only for bash, zsh and fish",
+                shell
+            )))
+        }
+    }
+    Ok(())
+}
+
+fn bash_dynamic() -> String {
+    format!(
+        r#"
+_mlflow_api_complete() {{
+    local uri=() i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${{COMP_WORDS[i]}}" in
+            -u|--tracking-uri) uri=(--tracking-uri "${{COMP_WORDS[i+1]}}") ;;
+        esac
+    done
+    mlflow-api "${{uri[@]}}" complete "$1" "$2" 2>/dev/null
+}}
+
+_mlflow_api_dynamic() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    local top="" kind="" i
+    # the top-level command, after the global options and their values
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${{COMP_WORDS[i]}}" in
+            -u|--tracking-uri|-o|--output) ((i++)) ;;
+            -*) ;;
+            *) top="${{COMP_WORDS[i]}}"; break ;;
+        esac
+    done
+    case "$prev" in
+        --experiment-id) kind=experiment-ids ;;
+        --experiment-name) kind=experiment-names ;;
+        get-by-name) kind=experiment-names ;;
+        *)
+            if [[ "$top" == diff-runs && "$cur" != -* ]]; then
+                kind=run-ids
+            elif [[ " {experiment_commands} " == *" $top:$prev "* || " {experiment_commands} " == *" $prev "* ]]; then
+                kind=experiment-ids
+            elif [[ ("$top" == run || "$top" == tail) && " {run_commands} " == *" $prev "* ]]; then
+                kind=run-ids
+            fi
+            ;;
+    esac
+    if [[ -n "$kind" ]]; then
+        # one candidate per line, escaped so that names with spaces stay one word
+        local prefix="${{cur//\\/}}" candidate
+        prefix="${{prefix#[\"\']}}"
+        COMPREPLY=()
+        while IFS= read -r candidate; do
+            COMPREPLY+=("$(printf '%q' "$candidate")")
+        done < <(_mlflow_api_complete "$kind" "$prefix")
+    else
+        _mlflow-api
+    fi
+}}
+
+complete -F _mlflow_api_dynamic -o bashdefault -o default mlflow-api
+"#,
+        experiment_commands = EXPERIMENT_ID_COMMANDS,
+        run_commands = RUN_ID_COMMANDS,
+    )
+}
+
+fn fish_dynamic() -> String {
+    let mut script = String::from(
+        r#"
+function __mlflow_api_complete
+    set -l words (commandline -opc)
+    set -l uri
+    for i in (seq (count $words))
+        if contains -- $words[$i] -u --tracking-uri
+            set uri --tracking-uri $words[(math $i + 1)]
+        end
+    end
+    mlflow-api $uri complete $argv[1] (commandline -ct) --with-descriptions 2>/dev/null
+end
+
+function __mlflow_api_first_argument_of
+    set -l words (commandline -opc)
+    test (count $words) -ge 2; and contains -- $words[-1] $argv
+end
+
+complete -c mlflow-api -n "__fish_seen_subcommand_from diff-runs" -f -a "(__mlflow_api_complete run-ids)"
+complete -c mlflow-api -l experiment-id -f -r -a "(__mlflow_api_complete experiment-ids)"
+complete -c mlflow-api -l experiment-name -f -r -a "(__mlflow_api_complete experiment-names)"
+complete -c mlflow-api -n "__mlflow_api_first_argument_of get-by-name" -f -a "(__mlflow_api_complete experiment-names)"
+"#,
+    );
+    script.push_str(&format!(
+        "complete -c mlflow-api -n \"__fish_seen_subcommand_from run tail; and __mlflow_api_first_argument_of {}\" \
+         -f -a \"(__mlflow_api_complete run-ids)\"\n",
+        RUN_ID_COMMANDS
+    ));
+    for command in EXPERIMENT_ID_COMMANDS.split_whitespace() {
+        let (condition, command) = match command.find(':') {
+            Some(index) => (
+                format!("__fish_seen_subcommand_from {}; and ", &command[..index]),
+                &command[index + 1..],
+            ),
+            None => (String::new(), command),
+        };
+        script.push_str(&format!(
+            "complete -c mlflow-api -n \"{}__mlflow_api_first_argument_of {}\" -f -a \"(__mlflow_api_complete experiment-ids)\"\n",
+            condition, command
+        ));
+    }
+    script
+}
+
+/// Print the candidates starting with `prefix`, one per line, with a description after a tab if `descriptions`.
+pub fn complete<W: Write>(
+    store: &dyn TrackingStore,
+    candidates: Candidates,
+    prefix: &str,
+    descriptions: bool,
+    output: &mut W,
+) -> Result<(), CliError> {
+    let experiments = store.list_experiments(Some(ViewType::ActiveOnly))?;
+    let candidates: Vec<(String, String)> = match candidates {
+        Candidates::ExperimentIds => experiments
+            .into_iter()
+            .map(|experiment| (experiment.experiment_id, experiment.name))
+            .collect(),
+        Candidates::ExperimentNames => experiments
+            .into_iter()
+            .map(|experiment| (experiment.name, String::new()))
+            .collect(),
+        Candidates::RunIds => {
+            let experiment_ids: Vec<&str> = experiments
+                .iter()
+                .map(|experiment| experiment.experiment_id.as_str())
+                .collect();
+            let (runs, _) = store.search_runs(
+                &experiment_ids,
+                None,
+                Some(ViewType::ActiveOnly),
+                Some(RECENT_RUNS),
+                Some(&["attributes.start_time DESC"]),
+                None,
+            )?;
+            runs.iter()
+                .map(|run| {
+                    (
+                        run.info.run_id.clone(),
+                        run.run_name().unwrap_or_default().to_string(),
+                    )
+                })
+                .collect()
+        }
+    };
+    for (candidate, description) in candidates {
+        if !candidate.starts_with(prefix) {
+            continue;
+        }
+        if descriptions && !description.is_empty() {
+            writeln!(output, "{}\t{}", candidate, description)?;
+        } else {
+            writeln!(output, "{}", candidate)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use mlflow_api::MemoryStore;
+    use spectral::prelude::*;
+
+    use super::{complete, Candidates};
+
+    #[test]
+    fn can_complete_experiments_and_runs() {
+        let store = MemoryStore::new();
+        let experiment_id = store.create_experiment("search", None).unwrap();
+        let run = store
+            .create_run(&experiment_id, None, None, Some("baseline"))
+            .unwrap();
+
+        let mut output = vec![];
+        complete(&store, Candidates::ExperimentNames, "s", false, &mut output).unwrap();
+        assert_that!(String::from_utf8(output).unwrap()).is_equal_to("search\n".to_string());
+
+        let mut output = vec![];
+        complete(&store, Candidates::RunIds, "", true, &mut output).unwrap();
+        assert_that!(String::from_utf8(output).unwrap())
+            .is_equal_to(format!("{}\tbaseline\n", run.info.run_id));
+    }
+}
@@ -0,0 +1,122 @@
+//! Errors of the CLI and their exit codes.
+
+use mlflow_api::errors::{ClientError, ErrorCode};
+
+/// The command succeeded.
+pub const EXIT_SUCCESS: i32 = 0;
+/// Any error not covered by a more specific code, like an invalid archive or an unreadable file.
+pub const EXIT_FAILURE: i32 = 1;
+/// The command line is not valid.
+pub const EXIT_USAGE: i32 = 2;
+/// The tracking store answered that the experiment, run or tag does not exist.
+pub const EXIT_NOT_FOUND: i32 = 3;
+/// The tracking store rejected the request, for an invalid or already existing value for example.
+pub const EXIT_API_ERROR: i32 = 4;
+/// The tracking server could not be reached, or its answer could not be read.
+pub const EXIT_QUERY_ERROR: i32 = 5;
+/// The local tracking store could not be read or written.
+pub const EXIT_STORE_ERROR: i32 = 6;
+
+/// Help text listing the exit codes.
+pub const EXIT_CODES_HELP: &str = "EXIT CODES:
+    0    success
+    1    other errors
+    2    invalid command line
+    3    experiment, run or tag not found
+    4    request rejected by the tracking store
+    5    tracking server unreachable
+    6    local tracking store error";
+
+/// An error of a CLI command.
+#[derive(Debug)]
+pub enum CliError {
+    /// Invalid command line.
+    Usage(String),
+    /// Error answered by the tracking store, with its error code.
+    Api { error_code: String, message: String },
+    /// The tracking server could not be reached.
+    Query(String),
+    /// The local tracking store could not be read or written.
+    Store(String),
+    /// Any other error.
+    Other(Box<dyn std::error::Error>),
+}
+
+impl CliError {
+    /// Exit code of the process for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => EXIT_USAGE,
+            CliError::Api { error_code, .. } if error_code == "RESOURCE_DOES_NOT_EXIST" => {
+                EXIT_NOT_FOUND
+            }
+            CliError::Api { .. } => EXIT_API_ERROR,
+            CliError::Query(_) => EXIT_QUERY_ERROR,
+            CliError::Store(_) => EXIT_STORE_ERROR,
+            CliError::Other(_) => EXIT_FAILURE,
+        }
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Usage(reason) => write!(f, "{}", reason),
+            CliError::Api {
+                error_code,
+                message,
+            } => write!(f, "{}: {}", error_code, message),
+            CliError::Query(reason) => write!(f, "Tracking server unreachable: {}", reason),
+            CliError::Store(reason) => write!(f, "Tracking store error: {}", reason),
+            CliError::Other(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<E: ErrorCode + serde::Serialize> From<ClientError<E>> for CliError {
+    fn from(error: ClientError<E>) -> CliError {
+        match error {
+            ClientError::ApiError {
+                error_code,
+                message,
+            } => CliError::Api {
+                error_code: serde_json::to_value(&error_code)
+                    .ok()
+                    .and_then(|code| code.as_str().map(str::to_string))
+                    .unwrap_or_default(),
+                message,
+            },
+            ClientError::QueryError(error) => CliError::Query(error.to_string()),
+            ClientError::StoreError(error) => CliError::Store(error.to_string()),
+        }
+    }
+}
+
+macro_rules! other_error {
+    ($($error:ty),*) => {
+        $(
+            impl From<$error> for CliError {
+                fn from(error: $error) -> CliError {
+                    CliError::Other(Box::new(error))
+                }
+            }
+        )*
+    };
+}
+
+other_error!(
+    std::io::Error,
+    serde_json::Error,
+    serde_yaml::Error,
+    csv::Error,
+    mlflow_api::errors::ArchiveError,
+    mlflow_api::errors::ArtifactError,
+    mlflow_api::errors::GcError,
+    mlflow_api::errors::SpoolError
+);
+
+impl From<mlflow_api::errors::SetupError> for CliError {
+    fn from(error: mlflow_api::errors::SetupError) -> CliError {
+        CliError::Usage(error.to_string())
+    }
+}
@@ -0,0 +1,252 @@
+//! Tracking of an arbitrary process as an MLflow run.
+
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use mlflow_api::artifacts::get_artifact_repository;
+use mlflow_api::store::TrackingStore;
+use mlflow_api::{RunStatus, RunTag};
+
+use crate::error::CliError;
+
+/// Tag with the command line of the process.
+pub const COMMAND_TAG: &str = "mlflow.exec.command";
+/// Tag with the exit code of the process, absent when it was killed by a signal.
+pub const EXIT_CODE_TAG: &str = "mlflow.exec.exitCode";
+/// Tag with the signal that killed the process.
+pub const SIGNAL_TAG: &str = "mlflow.exec.signal";
+/// Tag with the duration of the process, in milliseconds.
+pub const DURATION_TAG: &str = "mlflow.exec.durationMs";
+
+/// Where to create the run tracking the process.
+#[derive(Debug, Default)]
+pub struct RunTarget {
+    /// ID of the experiment of the run.
+    pub experiment_id: Option<String>,
+    /// Name of the experiment of the run, created if it doesn't exist.
+    pub experiment_name: Option<String>,
+    /// Name of the run.
+    pub run_name: Option<String>,
+}
+
+/// Run `command` as an MLflow run: the run ID and tracking URI are exported to the process, its standard output and
+/// error are copied to `stdout.txt` and `stderr.txt` artifacts, and the run status follows its exit status. Returns the
+/// exit code to exit with, the one of the process or 128 plus the signal that killed it.
+pub fn exec(
+    store: &dyn TrackingStore,
+    tracking_uri: &str,
+    target: RunTarget,
+    command: &[OsString],
+) -> Result<i32, CliError> {
+    let experiment_id = resolve_experiment(store, &target)?;
+    let command_line = command
+        .iter()
+        .map(|arg| quote(&arg.to_string_lossy()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut tags = vec![
+        RunTag {
+            key: "mlflow.user".to_string(),
+            value: whoami::username(),
+        },
+        RunTag {
+            key: "mlflow.source.name".to_string(),
+            value: command[0].to_string_lossy().to_string(),
+        },
+        RunTag {
+            key: "mlflow.source.type".to_string(),
+            value: "LOCAL".to_string(),
+        },
+        RunTag {
+            key: COMMAND_TAG.to_string(),
+            value: command_line,
+        },
+    ];
+    if let Some(run_name) = target.run_name.as_ref() {
+        tags.push(RunTag {
+            key: "mlflow.runName".to_string(),
+            value: run_name.clone(),
+        });
+    }
+    let run = store.create_run(
+        &experiment_id,
+        Some(crate::now()),
+        Some(tags),
+        target.run_name.as_deref(),
+    )?;
+    let run_id = run.info.run_id.clone();
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    {
+        // the terminal sends Ctrl-C to the process too, stay alive to record how it ended
+        let interrupted = interrupted.clone();
+        let _ = ctrlc::set_handler(move || interrupted.store(true, Ordering::SeqCst));
+    }
+
+    let logs = std::env::temp_dir().join(format!("mlflow-exec-{}", run_id));
+    std::fs::create_dir_all(&logs)?;
+    let started = Instant::now();
+    let status = run_process(command, &run_id, &experiment_id, tracking_uri, &logs);
+    let duration = started.elapsed().as_millis() as u64;
+
+    let status = match status {
+        Ok(status) => status,
+        Err(error) => {
+            store.update_run(&run_id, RunStatus::Failed, Some(crate::now()), None)?;
+            let _ = std::fs::remove_dir_all(&logs);
+            return Err(error.into());
+        }
+    };
+    let (run_status, exit_code) = match (status.code(), signal(&status)) {
+        (Some(0), _) if !interrupted.load(Ordering::SeqCst) => (RunStatus::Finished, 0),
+        (Some(code), _) if !interrupted.load(Ordering::SeqCst) => (RunStatus::Failed, code),
+        (Some(code), _) => (RunStatus::Killed, code),
+        (None, signal) => (RunStatus::Killed, 128 + signal.unwrap_or_default()),
+    };
+
+    let mut tags = vec![RunTag {
+        key: DURATION_TAG.to_string(),
+        value: duration.to_string(),
+    }];
+    match (status.code(), signal(&status)) {
+        (Some(code), _) => tags.push(RunTag {
+            key: EXIT_CODE_TAG.to_string(),
+            value: code.to_string(),
+        }),
+        (None, Some(signal)) => tags.push(RunTag {
+            key: SIGNAL_TAG.to_string(),
+            value: signal.to_string(),
+        }),
+        (None, None) => (),
+    }
+    let tags: Vec<&RunTag> = tags.iter().collect();
+    store.log_batch(&run_id, None, None, Some(&tags))?;
+
+    let artifacts = get_artifact_repository(&run.info.artifact_uri, tracking_uri)
+        .and_then(|repository| repository.log_artifacts(&logs, None));
+    let _ = std::fs::remove_dir_all(&logs);
+    store.update_run(&run_id, run_status, Some(crate::now()), None)?;
+    artifacts?;
+
+    Ok(exit_code)
+}
+
+/// Experiment of the run: the one given on the command line, or the one from `MLFLOW_EXPERIMENT_NAME` or
+/// `MLFLOW_EXPERIMENT_ID`, or the `Default` experiment, like `MLflowClient::start_run`.
+fn resolve_experiment(store: &dyn TrackingStore, target: &RunTarget) -> Result<String, CliError> {
+    if let Some(experiment_id) = target.experiment_id.as_ref() {
+        return Ok(store.get_experiment(experiment_id)?.experiment_id);
+    }
+    let experiment_name = target
+        .experiment_name
+        .clone()
+        .or_else(|| std::env::var("MLFLOW_EXPERIMENT_NAME").ok());
+    if let Some(experiment_name) = experiment_name {
+        match store.get_experiment_by_name(&experiment_name) {
+            Ok(experiment) => Ok(experiment.experiment_id),
+            Err(_) => Ok(store.create_experiment(&experiment_name, None)?),
+        }
+    } else if let Ok(experiment_id) = std::env::var("MLFLOW_EXPERIMENT_ID") {
+        Ok(store.get_experiment(&experiment_id)?.experiment_id)
+    } else {
+        Ok(store.get_experiment_by_name("Default")?.experiment_id)
+    }
+}
+
+/// Run the process, copying its standard output and error both to ours and to files in `logs`.
+fn run_process(
+    command: &[OsString],
+    run_id: &str,
+    experiment_id: &str,
+    tracking_uri: &str,
+    logs: &Path,
+) -> std::io::Result<ExitStatus> {
+    let mut child = Command::new(&command[0])
+        .args(&command[1..])
+        .env("MLFLOW_RUN_ID", run_id)
+        .env("MLFLOW_EXPERIMENT_ID", experiment_id)
+        .env("MLFLOW_TRACKING_URI", tracking_uri)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = tee(
+        child.stdout.take().expect("stdout is piped"),
+        std::io::stdout(),
+        logs.join("stdout.txt"),
+    )?;
+    let stderr = tee(
+        child.stderr.take().expect("stderr is piped"),
+        std::io::stderr(),
+        logs.join("stderr.txt"),
+    )?;
+    let status = child.wait()?;
+    for copy in [stdout, stderr] {
+        copy.join().expect("copy thread panicked")?;
+    }
+    Ok(status)
+}
+
+/// Copy `input` to `output` and to the file at `path` in a background thread.
+fn tee<R: Read + Send + 'static, W: Write + Send + 'static>(
+    mut input: R,
+    mut output: W,
+    path: PathBuf,
+) -> std::io::Result<std::thread::JoinHandle<std::io::Result<()>>> {
+    let mut file = File::create(path)?;
+    Ok(std::thread::spawn(move || {
+        let mut buffer = [0; 8192];
+        loop {
+            let read = input.read(&mut buffer)?;
+            if read == 0 {
+                return file.flush();
+            }
+            // the output may be closed, like when piped to `head`, keep the log complete anyway
+            let _ = output
+                .write_all(&buffer[..read])
+                .and_then(|_| output.flush());
+            file.write_all(&buffer[..read])?;
+        }
+    }))
+}
+
+#[cfg(unix)]
+fn signal(status: &ExitStatus) -> Option<i32> {
+    std::os::unix::process::ExitStatusExt::signal(status)
+}
+
+#[cfg(not(unix))]
+fn signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Quote an argument for a POSIX shell if needed.
+fn quote(arg: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::quote;
+    use spectral::prelude::*;
+
+    #[test]
+    fn can_quote_arguments() {
+        assert_that!(quote("train.py")).is_equal_to("train.py".to_string());
+        assert_that!(quote("--lr=0.1")).is_equal_to("--lr=0.1".to_string());
+        assert_that!(quote("two words")).is_equal_to("'two words'".to_string());
+        assert_that!(quote("it's")).is_equal_to(r"'it'\''s'".to_string());
+        assert_that!(quote("")).is_equal_to("''".to_string());
+    }
+}
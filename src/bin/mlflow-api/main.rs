@@ -0,0 +1,960 @@
+//! Command line client for MLflow tracking stores.
+
+mod bulk;
+mod completions;
+mod error;
+mod exec;
+mod output;
+mod tail;
+
+use std::path::PathBuf;
+
+use mlflow_api::store::{now, TrackingStore};
+use mlflow_api::{DatasetInput, Metric, Param, RunStatus, RunTag, ViewType};
+use structopt::{clap::AppSettings, StructOpt};
+
+use error::CliError;
+use output::Output;
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "mlflow-api",
+    about = "Command line client for MLflow tracking servers and stores",
+    global_settings(&[AppSettings::ColoredHelp, AppSettings::VersionlessSubcommands]),
+    after_help = error::EXIT_CODES_HELP
+)]
+struct Opt {
+    /// URI of the tracking store: a tracking server for http(s)://, a local store for file: URIs and paths
+    #[structopt(
+        short = "u",
+        long,
+        env = "MLFLOW_TRACKING_URI",
+        default_value = "mlruns"
+    )]
+    tracking_uri: String,
+    /// Format of the results: table, json, yaml or csv
+    #[structopt(short, long, global = true, default_value = "table")]
+    output: Output,
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    /// Manage experiments
+    Experiment(ExperimentCommand),
+    /// Manage runs and log to them
+    Run(RunCommand),
+    /// Tag, delete, restore or update all the runs matching a search
+    Bulk(BulkCommand),
+    /// Compare runs against the first one
+    DiffRuns {
+        /// IDs of the runs to compare, the baseline first
+        #[structopt(required = true, min_values = 2)]
+        run_ids: Vec<String>,
+    },
+    /// Export an experiment and its runs to a directory or a .tar.gz archive
+    ExportExperiment {
+        /// ID of the experiment to export
+        experiment_id: String,
+        /// Directory or .tar.gz file to export to
+        #[structopt(parse(from_os_str))]
+        destination: PathBuf,
+        /// Also export the artifacts of the runs
+        #[structopt(long)]
+        artifacts: bool,
+    },
+    /// Import an experiment and its runs from a directory or a .tar.gz archive
+    ImportExperiment {
+        /// Directory or .tar.gz file to import from
+        #[structopt(parse(from_os_str))]
+        source: PathBuf,
+        /// Name of the experiment to import into, the exported one if not set
+        #[structopt(long)]
+        experiment_name: Option<String>,
+        /// Also import the artifacts of the runs
+        #[structopt(long)]
+        artifacts: bool,
+    },
+    /// Run a command as an MLflow run, with its output as artifacts and its exit status as run status
+    ///
+    /// The run is created in the experiment given by the options, or by the MLFLOW_EXPERIMENT_NAME or
+    /// MLFLOW_EXPERIMENT_ID variables, or in the Default experiment. MLFLOW_RUN_ID and MLFLOW_TRACKING_URI are set
+    /// for the command, which exits with the code of the command.
+    Exec {
+        /// ID of the experiment of the run
+        #[structopt(long, conflicts_with = "experiment-name")]
+        experiment_id: Option<String>,
+        /// Name of the experiment of the run, created if it doesn't exist
+        #[structopt(long)]
+        experiment_name: Option<String>,
+        /// Name of the run
+        #[structopt(long)]
+        run_name: Option<String>,
+        /// Command to run, with its arguments
+        #[structopt(required = true, last = true, parse(from_os_str))]
+        command: Vec<std::ffi::OsString>,
+    },
+    /// Delete the artifacts and metadata of deleted experiments and runs
+    ///
+    /// Only reports what would be collected, unless --confirm is given. The metadata is only removed from local
+    /// stores, a tracking server keeps it.
+    Gc {
+        /// Only collect experiments and runs deleted for longer than this, like 30d or 1d12h, in d, h, m and s
+        #[structopt(long, parse(try_from_str = parse_duration))]
+        older_than: Option<std::time::Duration>,
+        /// Collect the experiments and runs instead of reporting them
+        #[structopt(long)]
+        confirm: bool,
+    },
+    /// Kill the running runs without activity for longer than a threshold, left running by crashed jobs
+    ///
+    /// The last activity of a run is the latest of its start time, its latest metrics and its mlflow.heartbeat tag.
+    /// Only reports the stale runs, unless --confirm is given.
+    KillStale {
+        /// ID of an experiment to look for stale runs in, can be repeated
+        #[structopt(long = "experiment-id", required = true, number_of_values = 1)]
+        experiment_ids: Vec<String>,
+        /// Kill runs without activity for longer than this, like 2h or 1d12h, in d, h, m and s
+        #[structopt(long, parse(try_from_str = parse_duration))]
+        older_than: std::time::Duration,
+        /// Kill the runs instead of reporting them
+        #[structopt(long)]
+        confirm: bool,
+    },
+    /// Print the best runs by a metric, with the URI of their artifacts
+    ///
+    /// Runs with the same value are ordered by start time, the most recent first.
+    BestRuns {
+        /// ID of an experiment to select runs from, can be repeated
+        #[structopt(long = "experiment-id", required = true, number_of_values = 1)]
+        experiment_ids: Vec<String>,
+        /// Metric to sort the runs by, lowest first unless --maximize is given
+        #[structopt(long)]
+        metric: String,
+        /// Select the runs with the highest metric
+        #[structopt(long)]
+        maximize: bool,
+        /// Number of runs to print
+        #[structopt(long, default_value = "1")]
+        top: usize,
+        /// Status of the runs to consider, can be repeated, finished if not set
+        #[structopt(long = "status", parse(try_from_str = parse_status), number_of_values = 1)]
+        statuses: Vec<RunStatus>,
+        /// Tag the runs must have, as KEY=VALUE, can be repeated
+        #[structopt(long = "tag", parse(try_from_str = parse_tag), number_of_values = 1)]
+        tags: Vec<RunTag>,
+        /// Filter, like "params.model = 'resnet'"
+        #[structopt(long)]
+        filter: Option<String>,
+        /// Path of the artifacts to point to, like model
+        #[structopt(long)]
+        artifact_path: Option<String>,
+        /// Prefer the oldest run among runs with the same value
+        #[structopt(long)]
+        prefer_oldest: bool,
+    },
+    /// Print the metric points of a run as they are logged, until the run ends
+    ///
+    /// Exits with 0 when the run finished, 1 when it failed or was killed.
+    Tail {
+        /// ID of the run
+        run_id: String,
+        /// Metric to follow, can be repeated, all the metrics of the run if not set
+        #[structopt(long = "metric")]
+        metrics: Vec<String>,
+        /// Seconds between two polls
+        #[structopt(long, default_value = "2")]
+        interval: f64,
+        /// Print a sparkline of the latest values of a metric after new points, with the table output
+        #[structopt(long)]
+        sparkline: bool,
+    },
+    /// Print the completion script for a shell: bash, zsh, fish, powershell or elvish
+    ///
+    /// With bash, add `source <(mlflow-api completions bash --dynamic)` to ~/.bashrc. With zsh, add the same line to
+    /// ~/.zshrc. With fish, run `mlflow-api completions fish --dynamic > ~/.config/fish/completions/mlflow-api.fish`.
+    Completions {
+        /// Shell to complete in
+        #[structopt(possible_values = &structopt::clap::Shell::variants(), case_insensitive = true)]
+        shell: structopt::clap::Shell,
+        /// Also complete experiment names and IDs and recent run IDs from the tracking store, with bash, zsh and fish
+        #[structopt(long)]
+        dynamic: bool,
+    },
+    /// Print the candidates for dynamic completion
+    #[structopt(name = "complete", setting = AppSettings::Hidden)]
+    Complete {
+        /// What to complete: run-ids, experiment-ids or experiment-names
+        candidates: completions::Candidates,
+        /// Start of the word to complete
+        #[structopt(default_value = "")]
+        prefix: String,
+        /// Follow each candidate with a tab and a description
+        #[structopt(long)]
+        with_descriptions: bool,
+    },
+    /// Replay logging calls spooled while the tracking server was unreachable
+    Sync {
+        /// Path to the spool journal
+        #[structopt(parse(from_os_str))]
+        journal: PathBuf,
+    },
+}
+
+#[derive(StructOpt)]
+struct BulkSelection {
+    /// ID of an experiment to search runs in, can be repeated
+    #[structopt(long = "experiment-id", required = true, number_of_values = 1)]
+    experiment_ids: Vec<String>,
+    /// Filter, like "attributes.status = 'RUNNING'"
+    #[structopt(long)]
+    filter: Option<String>,
+    /// Only runs started longer ago than this, like 1d or 12h, in d, h, m and s
+    #[structopt(long, parse(try_from_str = parse_duration))]
+    started_before: Option<std::time::Duration>,
+    /// Number of concurrent requests
+    #[structopt(long, default_value = "8")]
+    concurrency: usize,
+    /// Apply the operation instead of listing the matching runs
+    #[structopt(long)]
+    confirm: bool,
+}
+
+#[derive(StructOpt)]
+enum BulkCommand {
+    /// Set a tag on the matching runs
+    SetTag {
+        #[structopt(flatten)]
+        selection: BulkSelection,
+        /// Key of the tag
+        key: String,
+        /// Value of the tag
+        value: String,
+    },
+    /// Delete the matching runs
+    Delete {
+        #[structopt(flatten)]
+        selection: BulkSelection,
+    },
+    /// Restore the matching deleted runs
+    Restore {
+        #[structopt(flatten)]
+        selection: BulkSelection,
+    },
+    /// Update the status of the matching runs, like marking stale running runs as killed
+    Update {
+        #[structopt(flatten)]
+        selection: BulkSelection,
+        /// New status: running, scheduled, finished, failed or killed
+        #[structopt(long, parse(try_from_str = parse_status))]
+        status: RunStatus,
+        /// End time in milliseconds since the epoch, now if not set and the status is not running or scheduled
+        #[structopt(long)]
+        end_time: Option<u64>,
+    },
+}
+
+#[derive(StructOpt)]
+enum ExperimentCommand {
+    /// Create an experiment
+    Create {
+        /// Name of the experiment
+        name: String,
+        /// Location where the artifacts of its runs are stored
+        #[structopt(long)]
+        artifact_location: Option<String>,
+    },
+    /// List experiments
+    List {
+        /// Experiments to list: active-only, deleted-only or all
+        #[structopt(long, parse(try_from_str = parse_view_type))]
+        view_type: Option<ViewType>,
+    },
+    /// Get an experiment
+    Get {
+        /// ID of the experiment
+        experiment_id: String,
+    },
+    /// Get an experiment by its name
+    GetByName {
+        /// Name of the experiment
+        name: String,
+    },
+    /// Delete an experiment and its runs
+    Delete {
+        /// ID of the experiment
+        experiment_id: String,
+    },
+    /// Restore a deleted experiment
+    Restore {
+        /// ID of the experiment
+        experiment_id: String,
+    },
+    /// Rename an experiment
+    Update {
+        /// ID of the experiment
+        experiment_id: String,
+        /// New name of the experiment
+        new_name: String,
+    },
+    /// Set a tag on an experiment
+    SetTag {
+        /// ID of the experiment
+        experiment_id: String,
+        /// Key of the tag
+        key: String,
+        /// Value of the tag
+        value: String,
+    },
+}
+
+#[derive(StructOpt)]
+enum RunCommand {
+    /// Create a run
+    Create {
+        /// ID of the experiment of the run
+        experiment_id: String,
+        /// Name of the run
+        #[structopt(long)]
+        run_name: Option<String>,
+        /// Start time in milliseconds since the epoch, now if not set
+        #[structopt(long)]
+        start_time: Option<u64>,
+        /// Tag of the run, as KEY=VALUE
+        #[structopt(long = "tag", parse(try_from_str = parse_tag))]
+        tags: Vec<RunTag>,
+    },
+    /// Get a run with its latest metrics, params and tags
+    Get {
+        /// ID of the run
+        run_id: String,
+    },
+    /// Search runs of experiments
+    Search {
+        /// IDs of the experiments to search in
+        #[structopt(required = true)]
+        experiment_ids: Vec<String>,
+        /// Filter, like "metrics.rmse < 1 and params.model = 'tree'"
+        #[structopt(long)]
+        filter: Option<String>,
+        /// Runs to search: active-only, deleted-only or all
+        #[structopt(long, parse(try_from_str = parse_view_type))]
+        view_type: Option<ViewType>,
+        /// Maximum number of runs
+        #[structopt(long)]
+        max_results: Option<u32>,
+        /// Ordering, like "metrics.rmse DESC", can be repeated
+        #[structopt(long)]
+        order_by: Vec<String>,
+        /// Token of the page to get, from a previous search
+        #[structopt(long)]
+        page_token: Option<String>,
+    },
+    /// Delete a run
+    Delete {
+        /// ID of the run
+        run_id: String,
+    },
+    /// Restore a deleted run
+    Restore {
+        /// ID of the run
+        run_id: String,
+    },
+    /// Update the status, end time or name of a run
+    Update {
+        /// ID of the run
+        run_id: String,
+        /// New status: running, scheduled, finished, failed or killed
+        #[structopt(long, parse(try_from_str = parse_status))]
+        status: RunStatus,
+        /// End time in milliseconds since the epoch
+        #[structopt(long)]
+        end_time: Option<u64>,
+        /// New name of the run
+        #[structopt(long)]
+        run_name: Option<String>,
+    },
+    /// Set a tag on a run
+    SetTag {
+        /// ID of the run
+        run_id: String,
+        /// Key of the tag
+        key: String,
+        /// Value of the tag
+        value: String,
+    },
+    /// Delete a tag of a run
+    DeleteTag {
+        /// ID of the run
+        run_id: String,
+        /// Key of the tag
+        key: String,
+    },
+    /// Log a param of a run
+    LogParam {
+        /// ID of the run
+        run_id: String,
+        /// Key of the param
+        key: String,
+        /// Value of the param
+        value: String,
+    },
+    /// Log a metric value of a run
+    LogMetric {
+        /// ID of the run
+        run_id: String,
+        /// Key of the metric
+        key: String,
+        /// Value of the metric
+        value: f32,
+        /// Timestamp in milliseconds since the epoch, now if not set
+        #[structopt(long)]
+        timestamp: Option<u64>,
+        /// Step of the value
+        #[structopt(long)]
+        step: Option<u64>,
+    },
+    /// Log metrics, params and tags of a run from a JSON file shaped like a LogBatch request
+    LogBatch {
+        /// ID of the run
+        run_id: String,
+        /// JSON file with "metrics", "params" and "tags" lists, - for the standard input
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Get all the values of a metric of a run
+    History {
+        /// ID of the run
+        run_id: String,
+        /// Key of the metric
+        key: String,
+    },
+    /// Log the datasets used by a run from a JSON file with a list of dataset inputs
+    LogInputs {
+        /// ID of the run
+        run_id: String,
+        /// JSON file with a list of dataset inputs, - for the standard input
+        #[structopt(parse(from_os_str))]
+        file: PathBuf,
+    },
+    /// Get the values of a metric for several runs, sampled over an interval of steps
+    HistoryBulk {
+        /// Key of the metric
+        key: String,
+        /// IDs of the runs
+        #[structopt(required = true)]
+        run_ids: Vec<String>,
+        /// First step of the interval
+        #[structopt(long)]
+        start_step: Option<u64>,
+        /// Last step of the interval
+        #[structopt(long)]
+        end_step: Option<u64>,
+        /// Maximum number of values per run
+        #[structopt(long)]
+        max_results: Option<u32>,
+    },
+    /// List the artifacts of a run
+    ListArtifacts {
+        /// ID of the run
+        run_id: String,
+        /// Directory to list, the root of the artifacts if not set
+        path: Option<String>,
+    },
+}
+
+/// Content of a `log-batch` file.
+#[derive(serde::Deserialize)]
+struct Batch {
+    #[serde(default)]
+    metrics: Vec<Metric>,
+    #[serde(default)]
+    params: Vec<Param>,
+    #[serde(default)]
+    tags: Vec<RunTag>,
+}
+
+fn parse_enum<T: serde::de::DeserializeOwned>(value: &str, values: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(
+        value.to_uppercase().replace('-', "_"),
+    ))
+    .map_err(|_| format!("'{}' is not one of {}", value, values))
+}
+
+fn parse_view_type(value: &str) -> Result<ViewType, String> {
+    parse_enum(value, "active-only, deleted-only, all")
+}
+
+fn parse_status(value: &str) -> Result<RunStatus, String> {
+    parse_enum(value, "running, scheduled, finished, failed, killed")
+}
+
+fn parse_duration(value: &str) -> Result<std::time::Duration, String> {
+    let invalid = || format!("'{}' is not a duration like 30d or 1d12h", value);
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'd' => 86400,
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        seconds += number.parse::<u64>().map_err(|_| invalid())? * unit;
+        number.clear();
+    }
+    if !number.is_empty() || value.is_empty() {
+        return Err(invalid());
+    }
+    Ok(std::time::Duration::from_secs(seconds))
+}
+
+fn parse_tag(value: &str) -> Result<RunTag, String> {
+    match value.find('=') {
+        Some(index) => Ok(RunTag {
+            key: value[..index].to_string(),
+            value: value[index + 1..].to_string(),
+        }),
+        None => Err(format!("'{}' is not KEY=VALUE", value)),
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(file: &std::path::Path) -> Result<T, CliError> {
+    if file.as_os_str() == "-" {
+        Ok(serde_json::from_reader(std::io::stdin())?)
+    } else {
+        Ok(serde_json::from_reader(std::fs::File::open(file)?)?)
+    }
+}
+
+/// All the values of a metric of a run, from every page of its history.
+fn metric_history(
+    store: &dyn TrackingStore,
+    run_id: &str,
+    key: &str,
+) -> Result<Vec<Metric>, CliError> {
+    let mut history = vec![];
+    let mut page_token = None;
+    loop {
+        let (page, next_page_token) =
+            store.get_metric_history(run_id, key, None, page_token.as_deref())?;
+        history.extend(page);
+        match next_page_token {
+            Some(token) if !token.is_empty() => page_token = Some(token),
+            _ => return Ok(history),
+        }
+    }
+}
+
+fn run_experiment_command(
+    store: &dyn TrackingStore,
+    command: ExperimentCommand,
+    output: Output,
+) -> Result<(), CliError> {
+    match command {
+        ExperimentCommand::Create {
+            name,
+            artifact_location,
+        } => {
+            let experiment_id = store.create_experiment(&name, artifact_location.as_deref())?;
+            output.print(&serde_json::json!({ "experiment_id": experiment_id }))
+        }
+        ExperimentCommand::List { view_type } => output.print(&store.list_experiments(view_type)?),
+        ExperimentCommand::Get { experiment_id } => {
+            output.print(&store.get_experiment(&experiment_id)?)
+        }
+        ExperimentCommand::GetByName { name } => {
+            output.print(&store.get_experiment_by_name(&name)?)
+        }
+        ExperimentCommand::Delete { experiment_id } => Ok(store.delete_experiment(&experiment_id)?),
+        ExperimentCommand::Restore { experiment_id } => {
+            Ok(store.restore_experiment(&experiment_id)?)
+        }
+        ExperimentCommand::Update {
+            experiment_id,
+            new_name,
+        } => Ok(store.update_experiment(&experiment_id, &new_name)?),
+        ExperimentCommand::SetTag {
+            experiment_id,
+            key,
+            value,
+        } => Ok(store.set_experiment_tag(&experiment_id, &key, &value)?),
+    }
+}
+
+fn run_run_command(
+    store: &dyn TrackingStore,
+    command: RunCommand,
+    output: Output,
+) -> Result<(), CliError> {
+    match command {
+        RunCommand::Create {
+            experiment_id,
+            run_name,
+            start_time,
+            tags,
+        } => output.print(&store.create_run(
+            &experiment_id,
+            Some(start_time.unwrap_or_else(now)),
+            Some(tags),
+            run_name.as_deref(),
+        )?),
+        RunCommand::Get { run_id } => output.print(&store.get_run(&run_id)?),
+        RunCommand::Search {
+            experiment_ids,
+            filter,
+            view_type,
+            max_results,
+            order_by,
+            page_token,
+        } => {
+            let experiment_ids: Vec<&str> = experiment_ids.iter().map(String::as_str).collect();
+            let order_by: Vec<&str> = order_by.iter().map(String::as_str).collect();
+            let (runs, next_page_token) = store.search_runs(
+                &experiment_ids,
+                filter.as_deref(),
+                view_type,
+                max_results,
+                Some(&order_by)
+                    .filter(|order_by| !order_by.is_empty())
+                    .map(Vec::as_slice),
+                page_token.as_deref(),
+            )?;
+            output.print(&serde_json::json!({ "runs": runs, "next_page_token": next_page_token }))
+        }
+        RunCommand::Delete { run_id } => Ok(store.delete_run(&run_id)?),
+        RunCommand::Restore { run_id } => Ok(store.restore_run(&run_id)?),
+        RunCommand::Update {
+            run_id,
+            status,
+            end_time,
+            run_name,
+        } => output.print(&store.update_run(&run_id, status, end_time, run_name.as_deref())?),
+        RunCommand::SetTag { run_id, key, value } => Ok(store.set_run_tag(&run_id, &key, &value)?),
+        RunCommand::DeleteTag { run_id, key } => Ok(store.delete_run_tag(&run_id, &key)?),
+        RunCommand::LogParam { run_id, key, value } => Ok(store.log_param(&run_id, &key, &value)?),
+        RunCommand::LogMetric {
+            run_id,
+            key,
+            value,
+            timestamp,
+            step,
+        } => Ok(store.log_metric(&run_id, &key, value, timestamp.unwrap_or_else(now), step)?),
+        RunCommand::LogBatch { run_id, file } => {
+            let batch: Batch = read_json(&file)?;
+            let metrics: Vec<&Metric> = batch.metrics.iter().collect();
+            let params: Vec<&Param> = batch.params.iter().collect();
+            let tags: Vec<&RunTag> = batch.tags.iter().collect();
+            Ok(store.log_batch(&run_id, Some(&metrics), Some(&params), Some(&tags))?)
+        }
+        RunCommand::History { run_id, key } => output.print(&metric_history(store, &run_id, &key)?),
+        RunCommand::LogInputs { run_id, file } => {
+            let datasets: Vec<DatasetInput> = read_json(&file)?;
+            Ok(store.log_inputs(&run_id, Some(&datasets))?)
+        }
+        RunCommand::HistoryBulk {
+            key,
+            run_ids,
+            start_step,
+            end_step,
+            max_results,
+        } => {
+            let run_ids: Vec<&str> = run_ids.iter().map(String::as_str).collect();
+            output.print(&store.get_metric_history_bulk_interval(
+                &run_ids,
+                &key,
+                start_step,
+                end_step,
+                max_results,
+            )?)
+        }
+        RunCommand::ListArtifacts { run_id, path } => {
+            let (root_uri, files) = store.list_artifacts(&run_id, path.as_deref())?;
+            output.print(&serde_json::json!({ "root_uri": root_uri, "files": files }))
+        }
+    }
+}
+
+fn run_bulk_command(
+    store: &dyn TrackingStore,
+    command: BulkCommand,
+    output: Output,
+) -> Result<(), CliError> {
+    let (selection, view_type, action) = match command {
+        BulkCommand::SetTag {
+            selection,
+            key,
+            value,
+        } => (
+            selection,
+            ViewType::ActiveOnly,
+            bulk::Action::SetTag { key, value },
+        ),
+        BulkCommand::Delete { selection } => {
+            (selection, ViewType::ActiveOnly, bulk::Action::Delete)
+        }
+        BulkCommand::Restore { selection } => {
+            (selection, ViewType::DeletedOnly, bulk::Action::Restore)
+        }
+        BulkCommand::Update {
+            selection,
+            status,
+            end_time,
+        } => (
+            selection,
+            ViewType::ActiveOnly,
+            bulk::Action::Update { status, end_time },
+        ),
+    };
+    let started_before = selection.started_before.map(|started_before| {
+        format!(
+            "attributes.start_time < {}",
+            now().saturating_sub(started_before.as_millis() as u64)
+        )
+    });
+    let filter = match (selection.filter, started_before) {
+        (Some(filter), Some(started_before)) => Some(format!("{} and {}", filter, started_before)),
+        (filter, started_before) => filter.or(started_before),
+    };
+    let runs = bulk::find_runs(
+        store,
+        &bulk::Selection {
+            experiment_ids: selection.experiment_ids,
+            filter,
+            view_type,
+        },
+    )?;
+
+    if !selection.confirm {
+        let matched: Vec<bulk::MatchedRun> = runs.iter().map(bulk::MatchedRun::from).collect();
+        output.print(&matched)?;
+        eprintln!(
+            "{} matching runs, nothing was changed, run again with --confirm to apply",
+            runs.len()
+        );
+        return Ok(());
+    }
+    let report = bulk::apply(store, &runs, &action, selection.concurrency);
+    output.print(&report)?;
+    if report.failed.is_empty() {
+        Ok(())
+    } else {
+        eprintln!("{} of {} runs failed", report.failed.len(), report.matched);
+        std::process::exit(error::EXIT_FAILURE)
+    }
+}
+
+fn run(opt: Opt) -> Result<(), CliError> {
+    let output = opt.output;
+    if let Command::Completions { shell, dynamic } = opt.command {
+        return completions::generate(shell, dynamic, &mut std::io::stdout());
+    }
+    let tracking_uri = opt.tracking_uri;
+    let store = mlflow_api::store::open_store(&tracking_uri)?;
+    let store = &*store;
+    match opt.command {
+        Command::Experiment(command) => run_experiment_command(store, command, opt.output),
+        Command::Run(command) => run_run_command(store, command, opt.output),
+        Command::Bulk(command) => run_bulk_command(store, command, opt.output),
+        Command::DiffRuns { run_ids } => {
+            let runs = run_ids
+                .iter()
+                .map(|run_id| store.get_run(run_id))
+                .collect::<Result<Vec<_>, _>>()?;
+            let comparison = mlflow_api::compare::RunComparison::new(&runs);
+            match opt.output {
+                Output::Table => {
+                    print!("{}", comparison);
+                    Ok(())
+                }
+                output => output.print(&comparison),
+            }
+        }
+        Command::ExportExperiment {
+            experiment_id,
+            destination,
+            artifacts,
+        } => {
+            let mut exporter = mlflow_api::archive::Exporter::new(store);
+            if artifacts {
+                exporter = exporter.with_artifacts(&tracking_uri);
+            }
+            output.print(&exporter.export_experiment(&experiment_id, &destination)?)
+        }
+        Command::ImportExperiment {
+            source,
+            experiment_name,
+            artifacts,
+        } => {
+            let mut importer = mlflow_api::archive::Importer::new(store);
+            if artifacts {
+                importer = importer.with_artifacts(&tracking_uri);
+            }
+            output.print(&importer.import_experiment(&source, experiment_name.as_deref())?)
+        }
+        Command::Exec {
+            experiment_id,
+            experiment_name,
+            run_name,
+            command,
+        } => {
+            let target = exec::RunTarget {
+                experiment_id,
+                experiment_name,
+                run_name,
+            };
+            let code = exec::exec(store, &tracking_uri, target, &command)?;
+            std::process::exit(code)
+        }
+        Command::Gc {
+            older_than,
+            confirm,
+        } => {
+            let mut collector = mlflow_api::gc::GarbageCollector::new(store, &tracking_uri);
+            if let Some(older_than) = older_than {
+                collector = collector.older_than(older_than);
+            }
+            if confirm {
+                output.print(&collector.run()?)
+            } else {
+                output.print(&collector.plan()?)?;
+                eprintln!("Nothing was deleted, run again with --confirm to collect");
+                Ok(())
+            }
+        }
+        Command::BestRuns {
+            experiment_ids,
+            metric,
+            maximize,
+            top,
+            statuses,
+            tags,
+            filter,
+            artifact_path,
+            prefer_oldest,
+        } => {
+            let experiment_ids: Vec<&str> = experiment_ids.iter().map(String::as_str).collect();
+            let direction = if maximize {
+                mlflow_api::select::Direction::Maximize
+            } else {
+                mlflow_api::select::Direction::Minimize
+            };
+            let mut selector = mlflow_api::select::RunSelector::new(
+                store,
+                &tracking_uri,
+                &experiment_ids,
+                &metric,
+                direction,
+            );
+            if !statuses.is_empty() {
+                selector = selector.statuses(&statuses);
+            }
+            for tag in &tags {
+                selector = selector.require_tag(&tag.key, &tag.value);
+            }
+            if let Some(filter) = &filter {
+                selector = selector.filter(filter);
+            }
+            if let Some(artifact_path) = &artifact_path {
+                selector = selector.artifact_path(artifact_path);
+            }
+            if prefer_oldest {
+                selector = selector.prefer_oldest();
+            }
+            let rows: Vec<serde_json::Value> = selector
+                .top(top)?
+                .into_iter()
+                .map(|selected| {
+                    serde_json::json!({
+                        "run_id": selected.run.info.run_id,
+                        "run_name": selected.run.run_name(),
+                        "experiment_id": selected.run.info.experiment_id,
+                        "start_time": selected.run.info.start_time,
+                        metric.as_str(): selected.metric_value,
+                        "artifact_uri": selected.artifact_uri,
+                    })
+                })
+                .collect();
+            output.print(&rows)
+        }
+        Command::KillStale {
+            experiment_ids,
+            older_than,
+            confirm,
+        } => {
+            let experiment_ids: Vec<&str> = experiment_ids.iter().map(String::as_str).collect();
+            let detector =
+                mlflow_api::stale::StaleRunDetector::new(store, &experiment_ids, older_than);
+            let stale = detector.find()?;
+            if confirm {
+                let killed = detector.kill(&stale)?;
+                let killed: Vec<_> = stale
+                    .into_iter()
+                    .filter(|run| killed.contains(&run.run_id))
+                    .collect();
+                output.print(&killed)
+            } else {
+                output.print(&stale)?;
+                eprintln!("No run was killed, run again with --confirm to kill them");
+                Ok(())
+            }
+        }
+        Command::Tail {
+            run_id,
+            metrics,
+            interval,
+            sparkline,
+        } => {
+            let options = tail::TailOptions {
+                metrics,
+                interval: std::time::Duration::from_secs_f64(interval.max(0.)),
+                sparkline,
+                output,
+            };
+            match tail::tail(store, &run_id, &options)? {
+                RunStatus::Finished => Ok(()),
+                _ => std::process::exit(error::EXIT_FAILURE),
+            }
+        }
+        Command::Completions { .. } => unreachable!("completions don't need a store"),
+        Command::Complete {
+            candidates,
+            prefix,
+            with_descriptions,
+        } => completions::complete(
+            store,
+            candidates,
+            &prefix,
+            with_descriptions,
+            &mut std::io::stdout(),
+        ),
+        Command::Sync { journal } => output.print(&mlflow_api::spool::sync(&journal, store)?),
+    }
+}
+
+fn main() {
+    let opt = match Opt::clap().get_matches_safe() {
+        Ok(matches) => Opt::from_clap(&matches),
+        Err(error) => match error.kind {
+            structopt::clap::ErrorKind::HelpDisplayed
+            | structopt::clap::ErrorKind::VersionDisplayed => {
+                println!("{}", error.message);
+                std::process::exit(error::EXIT_SUCCESS)
+            }
+            _ => {
+                eprintln!("{}", error.message);
+                std::process::exit(error::EXIT_USAGE)
+            }
+        },
+    };
+    if let Err(error) = run(opt) {
+        eprintln!("error: {}", error);
+        std::process::exit(error.exit_code());
+    }
+}
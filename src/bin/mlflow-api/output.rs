@@ -0,0 +1,269 @@
+//! Rendering of command results as tables, JSON, YAML or CSV.
+
+use std::io::Write;
+use std::str::FromStr;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::CliError;
+
+/// Format of the results printed by commands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Output {
+    /// Aligned table, for humans.
+    Table,
+    /// Pretty printed JSON.
+    Json,
+    /// YAML document.
+    Yaml,
+    /// CSV with a header line.
+    Csv,
+}
+
+impl FromStr for Output {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Output, String> {
+        match value.to_lowercase().as_str() {
+            "table" => Ok(Output::Table),
+            "json" => Ok(Output::Json),
+            "yaml" => Ok(Output::Yaml),
+            "csv" => Ok(Output::Csv),
+            _ => Err(format!("'{}' is not one of table, json, yaml, csv", value)),
+        }
+    }
+}
+
+impl Output {
+    /// Print `value` to the standard output in this format.
+    pub fn print<T: Serialize>(self, value: &T) -> Result<(), CliError> {
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+        match self {
+            Output::Json => writeln!(stdout, "{}", serde_json::to_string_pretty(value)?)?,
+            Output::Yaml => write!(stdout, "{}", serde_yaml::to_string(value)?)?,
+            Output::Table => {
+                let (columns, rows) = records(serde_json::to_value(value)?);
+                write!(stdout, "{}", render_table(&columns, &rows))?
+            }
+            Output::Csv => {
+                let (columns, rows) = records(serde_json::to_value(value)?);
+                let mut writer = csv::Writer::from_writer(stdout);
+                writer.write_record(&columns)?;
+                for row in rows {
+                    writer.write_record(&row)?;
+                }
+                writer.flush()?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Split a result in records, each flattened to one line: a list gives one record per element, an object wrapping a
+/// single list of objects (like a page of runs with its token) gives the elements of that list, anything else a
+/// single record.
+/// Returns the columns, in the order they first appear, and the cells of each record.
+fn records(value: Value) -> (Vec<String>, Vec<Vec<String>>) {
+    let values = match value {
+        Value::Array(values) => values,
+        Value::Object(mut object) => {
+            let lists: Vec<String> = object
+                .iter()
+                .filter(|(_, value)| is_list_of_records(value))
+                .map(|(key, _)| key.clone())
+                .collect();
+            match lists.as_slice() {
+                [list] if object.values().all(|value| !value.is_object()) => {
+                    match object.remove(list) {
+                        Some(Value::Array(values)) => values,
+                        _ => unreachable!(),
+                    }
+                }
+                _ => vec![Value::Object(object)],
+            }
+        }
+        Value::Null => vec![],
+        value => vec![value],
+    };
+
+    let flattened: Vec<Map<String, Value>> = values
+        .into_iter()
+        .map(|value| {
+            let mut record = Map::new();
+            flatten("", value, &mut record);
+            record
+        })
+        .collect();
+    let mut columns: Vec<String> = vec![];
+    for record in &flattened {
+        for key in record.keys() {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+    }
+    let rows = flattened
+        .iter()
+        .map(|record| {
+            columns
+                .iter()
+                .map(|column| record.get(column).map(cell).unwrap_or_default())
+                .collect()
+        })
+        .collect();
+    (columns, rows)
+}
+
+/// Flatten `value` in `record`, with dotted keys for nested objects. Lists of key/value pairs, like the metrics,
+/// params and tags of a run, become one column per key.
+fn flatten(prefix: &str, value: Value, record: &mut Map<String, Value>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                flatten(&join(&key), value, record);
+            }
+        }
+        Value::Array(values) if !values.is_empty() && values.iter().all(is_key_value) => {
+            for value in values {
+                if let Value::Object(mut object) = value {
+                    if let (Some(Value::String(key)), Some(value)) =
+                        (object.remove("key"), object.remove("value"))
+                    {
+                        record.insert(join(&key), value);
+                    }
+                }
+            }
+        }
+        Value::Array(values) if values.is_empty() => {
+            record.insert(prefix.to_string(), Value::Null);
+        }
+        value if prefix.is_empty() => {
+            record.insert("value".to_string(), value);
+        }
+        value => {
+            record.insert(prefix.to_string(), value);
+        }
+    }
+}
+
+fn is_list_of_records(value: &Value) -> bool {
+    match value {
+        Value::Array(values) => {
+            values.iter().all(Value::is_object) && !values.iter().all(is_key_value)
+        }
+        _ => false,
+    }
+}
+
+fn is_key_value(value: &Value) -> bool {
+    value.get("key").is_some_and(Value::is_string) && value.get("value").is_some()
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Render records as a table aligned on the widest cell of each column. A single record is rendered vertically, one
+/// field per line.
+fn render_table(columns: &[String], rows: &[Vec<String>]) -> String {
+    let (columns, rows): (Vec<String>, Vec<Vec<String>>) = match rows {
+        [row] => (
+            vec!["FIELD".to_string(), "VALUE".to_string()],
+            columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| vec![column.clone(), value.clone()])
+                .collect(),
+        ),
+        _ => (
+            columns.iter().map(|column| column.to_uppercase()).collect(),
+            rows.to_vec(),
+        ),
+    };
+    if columns.is_empty() {
+        return String::new();
+    }
+    let widths: Vec<usize> = (0..columns.len())
+        .map(|index| {
+            rows.iter()
+                .map(|row| row[index].chars().count())
+                .chain(std::iter::once(columns[index].chars().count()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+    let mut table = String::new();
+    for row in std::iter::once(&columns).chain(rows.iter()) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{:<width$}", value, width = width))
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{records, render_table};
+    use spectral::prelude::*;
+
+    #[test]
+    fn can_flatten_runs() {
+        let (columns, rows) = records(serde_json::json!({
+            "runs": [
+                {
+                    "info": {"run_id": "a", "status": "FINISHED"},
+                    "data": {"metrics": [{"key": "rmse", "value": 0.5, "timestamp": 1, "step": 0}]},
+                },
+                {
+                    "info": {"run_id": "b", "status": "RUNNING"},
+                    "data": {"params": [{"key": "depth", "value": "4"}]},
+                },
+            ],
+            "next_page_token": null,
+        }));
+
+        assert_that!(columns).is_equal_to(vec![
+            "data.metrics.rmse".to_string(),
+            "info.run_id".to_string(),
+            "info.status".to_string(),
+            "data.params.depth".to_string(),
+        ]);
+        assert_that!(rows[1]).is_equal_to(vec![
+            String::new(),
+            "b".to_string(),
+            "RUNNING".to_string(),
+            "4".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn can_render_tables() {
+        let (columns, rows) = records(serde_json::json!([
+            {"experiment_id": "0", "name": "Default"},
+            {"experiment_id": "12", "name": "search"},
+        ]));
+        assert_that!(render_table(&columns, &rows).as_str())
+            .is_equal_to("EXPERIMENT_ID  NAME\n0              Default\n12             search\n");
+
+        let (columns, rows) = records(serde_json::json!({"experiment_id": "12"}));
+        assert_that!(render_table(&columns, &rows).as_str())
+            .is_equal_to("FIELD          VALUE\nexperiment_id  12\n");
+    }
+}
@@ -0,0 +1,244 @@
+//! Following the metrics of a run while it is running.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::time::Duration;
+
+use mlflow_api::store::TrackingStore;
+use mlflow_api::{Metric, Run, RunStatus};
+
+use crate::error::CliError;
+use crate::output::Output;
+
+/// Number of values shown in a sparkline.
+const SPARKLINE_WIDTH: usize = 40;
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// How to follow a run.
+#[derive(Debug)]
+pub struct TailOptions {
+    /// Metrics to follow, all the metrics of the run if empty.
+    pub metrics: Vec<String>,
+    /// Time between two polls.
+    pub interval: Duration,
+    /// Print a sparkline of the latest values of each metric after new points.
+    pub sparkline: bool,
+    /// Format of the points.
+    pub output: Output,
+}
+
+/// What was printed for a metric.
+#[derive(Debug, Default)]
+struct Followed {
+    /// Latest point of the metric in the run when its history was last fetched.
+    latest: Option<Metric>,
+    /// Timestamp and step of the newest printed point.
+    printed: Option<(u64, u64)>,
+    /// Last values of the metric, for the sparkline.
+    values: Vec<f32>,
+}
+
+/// Poll the run, printing new metric points as they are logged, until the run is not running or scheduled anymore.
+/// Returns the final status of the run.
+pub fn tail(
+    store: &dyn TrackingStore,
+    run_id: &str,
+    options: &TailOptions,
+) -> Result<RunStatus, CliError> {
+    let mut follower = Follower::new();
+    loop {
+        let run = store.get_run(run_id)?;
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+        follower.print_new_points(store, &run, options, &mut stdout)?;
+        stdout.flush()?;
+
+        match run.info.status {
+            RunStatus::Running | RunStatus::Scheduled => std::thread::sleep(options.interval),
+            // points logged between the last poll and the end of the run were printed above
+            status => return Ok(status),
+        }
+    }
+}
+
+/// Printer of the points of the metrics of a run not printed yet.
+#[derive(Debug)]
+struct Follower {
+    followed: BTreeMap<String, Followed>,
+    header: bool,
+}
+
+impl Follower {
+    fn new() -> Follower {
+        Follower {
+            followed: BTreeMap::new(),
+            header: true,
+        }
+    }
+
+    /// Print the points of the followed metrics newer than the printed ones. The history of a metric is only fetched
+    /// again when its latest point in `run` changed.
+    fn print_new_points<W: Write>(
+        &mut self,
+        store: &dyn TrackingStore,
+        run: &Run,
+        options: &TailOptions,
+        output: &mut W,
+    ) -> Result<(), CliError> {
+        let latest: BTreeMap<&str, &Metric> = run
+            .data
+            .iter()
+            .flat_map(|data| data.metrics.iter())
+            .map(|metric| (metric.key.as_str(), metric))
+            .collect();
+        let keys: BTreeSet<&str> = if options.metrics.is_empty() {
+            latest.keys().cloned().collect()
+        } else {
+            options.metrics.iter().map(String::as_str).collect()
+        };
+
+        for key in keys {
+            let latest = latest.get(key).cloned();
+            let state = self.followed.entry(key.to_string()).or_default();
+            if latest.is_none() || state.latest.as_ref() == latest {
+                continue;
+            }
+            state.latest = latest.cloned();
+            let history = crate::metric_history(store, &run.info.run_id, key)?;
+            let new_points: Vec<&Metric> = history
+                .iter()
+                .filter(|metric| Some((metric.timestamp, metric.step)) > state.printed)
+                .collect();
+            if new_points.is_empty() {
+                continue;
+            }
+            for metric in &new_points {
+                print_point(output, metric, options.output, &mut self.header)?;
+            }
+            state.printed = new_points
+                .iter()
+                .map(|metric| (metric.timestamp, metric.step))
+                .max();
+            state
+                .values
+                .extend(new_points.iter().map(|metric| metric.value));
+            let excess = state.values.len().saturating_sub(SPARKLINE_WIDTH);
+            state.values.drain(..excess);
+            if options.sparkline && options.output == Output::Table {
+                writeln!(
+                    output,
+                    "{:<20} {}",
+                    key,
+                    sparkline(&state.values, SPARKLINE_WIDTH)
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn print_point<W: Write>(
+    output: &mut W,
+    metric: &Metric,
+    format: Output,
+    header: &mut bool,
+) -> Result<(), CliError> {
+    match format {
+        Output::Table => writeln!(
+            output,
+            "{}  {:<20} step={:<6} {}",
+            chrono::DateTime::<chrono::Utc>::from(
+                std::time::UNIX_EPOCH + Duration::from_millis(metric.timestamp)
+            )
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            metric.key,
+            metric.step,
+            metric.value
+        )?,
+        // one document per point, so that the output can be read as it comes
+        Output::Json => writeln!(output, "{}", serde_json::to_string(metric)?)?,
+        Output::Yaml => write!(output, "{}", serde_yaml::to_string(metric)?)?,
+        Output::Csv => {
+            if *header {
+                writeln!(output, "key,step,timestamp,value")?;
+            }
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(&mut *output);
+            writer.serialize((&metric.key, metric.step, metric.timestamp, metric.value))?;
+            writer.flush()?;
+        }
+    }
+    *header = false;
+    Ok(())
+}
+
+/// Sparkline of the last `width` values, scaled between their minimum and maximum.
+fn sparkline(values: &[f32], width: usize) -> String {
+    let values = &values[values.len().saturating_sub(width)..];
+    let finite = values.iter().filter(|value| value.is_finite());
+    let min = finite.clone().cloned().fold(f32::INFINITY, f32::min);
+    let max = finite.cloned().fold(f32::NEG_INFINITY, f32::max);
+    values
+        .iter()
+        .map(|value| {
+            if !value.is_finite() {
+                ' '
+            } else if max > min {
+                let index = ((value - min) / (max - min) * (SPARKS.len() - 1) as f32).round();
+                SPARKS[index as usize]
+            } else {
+                SPARKS[(SPARKS.len() - 1) / 2]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use mlflow_api::MemoryStore;
+    use spectral::prelude::*;
+
+    use super::{sparkline, Follower, TailOptions};
+    use crate::output::Output;
+
+    #[test]
+    fn can_print_only_new_points() {
+        let store = MemoryStore::new();
+        let run_id = store.create_run("0", None, None, None).unwrap().info.run_id;
+        store.log_metric(&run_id, "loss", 1., 1, Some(0)).unwrap();
+        store.log_metric(&run_id, "loss", 0.5, 2, Some(1)).unwrap();
+        let options = TailOptions {
+            metrics: vec![],
+            interval: Duration::from_secs(1),
+            sparkline: false,
+            output: Output::Csv,
+        };
+        let mut follower = Follower::new();
+        let mut poll = || {
+            let mut output = vec![];
+            let run = store.get_run(&run_id).unwrap();
+            follower
+                .print_new_points(&store, &run, &options, &mut output)
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        assert_that!(poll())
+            .is_equal_to("key,step,timestamp,value\nloss,0,1,1.0\nloss,1,2,0.5\n".to_string());
+        assert_that!(poll()).is_equal_to(String::new());
+        store.log_metric(&run_id, "loss", 0.25, 3, Some(2)).unwrap();
+        assert_that!(poll()).is_equal_to("loss,2,3,0.25\n".to_string());
+    }
+
+    #[test]
+    fn can_draw_sparklines() {
+        assert_that!(sparkline(&[0., 1., 2., 3., 4., 5., 6., 7.], 40))
+            .is_equal_to("▁▂▃▄▅▆▇█".to_string());
+        assert_that!(sparkline(&[5., 1., 3.], 2)).is_equal_to("▁█".to_string());
+        assert_that!(sparkline(&[2., 2., f32::NAN], 40)).is_equal_to("▄▄ ".to_string());
+        assert_that!(sparkline(&[], 40)).is_equal_to(String::new());
+    }
+}
@@ -1,46 +1,146 @@
+use std::collections::HashMap;
 use std::env;
+use std::path::Path;
+
+/// Maximum number of runs in a single bulk metric history request.
+const METRIC_HISTORY_BULK_MAX_RUNS: usize = 100;
+/// Maximum number of points per run in a single bulk metric history request.
+const METRIC_HISTORY_BULK_MAX_RESULTS: usize = 2500;
+/// Number of concurrent requests when getting metric histories run by run.
+const METRIC_HISTORY_CONCURRENCY: usize = 8;
 
 /// MLflowClient, providing helpers methods for starting and managinf Mlflow `Run`s
 #[derive(Debug)]
 pub struct MLflowClient {
     active_experiment_id: Option<String>,
     active_run_id: Option<String>,
-    /// API Client used to send requests directly to MLflow.
-    pub api: crate::MLflowAPI,
+    tracking_uri: String,
+    /// Tracking store used to send requests directly to MLflow.
+    pub store: std::sync::Arc<dyn crate::store::TrackingStore>,
+    spool: Option<crate::spool::SpoolStore>,
+    heartbeat: Option<crate::stale::Heartbeat>,
 }
 
+// the logging methods return `Result<_, ()>` since the first release, changing their error type breaks callers
+#[allow(clippy::result_unit_err)]
 impl MLflowClient {
-    /// TODO: doc, name
+    /// New `MLflowClient` over the tracking store pointed to by `MLFLOW_TRACKING_URI`, or over a local `FileStore`
+    /// in `./mlruns` when it is unset, like the Python client.
     pub fn new() -> Result<Self, crate::errors::SetupError> {
-        Self::new_with_tracking_uri(
-            &env::var("MLFLOW_TRACKING_URI")
-                .unwrap_or_else(|_| "Missing MLFLOW_TRACKING_URI".to_string()),
-        )
+        let uri = env::var("MLFLOW_TRACKING_URI").unwrap_or_else(|_| "mlruns".to_string());
+        Self::new_with_tracking_uri(&uri)
     }
 
-    /// TODO: doc, name
+    /// New `MLflowClient` over the tracking store pointed to by `uri`: a tracking server for `http://` and
+    /// `https://` URIs, a local `FileStore` for `file:` URIs and plain paths, a `MemoryStore` for `memory:`.
     pub fn new_with_tracking_uri(uri: &str) -> Result<Self, crate::errors::SetupError> {
         Ok(MLflowClient {
             active_experiment_id: None,
             active_run_id: None,
-            api: crate::MLflowAPI::new(&uri)?,
+            tracking_uri: uri.to_string(),
+            store: crate::store::open_store(uri)?.into(),
+            spool: None,
+            heartbeat: None,
+        })
+    }
+
+    /// New `MLflowClient` over the tracking store pointed to by `uri`, spooling run creation, metrics, params, tags
+    /// and status updates to the journal at `journal` while the store is unreachable. The journal is replayed by
+    /// [`MLflowClient::sync`].
+    pub fn new_with_spool(uri: &str, journal: &Path) -> Result<Self, crate::errors::SetupError> {
+        let spool = crate::spool::SpoolStore::new(crate::store::open_store(uri)?, journal)
+            .map_err(|error| crate::errors::SetupError::InvalidStore(error.to_string()))?;
+        Ok(MLflowClient {
+            active_experiment_id: None,
+            active_run_id: None,
+            tracking_uri: uri.to_string(),
+            store: std::sync::Arc::new(spool.clone()),
+            spool: Some(spool),
+            heartbeat: None,
         })
     }
 
+    /// New `MLflowClient` over an existing tracking store, like a `MemoryStore` kept by a test to check what was
+    /// logged.
+    pub fn new_with_store(store: Box<dyn crate::store::TrackingStore>) -> Self {
+        MLflowClient {
+            active_experiment_id: None,
+            active_run_id: None,
+            tracking_uri: String::new(),
+            store: store.into(),
+            spool: None,
+            heartbeat: None,
+        }
+    }
+
+    /// Replay the calls spooled while the tracking store was unreachable, see [`crate::spool::sync`]. The active run,
+    /// if created offline, is replaced by the synced run. Does nothing without a spool.
+    pub fn sync(&mut self) -> Result<crate::spool::SyncReport, crate::errors::SpoolError> {
+        let report = match self.spool.as_ref() {
+            Some(spool) => spool.sync()?,
+            None => return Ok(crate::spool::SyncReport::default()),
+        };
+        if let Some(run_id) = self
+            .active_run_id
+            .as_ref()
+            .and_then(|run_id| report.run_ids.get(run_id))
+        {
+            let run_id = run_id.clone();
+            self.set_active_run(run_id);
+        }
+        Ok(report)
+    }
+
+    /// Set the [`crate::stale::HEARTBEAT_TAG`] of the active run every `interval` from a background thread, so that
+    /// a [`crate::stale::StaleRunDetector`] doesn't take it for a crashed run between two metrics. The heartbeat stops
+    /// when the run ends and the client is dropped. The beats go through the store of the client, spooled with the
+    /// other calls while it is unreachable.
+    pub fn enable_heartbeat(&mut self, interval: std::time::Duration) {
+        let heartbeat =
+            crate::stale::Heartbeat::start(std::sync::Arc::clone(&self.store), interval);
+        heartbeat.set_run(self.active_run_id.as_deref());
+        self.heartbeat = Some(heartbeat);
+    }
+
+    /// Take the error of the last failed heartbeat since the previous call, if any.
+    pub fn heartbeat_error(
+        &self,
+    ) -> Option<crate::errors::ClientError<crate::errors::GetExperimentErrorCode>> {
+        self.heartbeat
+            .as_ref()
+            .and_then(crate::stale::Heartbeat::take_error)
+    }
+
+    fn set_active_run(&mut self, run_id: String) {
+        if let Some(heartbeat) = self.heartbeat.as_ref() {
+            heartbeat.set_run(Some(&run_id));
+        }
+        self.active_run_id = Some(run_id);
+    }
+
     /// TODO: return error
     pub fn resume_run(&mut self, run_id: Option<&str>) -> Result<(), ()> {
         if let Some(run_id) = run_id {
-            self.api.get_run(run_id).map_err(|_| ())?;
-            self.active_run_id = Some(run_id.to_string());
+            self.store.get_run(run_id).map_err(|_| ())?;
+            self.set_active_run(run_id.to_string());
         } else if let Ok(run_id) = env::var("MLFLOW_RUN_ID") {
-            self.api.get_run(&run_id).map_err(|_| ())?;
-            self.active_run_id = Some(run_id);
+            self.store.get_run(&run_id).map_err(|_| ())?;
+            self.set_active_run(run_id);
         } else {
             return Err(());
         }
         Ok(())
     }
 
+    /// Set the run with the given name in an experiment as the active run. When several runs have this name, the
+    /// most recent one is used.
+    pub fn resume_run_by_name(&mut self, experiment_id: &str, run_name: &str) -> Result<(), ()> {
+        let run = self.get_run_by_name(experiment_id, run_name)?.ok_or(())?;
+        self.active_experiment_id = Some(run.info.experiment_id);
+        self.set_active_run(run.info.run_id);
+        Ok(())
+    }
+
     /// TODO
     pub fn start_run(&mut self, run_name: &str) -> Result<(), ()> {
         self.start_run_internal(None, Some(run_name)).map(|_| ())
@@ -61,16 +161,16 @@ impl MLflowClient {
         experiment_id: Option<&str>,
         run_name: Option<&str>,
     ) -> Result<crate::Run, ()> {
+        // name of the experiment to spool the run against, when it can not be resolved offline
+        let mut offline_experiment_name = None;
         if let Some(experiment_id) = experiment_id {
-            self.api.get_experiment(experiment_id).map_err(|_| ())?;
-            self.active_experiment_id = Some(experiment_id.to_string());
+            self.use_experiment(experiment_id)?;
         } else if let Ok(experiment_name) = env::var("MLFLOW_EXPERIMENT_NAME") {
-            self.set_experiment(&experiment_name).map_err(|_| ())?;
+            offline_experiment_name = self.use_experiment_named(&experiment_name)?;
         } else if let Ok(experiment_id) = env::var("MLFLOW_EXPERIMENT_ID") {
-            self.set_experiment(&experiment_id).map_err(|_| ())?;
-        }
-        if self.active_experiment_id.is_none() {
-            self.set_experiment("Default").map_err(|_| ())?;
+            self.use_experiment(&experiment_id)?;
+        } else if self.active_experiment_id.is_none() {
+            offline_experiment_name = self.use_experiment_named("Default")?;
         }
         let mut tags = vec![crate::RunTag {
             key: "mlflow.user".to_string(),
@@ -82,17 +182,28 @@ impl MLflowClient {
                 value: run_name.to_string(),
             });
         }
-        if let Ok(run) = self.api.create_run(
-            &self.active_experiment_id.clone().expect(""),
-            Some(
-                std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .expect("time went strange there")
-                    .as_millis() as u64,
+        let start_time = Some(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("time went strange there")
+                .as_millis() as u64,
+        );
+        let creation = match (offline_experiment_name, self.spool.as_ref()) {
+            (Some(experiment_name), Some(spool)) => spool.create_run_in_experiment_named(
+                &experiment_name,
+                start_time,
+                Some(tags),
+                run_name,
+            ),
+            _ => self.store.create_run(
+                &self.active_experiment_id.clone().expect(""),
+                start_time,
+                Some(tags),
+                run_name,
             ),
-            Some(tags),
-        ) {
-            self.active_run_id = Some(run.info.run_id.clone());
+        };
+        if let Ok(run) = creation {
+            self.set_active_run(run.info.run_id.clone());
             Ok(run)
         } else {
             Err(())
@@ -100,13 +211,37 @@ impl MLflowClient {
         // .map_err(|_| ())
     }
 
+    /// Check that the experiment with ID `experiment_id` exists and make it the active experiment.
+    fn use_experiment(&mut self, experiment_id: &str) -> Result<(), ()> {
+        match self.store.get_experiment(experiment_id) {
+            Ok(_) => {}
+            // a spooled run is checked against its experiment when synced
+            Err(error) if self.spool.is_some() && crate::spool::is_unreachable(&error) => {}
+            Err(_) => return Err(()),
+        }
+        self.active_experiment_id = Some(experiment_id.to_string());
+        Ok(())
+    }
+
+    /// Make the experiment named `experiment_name` the active experiment, see [`MLflowClient::set_experiment`]. When
+    /// the store is unreachable and calls are spooled, the name is returned for the run to be spooled against it.
+    fn use_experiment_named(&mut self, experiment_name: &str) -> Result<Option<String>, ()> {
+        match self.set_experiment(experiment_name) {
+            Ok(_) => Ok(None),
+            Err(error) if self.spool.is_some() && crate::spool::is_unreachable(&error) => {
+                Ok(Some(experiment_name.to_string()))
+            }
+            Err(_) => Err(()),
+        }
+    }
+
     /// Set given experiment as active experiment. If experiment does not exist, create an experiment with provided
     /// name.
     pub fn set_experiment(
         &mut self,
         experiment_name: &str,
     ) -> Result<String, crate::errors::ClientError<crate::errors::CreateExperimentErrorCode>> {
-        if let Ok(found) = self.api.get_experiment_by_name(experiment_name) {
+        if let Ok(found) = self.store.get_experiment_by_name(experiment_name) {
             self.active_experiment_id = Some(found.experiment_id.clone());
             Ok(found.experiment_id)
         } else {
@@ -120,7 +255,7 @@ impl MLflowClient {
         experiment_name: &str,
         artifact_path: Option<&str>,
     ) -> Result<String, crate::errors::ClientError<crate::errors::CreateExperimentErrorCode>> {
-        let creation = self.api.create_experiment(experiment_name, artifact_path);
+        let creation = self.store.create_experiment(experiment_name, artifact_path);
         if let Ok(experiment_id) = creation.as_ref() {
             self.active_experiment_id = Some(experiment_id.clone());
         }
@@ -137,13 +272,13 @@ impl MLflowClient {
     /// Log a parameter under the current run, creating a run if necessary.
     pub fn log_param(&mut self, key: &str, value: &str) -> Result<(), ()> {
         let run_id = self.ensure_active_run()?.clone();
-        self.api.log_param(&run_id, key, value).map_err(|_| ())
+        self.store.log_param(&run_id, key, value).map_err(|_| ())
     }
 
     /// Log a batch of params for the current run, starting a run if no runs are active.
     pub fn log_params(&mut self, params: &[&crate::Param]) -> Result<(), ()> {
         let run_id = self.ensure_active_run()?.clone();
-        self.api
+        self.store
             .log_batch(&run_id, None, Some(params), None)
             .map_err(|_| ())
     }
@@ -156,7 +291,7 @@ impl MLflowClient {
     /// Log a metric under the current run at step, creating a run if necessary.
     pub fn log_metric_at_step(&mut self, key: &str, value: f32, step: u64) -> Result<(), ()> {
         let run_id = self.ensure_active_run()?.clone();
-        self.api
+        self.store
             .log_metric(
                 &run_id,
                 key,
@@ -173,7 +308,7 @@ impl MLflowClient {
     /// Log a batch of metrics for the current run, starting a run if no runs are active.
     pub fn log_metrics(&mut self, metrics: &[&crate::Metric]) -> Result<(), ()> {
         let run_id = self.ensure_active_run()?.clone();
-        self.api
+        self.store
             .log_batch(&run_id, Some(metrics), None, None)
             .map_err(|_| ())
     }
@@ -181,13 +316,13 @@ impl MLflowClient {
     /// Set a tag under the current run, creating a run if necessary.
     pub fn set_tag(&mut self, key: &str, value: &str) -> Result<(), ()> {
         let run_id = self.ensure_active_run()?.clone();
-        self.api.set_run_tag(&run_id, key, value).map_err(|_| ())
+        self.store.set_run_tag(&run_id, key, value).map_err(|_| ())
     }
 
     /// Log a batch of tags for the current run, starting a run if no runs are active.
     pub fn set_tags(&mut self, tags: &[&crate::RunTag]) -> Result<(), ()> {
         let run_id = self.ensure_active_run()?.clone();
-        self.api
+        self.store
             .log_batch(&run_id, None, None, Some(tags))
             .map_err(|_| ())
     }
@@ -195,7 +330,7 @@ impl MLflowClient {
     /// Delete a tag from a run. This is irreversible.
     pub fn delete_tag(&mut self, key: &str) -> Result<(), ()> {
         let run_id = self.ensure_active_run()?.clone();
-        self.api.delete_run_tag(&run_id, key).map_err(|_| ())
+        self.store.delete_run_tag(&run_id, key).map_err(|_| ())
     }
 
     /// Get the currently active Run, or None if no such run exists.
@@ -203,7 +338,7 @@ impl MLflowClient {
         self.active_run_id
             .as_ref()
             .ok_or(())
-            .and_then(|run_id| self.api.get_run(&run_id).map_err(|_| ()))
+            .and_then(|run_id| self.store.get_run(run_id).map_err(|_| ()))
     }
 
     /// End an active MLflow run (if there is one).
@@ -224,9 +359,12 @@ impl MLflowClient {
             }
             _ => None,
         };
+        if let (Some(heartbeat), Some(_)) = (self.heartbeat.as_ref(), end_time) {
+            heartbeat.set_run(None);
+        }
         self.active_run_id.as_ref().ok_or(()).and_then(|run_id| {
-            self.api
-                .update_run(&run_id, status, end_time)
+            self.store
+                .update_run(run_id, status, end_time, None)
                 .map(|_| ())
                 .map_err(|_| ())
         })
@@ -241,7 +379,7 @@ impl MLflowClient {
         max_result: Option<u32>,
         order_by: Option<&[&str]>,
     ) -> Result<Vec<crate::Run>, ()> {
-        self.api
+        self.store
             .search_runs(
                 experiment_ids,
                 filter_string,
@@ -254,14 +392,288 @@ impl MLflowClient {
             .map_err(|_| ())
     }
 
+    /// Get the history of a metric for several runs, as a map from run ID to the values sorted by step and timestamp.
+    /// If `max_points` is specified, each history is downsampled to at most this number of points evenly spread over
+    /// the steps, using the bulk endpoint of the server when it's available. Otherwise, or on older servers, histories
+    /// are retrieved concurrently run by run.
+    pub fn get_metric_histories(
+        &self,
+        run_ids: &[&str],
+        metric_key: &str,
+        max_points: Option<usize>,
+    ) -> Result<HashMap<String, Vec<crate::Metric>>, ()> {
+        let mut histories = match max_points {
+            Some(max_points) if max_points <= METRIC_HISTORY_BULK_MAX_RESULTS => self
+                .get_metric_histories_bulk(run_ids, metric_key, max_points)
+                .or_else(|_| self.get_metric_histories_by_run(run_ids, metric_key))?,
+            _ => self.get_metric_histories_by_run(run_ids, metric_key)?,
+        };
+        for history in histories.values_mut() {
+            history.sort_by(|a, b| a.step.cmp(&b.step).then(a.timestamp.cmp(&b.timestamp)));
+            if let Some(max_points) = max_points {
+                downsample(history, max_points);
+            }
+        }
+        Ok(histories)
+    }
+
+    fn get_metric_histories_bulk(
+        &self,
+        run_ids: &[&str],
+        metric_key: &str,
+        max_points: usize,
+    ) -> Result<HashMap<String, Vec<crate::Metric>>, ()> {
+        let mut histories: HashMap<String, Vec<crate::Metric>> = run_ids
+            .iter()
+            .map(|run_id| (run_id.to_string(), vec![]))
+            .collect();
+        for chunk in run_ids.chunks(METRIC_HISTORY_BULK_MAX_RUNS) {
+            let metrics = self
+                .store
+                .get_metric_history_bulk_interval(
+                    chunk,
+                    metric_key,
+                    None,
+                    None,
+                    Some(max_points as u32),
+                )
+                .map_err(|_| ())?;
+            for metric in metrics {
+                histories
+                    .entry(metric.run_id)
+                    .or_default()
+                    .push(metric.metric);
+            }
+        }
+        Ok(histories)
+    }
+
+    fn get_metric_histories_by_run(
+        &self,
+        run_ids: &[&str],
+        metric_key: &str,
+    ) -> Result<HashMap<String, Vec<crate::Metric>>, ()> {
+        let mut histories = HashMap::new();
+        for chunk in run_ids.chunks(METRIC_HISTORY_CONCURRENCY) {
+            let chunk_histories = std::thread::scope(|scope| {
+                chunk
+                    .iter()
+                    .map(|run_id| {
+                        scope.spawn(move || {
+                            self.get_full_metric_history(run_id, metric_key)
+                                .map(|history| (run_id.to_string(), history))
+                        })
+                    })
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .map(|handle| handle.join().map_err(|_| ())?)
+                    .collect::<Result<Vec<_>, ()>>()
+            })?;
+            histories.extend(chunk_histories);
+        }
+        Ok(histories)
+    }
+
+    fn get_full_metric_history(
+        &self,
+        run_id: &str,
+        metric_key: &str,
+    ) -> Result<Vec<crate::Metric>, ()> {
+        let mut history = vec![];
+        let mut page_token = None;
+        loop {
+            let (metrics, next_page_token) = self
+                .store
+                .get_metric_history(run_id, metric_key, None, page_token.as_deref())
+                .map_err(|_| ())?;
+            history.extend(metrics);
+            match next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(history),
+            }
+        }
+    }
+
+    /// Get the most recent run with the given name in an experiment, or `None` if there is no such run. The run name
+    /// is looked up in run metadata, or in the `mlflow.runName` tag on servers that don't support it.
+    pub fn get_run_by_name(
+        &self,
+        experiment_id: &str,
+        run_name: &str,
+    ) -> Result<Option<crate::Run>, ()> {
+        let run_name_filter = run_name.replace('\\', "\\\\").replace('\'', "\\'");
+        let order_by = ["attributes.start_time DESC"];
+        self.store
+            .search_runs(
+                &[experiment_id],
+                Some(&format!("attributes.run_name = '{}'", run_name_filter)),
+                None,
+                Some(1),
+                Some(&order_by),
+                None,
+            )
+            .or_else(|_| {
+                self.store.search_runs(
+                    &[experiment_id],
+                    Some(&format!("tags.\"mlflow.runName\" = '{}'", run_name_filter)),
+                    None,
+                    Some(1),
+                    Some(&order_by),
+                    None,
+                )
+            })
+            .map(|(runs, _)| runs.into_iter().next())
+            .map_err(|_| ())
+    }
+
     /// Delete an experiment from the backend store.
     pub fn delete_experiment(&self, experiment_id: &str) -> Result<(), ()> {
-        self.api.delete_experiment(experiment_id).map_err(|_| ())
+        self.store.delete_experiment(experiment_id).map_err(|_| ())
     }
 
     /// Deletes a run with the given ID.
     pub fn delete_run(&self, run_id: &str) -> Result<(), ()> {
-        self.api.delete_experiment(run_id).map_err(|_| ())
+        self.store.delete_experiment(run_id).map_err(|_| ())
+    }
+
+    fn artifact_repository(
+        &mut self,
+    ) -> Result<Box<dyn crate::artifacts::ArtifactRepository>, crate::errors::ArtifactError> {
+        let run_id = self
+            .ensure_active_run()
+            .map_err(|_| crate::errors::ArtifactError::NoActiveRun)?
+            .clone();
+        let run = self.store.get_run(&run_id)?;
+        crate::artifacts::get_artifact_repository(&run.info.artifact_uri, &self.tracking_uri)
+    }
+
+    /// Log a local file as an artifact of the current run, creating a run if necessary. If `artifact_path` is
+    /// specified, the file is stored in this directory of the run's artifact root.
+    pub fn log_artifact(
+        &mut self,
+        local_path: &Path,
+        artifact_path: Option<&str>,
+    ) -> Result<(), crate::errors::ArtifactError> {
+        self.artifact_repository()?
+            .log_artifact(local_path, artifact_path)
+    }
+
+    /// Log all the content of a local directory as artifacts of the current run, creating a run if necessary.
+    pub fn log_artifacts(
+        &mut self,
+        local_dir: &Path,
+        artifact_path: Option<&str>,
+    ) -> Result<(), crate::errors::ArtifactError> {
+        self.artifact_repository()?
+            .log_artifacts(local_dir, artifact_path)
+    }
+
+    /// Log a model directory as artifacts of the current run, creating a run if necessary. The `MLmodel` file, filled
+    /// with the run information in `model`, and the requirements are written to a copy of `model_dir` that is
+    /// uploaded in its place, and the model is recorded in the `mlflow.log-model.history` run tag.
+    pub fn log_model(
+        &mut self,
+        model_dir: &Path,
+        artifact_path: &str,
+        model: &mut crate::model::Model,
+        requirements: Option<&[&str]>,
+    ) -> Result<(), crate::errors::ModelError> {
+        let run_id = self
+            .ensure_active_run()
+            .map_err(|_| crate::errors::ArtifactError::NoActiveRun)?
+            .clone();
+        model.run_id = Some(run_id.clone());
+        model.artifact_path = Some(artifact_path.to_string());
+        model.validate()?;
+        // the files are added to a copy, the directory of the caller is left untouched
+        let staging_dir =
+            env::temp_dir().join(format!("mlflow-model-{}", uuid::Uuid::new_v4().to_simple()));
+        let logged =
+            self.log_model_files(model_dir, &staging_dir, artifact_path, model, requirements);
+        if staging_dir.exists() {
+            std::fs::remove_dir_all(&staging_dir)?;
+        }
+        logged?;
+
+        let run = self.store.get_run(&run_id)?;
+        let mut history = run
+            .data
+            .and_then(|data| {
+                data.tags
+                    .into_iter()
+                    .find(|tag| tag.key == crate::model::LOG_MODEL_HISTORY_TAG)
+            })
+            .and_then(|tag| serde_json::from_str::<Vec<serde_json::Value>>(&tag.value).ok())
+            .unwrap_or_default();
+        history.push(model.history_entry()?);
+        self.store.set_run_tag(
+            &run_id,
+            crate::model::LOG_MODEL_HISTORY_TAG,
+            &serde_json::to_string(&history)?,
+        )?;
+        Ok(())
+    }
+
+    fn log_model_files(
+        &mut self,
+        model_dir: &Path,
+        staging_dir: &Path,
+        artifact_path: &str,
+        model: &crate::model::Model,
+        requirements: Option<&[&str]>,
+    ) -> Result<(), crate::errors::ModelError> {
+        crate::model::copy_dir(model_dir, staging_dir)?;
+        if let Some(requirements) = requirements {
+            crate::model::Model::save_requirements(staging_dir, requirements)?;
+        }
+        model.save(staging_dir)?;
+        self.log_artifacts(staging_dir, Some(artifact_path))?;
+        Ok(())
+    }
+
+    /// Log a local file as a dataset used by the current run, creating a run if necessary. The dataset is named after
+    /// the file if `name` is not specified, and its digest is computed from the file content. `context` describes how
+    /// the dataset is used, like `training` or `eval`.
+    pub fn log_dataset(
+        &mut self,
+        path: &Path,
+        name: Option<&str>,
+        context: Option<&str>,
+    ) -> Result<crate::Dataset, ()> {
+        let run_id = self.ensure_active_run()?.clone();
+        let path = path.canonicalize().map_err(|_| ())?;
+        let dataset = crate::Dataset {
+            name: name
+                .map(str::to_string)
+                .or_else(|| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                })
+                .ok_or(())?,
+            digest: file_digest(&path).map_err(|_| ())?,
+            source_type: "local".to_string(),
+            source: serde_json::json!({ "uri": path.to_string_lossy() }).to_string(),
+            schema: None,
+            profile: None,
+        };
+        let tags = context
+            .map(|context| {
+                vec![crate::InputTag {
+                    key: "mlflow.data.context".to_string(),
+                    value: context.to_string(),
+                }]
+            })
+            .unwrap_or_default();
+        self.store
+            .log_inputs(
+                &run_id,
+                Some(&[crate::DatasetInput {
+                    tags,
+                    dataset: dataset.clone(),
+                }]),
+            )
+            .map_err(|_| ())?;
+        Ok(dataset)
     }
 
     /// Get the absolute URI of the specified artifact in the currently active run. If path is not specified, the
@@ -270,7 +682,262 @@ impl MLflowClient {
         self.active_run_id
             .as_ref()
             .ok_or(())
-            .and_then(|run_id| self.api.get_run(&run_id).map_err(|_| ()))
+            .and_then(|run_id| self.store.get_run(run_id).map_err(|_| ()))
             .map(|run| format!("{}/{}", run.info.artifact_uri, artifact_path.unwrap_or("")))
     }
 }
+
+/// Keep at most `max_points` metrics, evenly spread and keeping the last one.
+fn downsample(history: &mut Vec<crate::Metric>, max_points: usize) {
+    if history.len() <= max_points {
+        return;
+    }
+    if max_points <= 1 {
+        let last = history.pop().filter(|_| max_points == 1);
+        *history = last.into_iter().collect();
+        return;
+    }
+    let len = history.len();
+    let kept = (0..max_points)
+        .map(|index| history[index * (len - 1) / (max_points - 1)].clone())
+        .collect();
+    *history = kept;
+}
+
+/// Digest of a file content, truncated to 8 characters like the digests computed by the Python client.
+fn file_digest(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut context = md5::Context::new();
+    std::io::copy(&mut file, &mut context)?;
+    let digest = format!("{:x}", context.compute());
+    Ok(digest[..8].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::{downsample, file_digest, MLflowClient};
+
+    #[test]
+    fn can_log_to_file_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut mlflow =
+            MLflowClient::new_with_tracking_uri(&format!("file://{}", dir.path().display()))
+                .unwrap();
+
+        let experiment_id = mlflow.set_experiment("local").unwrap();
+        mlflow
+            .start_run_in_experiment(&experiment_id, "run")
+            .unwrap();
+        mlflow.log_param("alpha", "0.5").unwrap();
+        mlflow.log_metric("loss", 0.25).unwrap();
+        mlflow.end_run().unwrap();
+
+        let run = mlflow.get_run_by_name(&experiment_id, "run").unwrap();
+        assert_that!(run)
+            .is_some()
+            .map(|run| &run.data.as_ref().unwrap().metrics)
+            .has_length(1);
+        assert_that!(MLflowClient::new_with_tracking_uri("s3://bucket")).is_err();
+    }
+
+    #[test]
+    fn can_log_model_without_changing_model_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let model_dir = dir.path().join("model");
+        std::fs::create_dir_all(model_dir.join("data")).unwrap();
+        std::fs::write(model_dir.join("data").join("weights.bin"), "weights").unwrap();
+        let mut mlflow = MLflowClient::new_with_tracking_uri(&format!(
+            "file://{}",
+            dir.path().join("mlruns").display()
+        ))
+        .unwrap();
+
+        let mut model = crate::model::Model::new();
+        model.add_flavor("python_function", crate::model::Flavor::new());
+        mlflow
+            .log_model(&model_dir, "model", &mut model, Some(&["numpy"]))
+            .unwrap();
+
+        let mut files: Vec<String> = std::fs::read_dir(&model_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        files.sort();
+        assert_that!(files).is_equal_to(vec!["data".to_string()]);
+        let run_id = mlflow.active_run().unwrap().info.run_id;
+        let (_, artifacts) = mlflow.store.list_artifacts(&run_id, Some("model")).unwrap();
+        let paths: Vec<String> = artifacts.into_iter().map(|file| file.path).collect();
+        assert_that!(paths).is_equal_to(vec![
+            "model/MLmodel".to_string(),
+            "model/data".to_string(),
+            "model/requirements.txt".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn can_inspect_memory_store_after_logging() {
+        let store = crate::MemoryStore::new();
+        let mut mlflow = MLflowClient::new_with_store(Box::new(store.clone()));
+
+        mlflow.start_run("run").unwrap();
+        mlflow.log_metric_at_step("loss", 0.5, 1).unwrap();
+        mlflow.log_metric_at_step("loss", 0.25, 2).unwrap();
+        mlflow.end_run().unwrap();
+
+        let runs = store.runs();
+        assert_that!(runs).has_length(1);
+        assert_that!(store.metric_history_of(&runs[0].info.run_id, "loss")).has_length(2);
+        assert_that!(runs[0].info.status).is_equal_to(crate::RunStatus::Finished);
+    }
+
+    #[test]
+    fn can_log_offline_and_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("journal.jsonl");
+        let mut offline = MLflowClient::new_with_spool("http://127.0.0.1:1", &journal).unwrap();
+
+        offline.start_run_in_experiment("0", "offline").unwrap();
+        offline.log_metric_at_step("loss", 0.5, 1).unwrap();
+        offline.set_tag("team", "core").unwrap();
+        let local_run_id = offline.active_run().unwrap().info.run_id;
+        assert_that!(journal.exists()).is_true();
+
+        // the journal is replayed once the store is reachable, and the active run follows the synced run
+        let store_uri = format!("file://{}", dir.path().join("mlruns").display());
+        let mut mlflow = MLflowClient::new_with_spool(&store_uri, &journal).unwrap();
+        mlflow.resume_run(Some(&local_run_id)).unwrap();
+        let report = mlflow.sync().unwrap();
+        assert_that!(report.replayed).is_equal_to(3);
+        assert_that!(journal.exists()).is_false();
+        let run_id = report.run_ids[&local_run_id].clone();
+        assert_that!(mlflow.active_run().unwrap().info.run_id).is_equal_to(&run_id);
+
+        mlflow.log_metric_at_step("loss", 0.25, 2).unwrap();
+        mlflow.end_run().unwrap();
+        let store = crate::FileStore::new(&store_uri).unwrap();
+        let run = store.get_run(&run_id).unwrap();
+        assert_that!(run.info.status).is_equal_to(crate::RunStatus::Finished);
+        assert_that!(
+            store
+                .get_metric_history(&run_id, "loss", None, None)
+                .unwrap()
+                .0
+        )
+        .has_length(2);
+        assert_that!(journal.exists()).is_false();
+    }
+
+    #[test]
+    fn can_log_offline_in_the_default_experiment() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("journal.jsonl");
+        let mut offline = MLflowClient::new_with_spool("http://127.0.0.1:1", &journal).unwrap();
+
+        offline.start_run("offline").unwrap();
+        offline.log_metric("loss", 0.5).unwrap();
+        let local_run_id = offline.active_run().unwrap().info.run_id;
+        offline.end_run().unwrap();
+        // an implicit run is spooled against the default experiment as well
+        let mut offline = MLflowClient::new_with_spool("http://127.0.0.1:1", &journal).unwrap();
+        offline.log_param("alpha", "0.1").unwrap();
+        let implicit_run_id = offline.active_run().unwrap().info.run_id;
+
+        // the default experiment is resolved by name when syncing
+        let store_uri = format!("file://{}", dir.path().join("mlruns").display());
+        let mut mlflow = MLflowClient::new_with_spool(&store_uri, &journal).unwrap();
+        let report = mlflow.sync().unwrap();
+        let store = crate::FileStore::new(&store_uri).unwrap();
+        let default_id = store
+            .get_experiment_by_name("Default")
+            .unwrap()
+            .experiment_id;
+        let run = store.get_run(&report.run_ids[&local_run_id]).unwrap();
+        assert_that!(run.info.experiment_id).is_equal_to(&default_id);
+        assert_that!(run.info.status).is_equal_to(crate::RunStatus::Finished);
+        assert_that!(run.data.unwrap().metrics).has_length(1);
+        let run = store.get_run(&report.run_ids[&implicit_run_id]).unwrap();
+        assert_that!(run.info.experiment_id).is_equal_to(&default_id);
+        assert_that!(run.data.unwrap().params).has_length(1);
+    }
+
+    #[test]
+    fn can_beat_for_the_active_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut mlflow =
+            MLflowClient::new_with_tracking_uri(&format!("file://{}", dir.path().display()))
+                .unwrap();
+        mlflow.enable_heartbeat(std::time::Duration::from_millis(10));
+
+        mlflow.start_run_in_experiment("0", "beating").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        mlflow.end_run().unwrap();
+
+        let run = mlflow.active_run().unwrap();
+        assert_that!(run
+            .data
+            .unwrap()
+            .tags
+            .iter()
+            .map(|tag| tag.key.as_str())
+            .collect::<Vec<_>>())
+        .contains(crate::stale::HEARTBEAT_TAG);
+        assert_that!(mlflow.heartbeat_error()).is_none();
+
+        // the heartbeat shares the store of the client
+        let store = crate::MemoryStore::new();
+        let mut mlflow = MLflowClient::new_with_store(Box::new(store.clone()));
+        mlflow.enable_heartbeat(std::time::Duration::from_millis(10));
+        mlflow.start_run_in_experiment("0", "beating").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let run = store
+            .get_run(&mlflow.active_run().unwrap().info.run_id)
+            .unwrap();
+        assert_that!(run
+            .data
+            .unwrap()
+            .tags
+            .iter()
+            .any(|tag| tag.key == crate::stale::HEARTBEAT_TAG))
+        .is_true();
+    }
+
+    #[test]
+    fn can_compute_file_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dataset.csv");
+        std::fs::write(&path, "x,y\n1,2\n").unwrap();
+
+        assert_that!(file_digest(&path))
+            .is_ok()
+            .is_equal_to(format!("{:x}", md5::compute("x,y\n1,2\n"))[..8].to_string());
+        assert_that!(file_digest(&dir.path().join("missing.csv"))).is_err();
+    }
+
+    #[test]
+    fn can_downsample_metric_history() {
+        let history: Vec<crate::Metric> = (0..10)
+            .map(|step| crate::Metric {
+                key: "loss".to_string(),
+                value: step as f32,
+                timestamp: step,
+                step,
+            })
+            .collect();
+
+        let mut sampled = history.clone();
+        downsample(&mut sampled, 4);
+        assert_that!(sampled.iter().map(|metric| metric.step).collect::<Vec<_>>())
+            .is_equal_to(vec![0, 3, 6, 9]);
+
+        let mut sampled = history.clone();
+        downsample(&mut sampled, 1);
+        assert_that!(sampled.iter().map(|metric| metric.step).collect::<Vec<_>>())
+            .is_equal_to(vec![9]);
+
+        let mut sampled = history.clone();
+        downsample(&mut sampled, 20);
+        assert_that!(sampled).is_equal_to(history);
+    }
+}
@@ -0,0 +1,374 @@
+//! Comparison of runs: params, metrics, tags, status and duration of each run against a baseline run.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::search::status_name;
+use crate::{Run, RunStatus};
+
+/// How a value of a run compares to the same value of the baseline run.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    /// Same value as the baseline.
+    Unchanged,
+    /// Value the baseline does not have.
+    Added,
+    /// Value of the baseline the run does not have.
+    Removed,
+    /// Different value than the baseline.
+    Changed,
+}
+
+impl Change {
+    fn of<T: PartialEq>(baseline: Option<&T>, value: Option<&T>) -> Change {
+        match (baseline, value) {
+            (None, Some(_)) => Change::Added,
+            (Some(_), None) => Change::Removed,
+            (Some(baseline), Some(value)) if baseline != value => Change::Changed,
+            _ => Change::Unchanged,
+        }
+    }
+}
+
+/// A param or tag that is not the same in all the runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValueDiff {
+    /// Key of the param or tag.
+    pub key: String,
+    /// Value in each run, `None` when the run does not have it.
+    pub values: Vec<Option<String>>,
+    /// Change of the value in each run against the baseline.
+    pub changes: Vec<Change>,
+}
+
+/// A metric of the compared runs, with its latest value in each run.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricDiff {
+    /// Key of the metric.
+    pub key: String,
+    /// Latest value in each run, `None` when the run does not have it.
+    pub values: Vec<Option<f64>>,
+    /// Difference with the value of the baseline, for runs where both are known.
+    pub deltas: Vec<Option<f64>>,
+    /// Difference with the value of the baseline relative to it, for runs where both are known and the baseline is
+    /// not zero.
+    pub relative_changes: Vec<Option<f64>>,
+}
+
+/// Comparison of runs against the first of them, the baseline.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunComparison {
+    /// IDs of the compared runs, the baseline first.
+    pub run_ids: Vec<String>,
+    /// Names of the compared runs.
+    pub run_names: Vec<Option<String>>,
+    /// Status of each run.
+    pub statuses: Vec<RunStatus>,
+    /// Duration of each run in milliseconds, `None` for runs that did not end.
+    pub durations: Vec<Option<u64>>,
+    /// Params that are not the same in all the runs, sorted by key.
+    pub params: Vec<ValueDiff>,
+    /// All the metrics of the runs, sorted by key.
+    pub metrics: Vec<MetricDiff>,
+    /// Tags that are not the same in all the runs, sorted by key.
+    pub tags: Vec<ValueDiff>,
+}
+
+impl RunComparison {
+    /// Compare `runs` against the first of them.
+    pub fn new(runs: &[Run]) -> RunComparison {
+        let data: Vec<_> = runs
+            .iter()
+            .map(|run| run.data.clone().unwrap_or_default())
+            .collect();
+        let params: Vec<BTreeMap<String, String>> = data
+            .iter()
+            .map(|data| {
+                data.params
+                    .iter()
+                    .map(|param| (param.key.clone(), param.value.clone()))
+                    .collect()
+            })
+            .collect();
+        let tags: Vec<BTreeMap<String, String>> = data
+            .iter()
+            .map(|data| {
+                data.tags
+                    .iter()
+                    .map(|tag| (tag.key.clone(), tag.value.clone()))
+                    .collect()
+            })
+            .collect();
+        let metrics: Vec<BTreeMap<String, f64>> = data
+            .iter()
+            .map(|data| {
+                data.metrics
+                    .iter()
+                    .map(|metric| (metric.key.clone(), f64::from(metric.value)))
+                    .collect()
+            })
+            .collect();
+
+        RunComparison {
+            run_ids: runs.iter().map(|run| run.info.run_id.clone()).collect(),
+            run_names: runs.iter().map(|run| run.info.run_name.clone()).collect(),
+            statuses: runs.iter().map(|run| run.info.status).collect(),
+            durations: runs
+                .iter()
+                .map(|run| {
+                    run.info
+                        .end_time
+                        .map(|end_time| end_time.saturating_sub(run.info.start_time))
+                })
+                .collect(),
+            params: value_diffs(&params),
+            metrics: keys(&metrics)
+                .into_iter()
+                .map(|key| {
+                    let values: Vec<Option<f64>> =
+                        metrics.iter().map(|run| run.get(key).copied()).collect();
+                    let baseline = values.first().copied().flatten();
+                    MetricDiff {
+                        key: key.to_string(),
+                        deltas: values
+                            .iter()
+                            .map(|value| Some(value.as_ref()? - baseline?))
+                            .collect(),
+                        relative_changes: values
+                            .iter()
+                            .map(|value| {
+                                let baseline = baseline.filter(|baseline| *baseline != 0.)?;
+                                Some((value.as_ref()? - baseline) / baseline.abs())
+                            })
+                            .collect(),
+                        values,
+                    }
+                })
+                .collect(),
+            tags: value_diffs(&tags),
+        }
+    }
+
+    /// Whether the runs do not all have the same status.
+    pub fn status_differs(&self) -> bool {
+        self.statuses
+            .iter()
+            .any(|status| Some(status) != self.statuses.first())
+    }
+}
+
+fn keys<T>(maps: &[BTreeMap<String, T>]) -> BTreeSet<&str> {
+    maps.iter()
+        .flat_map(|map| map.keys().map(String::as_str))
+        .collect()
+}
+
+fn value_diffs(maps: &[BTreeMap<String, String>]) -> Vec<ValueDiff> {
+    keys(maps)
+        .into_iter()
+        .filter_map(|key| {
+            let values: Vec<Option<String>> =
+                maps.iter().map(|map| map.get(key).cloned()).collect();
+            let changes: Vec<Change> = values
+                .iter()
+                .map(|value| Change::of(values[0].as_ref(), value.as_ref()))
+                .collect();
+            if values.iter().all(|value| value == &values[0]) {
+                None
+            } else {
+                Some(ValueDiff {
+                    key: key.to_string(),
+                    values,
+                    changes,
+                })
+            }
+        })
+        .collect()
+}
+
+fn format_duration(milliseconds: u64) -> String {
+    format!("{:.1}s", milliseconds as f64 / 1000.)
+}
+
+/// Aligned table, one row per compared value and one column per run, with the changes against the baseline.
+impl std::fmt::Display for RunComparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rows: Vec<Vec<String>> = vec![];
+        let mut header = vec![String::new()];
+        header.extend(
+            self.run_ids
+                .iter()
+                .zip(&self.run_names)
+                .map(|(run_id, run_name)| run_name.clone().unwrap_or_else(|| run_id.clone())),
+        );
+        rows.push(header);
+
+        let mut status = vec!["status".to_string()];
+        status.extend(
+            self.statuses
+                .iter()
+                .map(|status| status_name(*status).to_string()),
+        );
+        rows.push(status);
+        let mut durations = vec!["duration".to_string()];
+        let baseline_duration = self.durations.first().copied().flatten();
+        durations.extend(self.durations.iter().enumerate().map(|(index, duration)| {
+            match (duration, baseline_duration) {
+                (None, _) => "-".to_string(),
+                (Some(duration), Some(baseline)) if index > 0 && *duration != baseline => {
+                    let delta = *duration as i64 - baseline as i64;
+                    format!(
+                        "{} ({}{})",
+                        format_duration(*duration),
+                        if delta < 0 { "-" } else { "+" },
+                        format_duration(delta.unsigned_abs())
+                    )
+                }
+                (Some(duration), _) => format_duration(*duration),
+            }
+        }));
+        rows.push(durations);
+
+        for (title, diffs) in &[("params", &self.params), ("tags", &self.tags)] {
+            if diffs.is_empty() {
+                continue;
+            }
+            rows.push(vec![title.to_string()]);
+            for diff in diffs.iter() {
+                let mut row = vec![format!("  {}", diff.key)];
+                row.extend(
+                    diff.values
+                        .iter()
+                        .zip(&diff.changes)
+                        .map(|(value, change)| {
+                            let value = value.clone().unwrap_or_else(|| "-".to_string());
+                            match change {
+                                Change::Added => format!("+ {}", value),
+                                Change::Removed => "(removed)".to_string(),
+                                _ => value,
+                            }
+                        }),
+                );
+                rows.push(row);
+            }
+        }
+
+        if !self.metrics.is_empty() {
+            rows.push(vec!["metrics".to_string()]);
+            for diff in &self.metrics {
+                let mut row = vec![format!("  {}", diff.key)];
+                row.extend(diff.values.iter().enumerate().map(|(index, value)| {
+                    match (value, diff.deltas[index], diff.relative_changes[index]) {
+                        (None, _, _) => "-".to_string(),
+                        // metrics are logged as 32 bits floats, printed as such to avoid spurious digits
+                        (Some(value), Some(delta), relative) if index > 0 && delta != 0. => {
+                            match relative {
+                                Some(relative) => format!(
+                                    "{} ({:+}, {:+.1}%)",
+                                    *value as f32,
+                                    delta as f32,
+                                    relative * 100.
+                                ),
+                                None => format!("{} ({:+})", *value as f32, delta as f32),
+                            }
+                        }
+                        (Some(value), _, _) => (*value as f32).to_string(),
+                    }
+                }));
+                rows.push(row);
+            }
+        }
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let widths: Vec<usize> = (0..columns)
+            .map(|column| {
+                rows.iter()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        for row in rows {
+            let line: Vec<String> = row
+                .iter()
+                .enumerate()
+                .map(|(column, cell)| format!("{:width$}", cell, width = widths[column]))
+                .collect();
+            writeln!(f, "{}", line.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::{Change, RunComparison};
+    use crate::{MemoryStore, RunStatus};
+
+    fn runs() -> Vec<crate::Run> {
+        let store = MemoryStore::new();
+        let baseline = store
+            .create_run("0", Some(1000), None, Some("baseline"))
+            .unwrap();
+        let other = store
+            .create_run("0", Some(1000), None, Some("other"))
+            .unwrap();
+        for (run, lr, loss, status, end_time) in &[
+            (&baseline, "0.1", 0.5, RunStatus::Finished, 3000),
+            (&other, "0.01", 0.25, RunStatus::Failed, 2000),
+        ] {
+            let run_id = &run.info.run_id;
+            store.log_param(run_id, "lr", lr).unwrap();
+            store.log_param(run_id, "epochs", "10").unwrap();
+            store.log_metric(run_id, "loss", *loss, 0, None).unwrap();
+            store
+                .update_run(run_id, *status, Some(*end_time), None)
+                .unwrap();
+        }
+        store
+            .set_run_tag(&baseline.info.run_id, "owner", "alice")
+            .unwrap();
+        store
+            .set_run_tag(&other.info.run_id, "team", "core")
+            .unwrap();
+        store.runs()
+    }
+
+    #[test]
+    fn can_compare_runs() {
+        let comparison = RunComparison::new(&runs());
+
+        assert_that!(comparison.status_differs()).is_true();
+        assert_that!(comparison.durations).is_equal_to(vec![Some(2000), Some(1000)]);
+        assert_that!(comparison.params).has_length(1);
+        assert_that!(comparison.params[0].key.as_str()).is_equal_to("lr");
+        assert_that!(comparison.params[0].changes)
+            .is_equal_to(vec![Change::Unchanged, Change::Changed]);
+        let loss = &comparison.metrics[0];
+        assert_that!(loss.deltas).is_equal_to(vec![Some(0.), Some(-0.25)]);
+        assert_that!(loss.relative_changes).is_equal_to(vec![Some(0.), Some(-0.5)]);
+        let changes: Vec<(&str, Change)> = comparison
+            .tags
+            .iter()
+            .filter(|diff| !diff.key.starts_with("mlflow."))
+            .map(|diff| (diff.key.as_str(), diff.changes[1]))
+            .collect();
+        assert_that!(changes)
+            .is_equal_to(vec![("owner", Change::Removed), ("team", Change::Added)]);
+    }
+
+    #[test]
+    fn can_render_comparison() {
+        let rendered = RunComparison::new(&runs()).to_string();
+
+        assert_that!(rendered.lines().next().unwrap()).contains("baseline  other");
+        assert_that!(rendered).contains("2.0s      1.0s (-1.0s)");
+        assert_that!(rendered).contains("0.25 (-0.25, -50.0%)");
+        assert_that!(rendered).contains("+ core");
+    }
+}
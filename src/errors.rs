@@ -5,6 +5,10 @@
 pub enum SetupError {
     /// URL is invalid.
     InvalidUrl(String),
+    /// The local store could not be opened.
+    InvalidStore(String),
+    /// The URI scheme does not match any tracking store.
+    UnsupportedScheme(String),
 }
 
 impl std::error::Error for SetupError {}
@@ -12,10 +16,399 @@ impl std::fmt::Display for SetupError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             SetupError::InvalidUrl(url) => write!(f, "Invalid URL: '{}'", url),
+            SetupError::InvalidStore(reason) => write!(f, "Invalid store: {}", reason),
+            SetupError::UnsupportedScheme(uri) => write!(f, "Unsupported store URI: '{}'", uri),
         }
     }
 }
 
+/// An error when storing or retrieving artifacts.
+#[derive(Debug)]
+pub enum ArtifactError {
+    /// No run is active, and none could be started.
+    NoActiveRun,
+    /// The artifact URI uses a scheme that is not supported.
+    UnsupportedUri(String),
+    /// An error reading or writing local files.
+    Io(std::io::Error),
+    /// A request error.
+    QueryError(reqwest::Error),
+    /// An error getting the run from the tracking store.
+    Tracking(ClientError<GetExperimentErrorCode>),
+}
+
+impl std::error::Error for ArtifactError {}
+impl std::fmt::Display for ArtifactError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArtifactError::NoActiveRun => write!(f, "No active run"),
+            ArtifactError::UnsupportedUri(uri) => write!(f, "Unsupported artifact URI: '{}'", uri),
+            ArtifactError::Io(error) => error.fmt(f),
+            ArtifactError::QueryError(error) => error.fmt(f),
+            ArtifactError::Tracking(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<std::io::Error> for ArtifactError {
+    fn from(error: std::io::Error) -> ArtifactError {
+        ArtifactError::Io(error)
+    }
+}
+
+impl From<reqwest::Error> for ArtifactError {
+    fn from(error: reqwest::Error) -> ArtifactError {
+        ArtifactError::QueryError(error)
+    }
+}
+
+impl From<ClientError<GetExperimentErrorCode>> for ArtifactError {
+    fn from(error: ClientError<GetExperimentErrorCode>) -> ArtifactError {
+        ArtifactError::Tracking(error)
+    }
+}
+
+/// An input that does not match a model signature.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    /// A required input is missing.
+    MissingInput(String),
+    /// An input is not part of the schema.
+    UnknownInput(String),
+    /// An input has a value of the wrong type.
+    TypeMismatch {
+        /// Name of the input.
+        input: String,
+        /// Expected type.
+        expected: String,
+    },
+    /// A tensor input has the wrong shape.
+    ShapeMismatch {
+        /// Name of the input.
+        input: String,
+        /// Expected shape.
+        expected: Vec<i64>,
+    },
+    /// The input is not in a supported format.
+    UnsupportedFormat(String),
+}
+
+impl std::error::Error for SchemaError {}
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaError::MissingInput(input) => write!(f, "Missing input '{}'", input),
+            SchemaError::UnknownInput(input) => write!(f, "Unknown input '{}'", input),
+            SchemaError::TypeMismatch { input, expected } => {
+                write!(f, "Input '{}' should be of type {}", input, expected)
+            }
+            SchemaError::ShapeMismatch { input, expected } => {
+                write!(f, "Input '{}' should be of shape {:?}", input, expected)
+            }
+            SchemaError::UnsupportedFormat(reason) => write!(f, "Unsupported input: {}", reason),
+        }
+    }
+}
+
+/// An error when reading, writing or logging a model.
+#[derive(Debug)]
+pub enum ModelError {
+    /// The `MLmodel` file is not valid.
+    Invalid(String),
+    /// An error reading or writing local files.
+    Io(std::io::Error),
+    /// An error parsing or serializing the `MLmodel` file.
+    Yaml(serde_yaml::Error),
+    /// An error parsing or serializing JSON metadata.
+    Json(serde_json::Error),
+    /// The input example does not match the model signature.
+    Schema(SchemaError),
+    /// An error uploading the model artifacts.
+    Artifact(ArtifactError),
+    /// An error recording the model on the run.
+    Tracking(ClientError<GetExperimentErrorCode>),
+}
+
+impl std::error::Error for ModelError {}
+impl std::fmt::Display for ModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModelError::Invalid(reason) => write!(f, "Invalid model: {}", reason),
+            ModelError::Io(error) => error.fmt(f),
+            ModelError::Yaml(error) => error.fmt(f),
+            ModelError::Json(error) => error.fmt(f),
+            ModelError::Schema(error) => error.fmt(f),
+            ModelError::Artifact(error) => error.fmt(f),
+            ModelError::Tracking(error) => {
+                write!(f, "Error recording the model on the run: {}", error)
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for ModelError {
+    fn from(error: std::io::Error) -> ModelError {
+        ModelError::Io(error)
+    }
+}
+
+impl From<serde_yaml::Error> for ModelError {
+    fn from(error: serde_yaml::Error) -> ModelError {
+        ModelError::Yaml(error)
+    }
+}
+
+impl From<serde_json::Error> for ModelError {
+    fn from(error: serde_json::Error) -> ModelError {
+        ModelError::Json(error)
+    }
+}
+
+impl From<SchemaError> for ModelError {
+    fn from(error: SchemaError) -> ModelError {
+        ModelError::Schema(error)
+    }
+}
+
+impl From<ArtifactError> for ModelError {
+    fn from(error: ArtifactError) -> ModelError {
+        ModelError::Artifact(error)
+    }
+}
+
+impl From<ClientError<GetExperimentErrorCode>> for ModelError {
+    fn from(error: ClientError<GetExperimentErrorCode>) -> ModelError {
+        ModelError::Tracking(error)
+    }
+}
+
+/// An error when exporting experiments to an archive or importing them from one.
+#[derive(Debug)]
+pub enum ArchiveError {
+    /// The archive is not valid.
+    Invalid(String),
+    /// An error reading or writing local files.
+    Io(std::io::Error),
+    /// An error parsing or serializing the archive metadata.
+    Json(serde_json::Error),
+    /// An error copying the artifacts of a run.
+    Artifact(ArtifactError),
+    /// An error reading or writing the tracking store.
+    Tracking(String),
+}
+
+impl std::error::Error for ArchiveError {}
+impl std::fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchiveError::Invalid(reason) => write!(f, "Invalid archive: {}", reason),
+            ArchiveError::Io(error) => error.fmt(f),
+            ArchiveError::Json(error) => error.fmt(f),
+            ArchiveError::Artifact(error) => error.fmt(f),
+            ArchiveError::Tracking(reason) => write!(f, "Tracking store error: {}", reason),
+        }
+    }
+}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(error: std::io::Error) -> ArchiveError {
+        ArchiveError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for ArchiveError {
+    fn from(error: serde_json::Error) -> ArchiveError {
+        ArchiveError::Json(error)
+    }
+}
+
+impl From<ArtifactError> for ArchiveError {
+    fn from(error: ArtifactError) -> ArchiveError {
+        ArchiveError::Artifact(error)
+    }
+}
+
+impl<E: ErrorCode + serde::Serialize> From<ClientError<E>> for ArchiveError {
+    fn from(error: ClientError<E>) -> ArchiveError {
+        ArchiveError::Tracking(error.to_string())
+    }
+}
+
+/// An error when collecting deleted experiments and runs.
+#[derive(Debug)]
+pub enum GcError {
+    /// An error deleting the artifacts of a run.
+    Artifact(ArtifactError),
+    /// An error reading or writing the tracking store.
+    Tracking(String),
+}
+
+impl std::error::Error for GcError {}
+impl std::fmt::Display for GcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GcError::Artifact(error) => error.fmt(f),
+            GcError::Tracking(reason) => write!(f, "Tracking store error: {}", reason),
+        }
+    }
+}
+
+impl From<ArtifactError> for GcError {
+    fn from(error: ArtifactError) -> GcError {
+        GcError::Artifact(error)
+    }
+}
+
+impl<E: ErrorCode + serde::Serialize> From<ClientError<E>> for GcError {
+    fn from(error: ClientError<E>) -> GcError {
+        GcError::Tracking(error.to_string())
+    }
+}
+
+/// An error when writing a table of runs.
+#[derive(Debug)]
+pub enum TableError {
+    /// An error writing the output.
+    Io(std::io::Error),
+    /// An error writing CSV.
+    Csv(csv::Error),
+    /// An error writing JSON.
+    Json(serde_json::Error),
+    /// An error building the Arrow record batch.
+    #[cfg(feature = "parquet")]
+    Arrow(arrow_schema::ArrowError),
+    /// An error writing Parquet.
+    #[cfg(feature = "parquet")]
+    Parquet(parquet::errors::ParquetError),
+    /// An error building a Polars DataFrame.
+    #[cfg(feature = "polars")]
+    Polars(polars::prelude::PolarsError),
+}
+
+impl std::error::Error for TableError {}
+impl std::fmt::Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::Io(error) => error.fmt(f),
+            TableError::Csv(error) => error.fmt(f),
+            TableError::Json(error) => error.fmt(f),
+            #[cfg(feature = "parquet")]
+            TableError::Arrow(error) => error.fmt(f),
+            #[cfg(feature = "parquet")]
+            TableError::Parquet(error) => error.fmt(f),
+            #[cfg(feature = "polars")]
+            TableError::Polars(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<std::io::Error> for TableError {
+    fn from(error: std::io::Error) -> TableError {
+        TableError::Io(error)
+    }
+}
+
+impl From<csv::Error> for TableError {
+    fn from(error: csv::Error) -> TableError {
+        TableError::Csv(error)
+    }
+}
+
+impl From<serde_json::Error> for TableError {
+    fn from(error: serde_json::Error) -> TableError {
+        TableError::Json(error)
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<arrow_schema::ArrowError> for TableError {
+    fn from(error: arrow_schema::ArrowError) -> TableError {
+        TableError::Arrow(error)
+    }
+}
+
+#[cfg(feature = "parquet")]
+impl From<parquet::errors::ParquetError> for TableError {
+    fn from(error: parquet::errors::ParquetError) -> TableError {
+        TableError::Parquet(error)
+    }
+}
+
+#[cfg(feature = "polars")]
+impl From<polars::prelude::PolarsError> for TableError {
+    fn from(error: polars::prelude::PolarsError) -> TableError {
+        TableError::Polars(error)
+    }
+}
+
+/// An error when spooling logging calls to the local journal or replaying them to a tracking store.
+#[derive(Debug)]
+pub enum SpoolError {
+    /// An error reading or writing the journal.
+    Io(std::io::Error),
+    /// An entry of the journal is not valid.
+    Json(serde_json::Error),
+    /// A spooled run ID has no matching run in the tracking store.
+    UnknownRun(String),
+    /// An error replaying a spooled call to the tracking store.
+    Tracking(String),
+}
+
+impl std::error::Error for SpoolError {}
+impl std::fmt::Display for SpoolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpoolError::Io(error) => error.fmt(f),
+            SpoolError::Json(error) => write!(f, "Invalid journal entry: {}", error),
+            SpoolError::UnknownRun(run_id) => write!(f, "Unknown spooled run {}", run_id),
+            SpoolError::Tracking(reason) => write!(f, "Error replaying the journal: {}", reason),
+        }
+    }
+}
+
+impl From<std::io::Error> for SpoolError {
+    fn from(error: std::io::Error) -> SpoolError {
+        SpoolError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for SpoolError {
+    fn from(error: serde_json::Error) -> SpoolError {
+        SpoolError::Json(error)
+    }
+}
+
+impl<E: ErrorCode + serde::Serialize> From<ClientError<E>> for SpoolError {
+    fn from(error: ClientError<E>) -> SpoolError {
+        SpoolError::Tracking(error.to_string())
+    }
+}
+
+/// An error when running a hyperparameter sweep.
+#[derive(Debug, PartialEq)]
+pub enum SweepError {
+    /// The parameter space is empty or has an invalid domain.
+    InvalidSpace(String),
+    /// An error creating or updating the runs of the sweep.
+    Tracking(String),
+}
+
+impl std::error::Error for SweepError {}
+impl std::fmt::Display for SweepError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SweepError::InvalidSpace(reason) => write!(f, "Invalid parameter space: {}", reason),
+            SweepError::Tracking(reason) => write!(f, "Tracking store error: {}", reason),
+        }
+    }
+}
+
+impl<E: ErrorCode + serde::Serialize> From<ClientError<E>> for SweepError {
+    fn from(error: ClientError<E>) -> SweepError {
+        SweepError::Tracking(error.to_string())
+    }
+}
+
 #[derive(serde::Deserialize, Debug)]
 pub(crate) struct ErrorResponse<E: ErrorCode + std::fmt::Debug + serde::Serialize> {
     /// The error code.
@@ -36,6 +429,8 @@ pub enum ClientError<E: ErrorCode> {
     },
     /// A request error.
     QueryError(reqwest::Error),
+    /// An error reading or writing a local store.
+    StoreError(std::io::Error),
 }
 
 #[cfg(feature = "integration-tests")]
@@ -44,6 +439,8 @@ impl<E: ErrorCode + PartialEq> PartialEq for ClientError<E> {
         match (self, other) {
             (ClientError::QueryError(_), _) => unimplemented!(),
             (_, ClientError::QueryError(_)) => unimplemented!(),
+            (ClientError::StoreError(_), _) => unimplemented!(),
+            (_, ClientError::StoreError(_)) => unimplemented!(),
             (
                 ClientError::ApiError {
                     error_code: error_code_1,
@@ -58,12 +455,13 @@ impl<E: ErrorCode + PartialEq> PartialEq for ClientError<E> {
     }
 }
 
-impl<'d, E: ErrorCode + std::fmt::Debug + serde::Serialize> std::error::Error for ClientError<E> {}
+impl<E: ErrorCode + std::fmt::Debug + serde::Serialize> std::error::Error for ClientError<E> {}
 
-impl<'d, E: ErrorCode + serde::Serialize> std::fmt::Display for ClientError<E> {
+impl<E: ErrorCode + serde::Serialize> std::fmt::Display for ClientError<E> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ClientError::QueryError(error) => error.fmt(f),
+            ClientError::StoreError(error) => error.fmt(f),
             ClientError::ApiError {
                 error_code,
                 message,
@@ -83,6 +481,12 @@ impl<E: ErrorCode> From<reqwest::Error> for ClientError<E> {
     }
 }
 
+impl<E: ErrorCode> From<std::io::Error> for ClientError<E> {
+    fn from(error: std::io::Error) -> ClientError<E> {
+        ClientError::StoreError(error)
+    }
+}
+
 impl<E: ErrorCode + std::fmt::Debug + serde::Serialize> From<ErrorResponse<E>> for ClientError<E> {
     fn from(error: ErrorResponse<E>) -> ClientError<E> {
         ClientError::ApiError {
@@ -117,6 +521,8 @@ impl ErrorCode for GetExperimentErrorCode {}
 pub enum CreateExperimentErrorCode {
     /// An experiment with the same name already exists.
     ResourceAlreadyExists,
+    /// A parameter has an invalid value
+    InvalidParameterValue,
     /// Unknown error.
     #[serde(other)]
     UnknownError,
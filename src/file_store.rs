@@ -0,0 +1,1474 @@
+//! Tracking store reading and writing the `mlruns` directory layout of the MLflow FileStore.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{
+    ClientError, CreateExperimentErrorCode, ErrorCode, GetExperimentErrorCode,
+    ListExperimentsErrorCode, SetupError,
+};
+use crate::search::{
+    latest_metric, paginate, sample_interval, sort_runs, SearchFilter,
+    BULK_INTERVAL_MAX_RESULTS_DEFAULT,
+};
+use crate::store::{
+    api_error, experiment_already_exists, experiment_name_not_found, experiment_not_found, now,
+    run_not_found, validate_key,
+};
+use crate::{
+    Dataset, DatasetInput, Experiment, ExperimentTag, FileInfo, InputTag, LifecycleStage, Metric,
+    MetricWithRunId, Param, Run, RunData, RunInfo, RunInputs, RunStatus, RunTag, ViewType,
+};
+
+const META_FILE_NAME: &str = "meta.yaml";
+const TRASH_FOLDER_NAME: &str = ".trash";
+const DEFAULT_EXPERIMENT_ID: &str = "0";
+const DEFAULT_EXPERIMENT_NAME: &str = "Default";
+/// Source type of runs created locally.
+const SOURCE_TYPE_LOCAL: u32 = 4;
+
+/// Tracking store writing to a local directory, with the same layout as the FileStore of the Python client. Runs
+/// logged with this store can be browsed with `mlflow ui`.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    root: PathBuf,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExperimentMeta {
+    artifact_location: String,
+    #[serde(default)]
+    creation_time: Option<u64>,
+    #[serde(deserialize_with = "string_or_number")]
+    experiment_id: String,
+    #[serde(default)]
+    last_update_time: Option<u64>,
+    lifecycle_stage: LifecycleStage,
+    name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RunMeta {
+    artifact_uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deleted_time: Option<u64>,
+    #[serde(default)]
+    end_time: Option<u64>,
+    #[serde(default)]
+    entry_point_name: String,
+    #[serde(deserialize_with = "string_or_number")]
+    experiment_id: String,
+    lifecycle_stage: LifecycleStage,
+    run_id: String,
+    #[serde(default)]
+    run_name: Option<String>,
+    run_uuid: String,
+    #[serde(default)]
+    source_name: String,
+    #[serde(default = "source_type_local")]
+    source_type: u32,
+    #[serde(default)]
+    source_version: String,
+    #[serde(default)]
+    start_time: Option<u64>,
+    status: u32,
+    #[serde(default)]
+    tags: Vec<serde_yaml::Value>,
+    #[serde(default)]
+    user_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct InputMeta {
+    destination_id: String,
+    destination_type: String,
+    source_id: String,
+    source_type: String,
+    #[serde(default)]
+    tags: BTreeMap<String, String>,
+}
+
+fn source_type_local() -> u32 {
+    SOURCE_TYPE_LOCAL
+}
+
+fn string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        String(String),
+        Number(u64),
+    }
+    Ok(match StringOrNumber::deserialize(deserializer)? {
+        StringOrNumber::String(value) => value,
+        StringOrNumber::Number(value) => value.to_string(),
+    })
+}
+
+fn status_to_int(status: RunStatus) -> u32 {
+    match status {
+        RunStatus::Running => 1,
+        RunStatus::Scheduled => 2,
+        RunStatus::Finished => 3,
+        RunStatus::Failed => 4,
+        RunStatus::Killed => 5,
+    }
+}
+
+fn status_from_int(status: u32) -> RunStatus {
+    match status {
+        2 => RunStatus::Scheduled,
+        3 => RunStatus::Finished,
+        4 => RunStatus::Failed,
+        5 => RunStatus::Killed,
+        _ => RunStatus::Running,
+    }
+}
+
+/// Whether an experiment or run ID can be used as a directory name in the store, without escaping its root.
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty() && !id.contains('/') && !id.contains('\\') && !id.contains('.')
+}
+
+fn yaml_error<E: ErrorCode>(error: serde_yaml::Error) -> ClientError<E> {
+    ClientError::StoreError(std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+}
+
+fn read_yaml<T: serde::de::DeserializeOwned, E: ErrorCode>(
+    path: &Path,
+) -> Result<T, ClientError<E>> {
+    serde_yaml::from_str(&fs::read_to_string(path)?).map_err(yaml_error)
+}
+
+fn write_yaml<T: Serialize, E: ErrorCode>(path: &Path, value: &T) -> Result<(), ClientError<E>> {
+    fs::write(path, serde_yaml::to_string(value).map_err(yaml_error)?)?;
+    Ok(())
+}
+
+/// Read all files under `dir`, with their path relative to `dir` as key.
+fn read_key_files(dir: &Path) -> std::io::Result<Vec<(String, String)>> {
+    fn visit(dir: &Path, prefix: &str, files: &mut Vec<(String, String)>) -> std::io::Result<()> {
+        if !dir.is_dir() {
+            return Ok(());
+        }
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let key = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}/{}", prefix, name)
+            };
+            if entry.path().is_dir() {
+                visit(&entry.path(), &key, files)?;
+            } else {
+                files.push((key, fs::read_to_string(entry.path())?));
+            }
+        }
+        Ok(())
+    }
+    let mut files = vec![];
+    visit(dir, "", &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+fn write_key_file(dir: &Path, key: &str, value: &str) -> std::io::Result<()> {
+    let path = dir.join(key);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, value)
+}
+
+fn parse_metric_line(key: &str, line: &str) -> Option<Metric> {
+    let mut parts = line.split_whitespace();
+    let timestamp = parts.next()?.parse().ok()?;
+    let value = parts.next()?.parse().ok()?;
+    let step = parts.next().and_then(|step| step.parse().ok()).unwrap_or(0);
+    Some(Metric {
+        key: key.to_string(),
+        value,
+        timestamp,
+        step,
+    })
+}
+
+fn path_to_uri(path: &Path) -> String {
+    format!("file://{}", path.display())
+}
+
+impl FileStore {
+    /// New `FileStore` in the directory pointed to by `uri`, either a path or a `file:` URI. The directory and the
+    /// `Default` experiment are created if needed.
+    pub fn new(uri: &str) -> Result<FileStore, SetupError> {
+        let path = if uri.starts_with("file://") {
+            uri.trim_start_matches("file://")
+        } else {
+            uri.trim_start_matches("file:")
+        };
+        let path = if path.is_empty() { "mlruns" } else { path };
+        let invalid_store = |error: std::io::Error| SetupError::InvalidStore(error.to_string());
+        fs::create_dir_all(path).map_err(invalid_store)?;
+        let store = FileStore {
+            root: Path::new(path).canonicalize().map_err(invalid_store)?,
+        };
+        if store
+            .list_experiment_dirs(ViewType::All)
+            .map_err(invalid_store)?
+            .is_empty()
+        {
+            store
+                .write_new_experiment(DEFAULT_EXPERIMENT_ID, DEFAULT_EXPERIMENT_NAME, None)
+                .map_err(|error| SetupError::InvalidStore(format!("{}", error)))?;
+        }
+        Ok(store)
+    }
+
+    /// Root directory of the store.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn trash(&self) -> PathBuf {
+        self.root.join(TRASH_FOLDER_NAME)
+    }
+
+    fn list_experiment_dirs(&self, view_type: ViewType) -> std::io::Result<Vec<PathBuf>> {
+        let mut parents = vec![];
+        if view_type != ViewType::DeletedOnly {
+            parents.push(self.root.clone());
+        }
+        if view_type != ViewType::ActiveOnly {
+            parents.push(self.trash());
+        }
+        let mut dirs = vec![];
+        for parent in parents {
+            if !parent.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(parent)? {
+                let path = entry?.path();
+                if path.join(META_FILE_NAME).is_file() {
+                    dirs.push(path);
+                }
+            }
+        }
+        Ok(dirs)
+    }
+
+    fn experiment_dir<E: ErrorCode>(
+        &self,
+        experiment_id: &str,
+        not_found: E,
+    ) -> Result<PathBuf, ClientError<E>> {
+        [
+            self.root.join(experiment_id),
+            self.trash().join(experiment_id),
+        ]
+        .iter()
+        .find(|path| is_valid_id(experiment_id) && path.join(META_FILE_NAME).is_file())
+        .cloned()
+        .ok_or_else(|| api_error(not_found, experiment_not_found(experiment_id)))
+    }
+
+    fn read_experiment<E: ErrorCode>(&self, dir: &Path) -> Result<Experiment, ClientError<E>> {
+        let meta: ExperimentMeta = read_yaml(&dir.join(META_FILE_NAME))?;
+        let tags: Vec<ExperimentTag> = read_key_files(&dir.join("tags"))?
+            .into_iter()
+            .map(|(key, value)| ExperimentTag { key, value })
+            .collect();
+        Ok(Experiment {
+            experiment_id: meta.experiment_id,
+            name: meta.name,
+            artifact_location: meta.artifact_location,
+            lifecycle_stage: meta.lifecycle_stage,
+            last_update_time: meta.last_update_time,
+            creation_time: meta.creation_time,
+            tags: if tags.is_empty() { None } else { Some(tags) },
+        })
+    }
+
+    fn write_new_experiment(
+        &self,
+        experiment_id: &str,
+        name: &str,
+        artifact_location: Option<&str>,
+    ) -> Result<(), ClientError<CreateExperimentErrorCode>> {
+        let dir = self.root.join(experiment_id);
+        fs::create_dir(&dir)?;
+        let now = now();
+        write_yaml(
+            &dir.join(META_FILE_NAME),
+            &ExperimentMeta {
+                artifact_location: artifact_location
+                    .map(str::to_string)
+                    .unwrap_or_else(|| path_to_uri(&dir)),
+                creation_time: Some(now),
+                experiment_id: experiment_id.to_string(),
+                last_update_time: Some(now),
+                lifecycle_stage: LifecycleStage::Active,
+                name: name.to_string(),
+            },
+        )
+    }
+
+    fn update_experiment_meta<F: FnOnce(&mut ExperimentMeta)>(
+        &self,
+        dir: &Path,
+        update: F,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let mut meta: ExperimentMeta = read_yaml(&dir.join(META_FILE_NAME))?;
+        update(&mut meta);
+        meta.last_update_time = Some(now());
+        write_yaml(&dir.join(META_FILE_NAME), &meta)
+    }
+
+    /// Create an experiment with a name. Returns the ID of the newly created experiment. Validates that another
+    /// experiment with the same name does not already exist and fails if another experiment with the same name
+    /// already exists.
+    pub fn create_experiment(
+        &self,
+        name: &str,
+        artifact_location: Option<&str>,
+    ) -> Result<String, ClientError<CreateExperimentErrorCode>> {
+        if name.is_empty() {
+            return Err(api_error(
+                CreateExperimentErrorCode::InvalidParameterValue,
+                "Invalid experiment name: ''",
+            ));
+        }
+        for dir in self.list_experiment_dirs(ViewType::All)? {
+            if self
+                .read_experiment::<CreateExperimentErrorCode>(&dir)?
+                .name
+                == name
+            {
+                return Err(api_error(
+                    CreateExperimentErrorCode::ResourceAlreadyExists,
+                    experiment_already_exists(name),
+                ));
+            }
+        }
+        let mut experiment_id = self
+            .list_experiment_dirs(ViewType::All)?
+            .iter()
+            .filter_map(|dir| dir.file_name()?.to_str()?.parse::<u64>().ok())
+            .max()
+            .map(|id| id + 1)
+            .unwrap_or(0);
+        // another process may create an experiment at the same time
+        loop {
+            match self.write_new_experiment(&experiment_id.to_string(), name, artifact_location) {
+                Err(ClientError::StoreError(ref error))
+                    if error.kind() == std::io::ErrorKind::AlreadyExists =>
+                {
+                    experiment_id += 1
+                }
+                result => return result.map(|_| experiment_id.to_string()),
+            }
+        }
+    }
+
+    /// Get a list of all experiments.
+    pub fn list_experiments(
+        &self,
+        view_type: Option<ViewType>,
+    ) -> Result<Vec<Experiment>, ClientError<ListExperimentsErrorCode>> {
+        let mut experiments = self
+            .list_experiment_dirs(view_type.unwrap_or(ViewType::ActiveOnly))?
+            .iter()
+            .map(|dir| self.read_experiment(dir))
+            .collect::<Result<Vec<_>, _>>()?;
+        experiments.sort_by_key(|experiment| {
+            (
+                experiment.experiment_id.parse::<u64>().unwrap_or(u64::MAX),
+                experiment.experiment_id.clone(),
+            )
+        });
+        Ok(experiments)
+    }
+
+    /// Get metadata for an experiment. This method works on deleted experiments.
+    pub fn get_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<Experiment, ClientError<GetExperimentErrorCode>> {
+        let dir =
+            self.experiment_dir(experiment_id, GetExperimentErrorCode::ResourceDoesNotExist)?;
+        self.read_experiment(&dir)
+    }
+
+    /// Get metadata for an experiment. This endpoint will return deleted experiments, but prefers the active
+    /// experiment if an active and deleted experiment share the same name.
+    pub fn get_experiment_by_name(
+        &self,
+        experiment_name: &str,
+    ) -> Result<Experiment, ClientError<GetExperimentErrorCode>> {
+        let mut found = None;
+        for dir in self.list_experiment_dirs(ViewType::All)? {
+            let experiment = self.read_experiment::<GetExperimentErrorCode>(&dir)?;
+            if experiment.name == experiment_name
+                && (found.is_none() || experiment.lifecycle_stage == LifecycleStage::Active)
+            {
+                found = Some(experiment);
+            }
+        }
+        found.ok_or_else(|| {
+            api_error(
+                GetExperimentErrorCode::ResourceDoesNotExist,
+                experiment_name_not_found(experiment_name),
+            )
+        })
+    }
+
+    /// Mark an experiment and associated metadata, runs, metrics, params, and tags for deletion. The experiment is
+    /// moved to the `.trash` directory of the store.
+    pub fn delete_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let dir = self.root.join(experiment_id);
+        if !is_valid_id(experiment_id) || !dir.join(META_FILE_NAME).is_file() {
+            return Err(api_error(
+                GetExperimentErrorCode::ResourceDoesNotExist,
+                format!("Could not find active experiment with ID {}", experiment_id),
+            ));
+        }
+        self.update_experiment_meta(&dir, |meta| meta.lifecycle_stage = LifecycleStage::Deleted)?;
+        fs::create_dir_all(self.trash())?;
+        fs::rename(&dir, self.trash().join(experiment_id))?;
+        Ok(())
+    }
+
+    /// Restore an experiment marked for deletion.
+    pub fn restore_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let dir = self.trash().join(experiment_id);
+        if !is_valid_id(experiment_id) || !dir.join(META_FILE_NAME).is_file() {
+            return Err(api_error(
+                GetExperimentErrorCode::ResourceDoesNotExist,
+                format!(
+                    "Could not find deleted experiment with ID {}",
+                    experiment_id
+                ),
+            ));
+        }
+        if self.root.join(experiment_id).exists() {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                format!(
+                    "Cannot restore experiment with ID {}, an active experiment has this ID",
+                    experiment_id
+                ),
+            ));
+        }
+        self.update_experiment_meta(&dir, |meta| meta.lifecycle_stage = LifecycleStage::Active)?;
+        fs::rename(&dir, self.root.join(experiment_id))?;
+        Ok(())
+    }
+
+    /// Permanently remove a deleted experiment from the `.trash` directory, with its runs.
+    pub fn purge_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let dir = self.trash().join(experiment_id);
+        if !is_valid_id(experiment_id) || !dir.join(META_FILE_NAME).is_file() {
+            return Err(api_error(
+                GetExperimentErrorCode::ResourceDoesNotExist,
+                format!(
+                    "Could not find deleted experiment with ID {}",
+                    experiment_id
+                ),
+            ));
+        }
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    /// Update experiment metadata.
+    pub fn update_experiment(
+        &self,
+        experiment_id: &str,
+        new_name: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let dir =
+            self.experiment_dir(experiment_id, GetExperimentErrorCode::ResourceDoesNotExist)?;
+        let experiment = self.read_experiment::<GetExperimentErrorCode>(&dir)?;
+        if experiment.lifecycle_stage != LifecycleStage::Active {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                "Cannot rename a non-active experiment.",
+            ));
+        }
+        if new_name.is_empty() {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                "Invalid experiment name: ''",
+            ));
+        }
+        for other in self.list_experiment_dirs(ViewType::All)? {
+            let other = self.read_experiment::<GetExperimentErrorCode>(&other)?;
+            if other.name == new_name && other.experiment_id != experiment_id {
+                return Err(api_error(
+                    GetExperimentErrorCode::InvalidParameterValue,
+                    experiment_already_exists(new_name),
+                ));
+            }
+        }
+        self.update_experiment_meta(&dir, |meta| meta.name = new_name.to_string())
+    }
+
+    /// Set a tag on an experiment. Experiment tags are metadata that can be updated.
+    pub fn set_experiment_tag(
+        &self,
+        experiment_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        validate_key("tag", key, GetExperimentErrorCode::InvalidParameterValue)?;
+        let dir =
+            self.experiment_dir(experiment_id, GetExperimentErrorCode::ResourceDoesNotExist)?;
+        if self
+            .read_experiment::<GetExperimentErrorCode>(&dir)?
+            .lifecycle_stage
+            != LifecycleStage::Active
+        {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                "The experiment must be in the 'active' state.",
+            ));
+        }
+        write_key_file(&dir.join("tags"), key, value)?;
+        Ok(())
+    }
+
+    fn run_dir(&self, run_id: &str) -> Result<PathBuf, ClientError<GetExperimentErrorCode>> {
+        if is_valid_id(run_id) {
+            for experiment_dir in self.list_experiment_dirs(ViewType::All)? {
+                let dir = experiment_dir.join(run_id);
+                if dir.join(META_FILE_NAME).is_file() {
+                    return Ok(dir);
+                }
+            }
+        }
+        Err(api_error(
+            GetExperimentErrorCode::ResourceDoesNotExist,
+            run_not_found(run_id),
+        ))
+    }
+
+    fn read_run_meta(
+        &self,
+        run_id: &str,
+    ) -> Result<(PathBuf, RunMeta), ClientError<GetExperimentErrorCode>> {
+        let dir = self.run_dir(run_id)?;
+        let meta = read_yaml(&dir.join(META_FILE_NAME))?;
+        Ok((dir, meta))
+    }
+
+    fn active_run_dir(&self, run_id: &str) -> Result<PathBuf, ClientError<GetExperimentErrorCode>> {
+        let (dir, meta) = self.read_run_meta(run_id)?;
+        if meta.lifecycle_stage != LifecycleStage::Active {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                format!("The run {} must be in the 'active' state.", run_id),
+            ));
+        }
+        Ok(dir)
+    }
+
+    fn read_run_info(meta: &RunMeta) -> RunInfo {
+        RunInfo {
+            run_id: meta.run_id.clone(),
+            run_name: meta.run_name.clone(),
+            experiment_id: meta.experiment_id.clone(),
+            status: status_from_int(meta.status),
+            start_time: meta.start_time.unwrap_or(0),
+            end_time: meta.end_time,
+            artifact_uri: meta.artifact_uri.clone(),
+            lifecycle_stage: meta.lifecycle_stage,
+        }
+    }
+
+    fn read_metric_history(dir: &Path, key: &str) -> std::io::Result<Vec<Metric>> {
+        let path = dir.join("metrics").join(key);
+        if !path.is_file() {
+            return Ok(vec![]);
+        }
+        Ok(fs::read_to_string(path)?
+            .lines()
+            .filter_map(|line| parse_metric_line(key, line))
+            .collect())
+    }
+
+    fn read_run(&self, dir: &Path) -> Result<Run, ClientError<GetExperimentErrorCode>> {
+        let meta: RunMeta = read_yaml(&dir.join(META_FILE_NAME))?;
+        let metrics = read_key_files(&dir.join("metrics"))?
+            .into_iter()
+            .filter_map(|(key, content)| {
+                latest_metric(
+                    content
+                        .lines()
+                        .filter_map(|line| parse_metric_line(&key, line)),
+                )
+            })
+            .collect();
+        let params = read_key_files(&dir.join("params"))?
+            .into_iter()
+            .map(|(key, value)| Param { key, value })
+            .collect();
+        let tags = read_key_files(&dir.join("tags"))?
+            .into_iter()
+            .map(|(key, value)| RunTag { key, value })
+            .collect();
+        let inputs = self.read_run_inputs(dir, &meta.experiment_id)?;
+        Ok(Run {
+            info: FileStore::read_run_info(&meta),
+            data: Some(RunData {
+                metrics,
+                params,
+                tags,
+            }),
+            inputs: Some(inputs),
+        })
+    }
+
+    fn read_run_inputs(
+        &self,
+        dir: &Path,
+        experiment_id: &str,
+    ) -> Result<RunInputs, ClientError<GetExperimentErrorCode>> {
+        let inputs_dir = dir.join("inputs");
+        let mut dataset_inputs = vec![];
+        if inputs_dir.is_dir() {
+            let experiment_dir =
+                self.experiment_dir(experiment_id, GetExperimentErrorCode::ResourceDoesNotExist)?;
+            let mut input_dirs = fs::read_dir(inputs_dir)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            input_dirs.sort();
+            for input_dir in input_dirs {
+                let input: InputMeta = read_yaml(&input_dir.join(META_FILE_NAME))?;
+                let dataset: Dataset = read_yaml(
+                    &experiment_dir
+                        .join("datasets")
+                        .join(&input.source_id)
+                        .join(META_FILE_NAME),
+                )?;
+                dataset_inputs.push(DatasetInput {
+                    tags: input
+                        .tags
+                        .into_iter()
+                        .map(|(key, value)| InputTag { key, value })
+                        .collect(),
+                    dataset,
+                });
+            }
+        }
+        Ok(RunInputs { dataset_inputs })
+    }
+
+    fn write_run_meta(
+        dir: &Path,
+        meta: &RunMeta,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        write_yaml(&dir.join(META_FILE_NAME), meta)
+    }
+
+    /// Create a new run within an experiment. A run is usually a single execution of a machine learning or data ETL
+    /// pipeline. MLflow uses runs to track `Param`, `Metric`, and `RunTag` associated with a single execution.
+    pub fn create_run(
+        &self,
+        experiment_id: &str,
+        start_time: Option<u64>,
+        tags: Option<Vec<RunTag>>,
+        run_name: Option<&str>,
+    ) -> Result<Run, ClientError<GetExperimentErrorCode>> {
+        let experiment_dir =
+            self.experiment_dir(experiment_id, GetExperimentErrorCode::ResourceDoesNotExist)?;
+        let experiment = self.read_experiment::<GetExperimentErrorCode>(&experiment_dir)?;
+        if experiment.lifecycle_stage != LifecycleStage::Active {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                format!(
+                    "Could not create run under non-active experiment with ID {}.",
+                    experiment_id
+                ),
+            ));
+        }
+        let tags = tags.unwrap_or_default();
+        for tag in &tags {
+            validate_key(
+                "tag",
+                &tag.key,
+                GetExperimentErrorCode::InvalidParameterValue,
+            )?;
+        }
+        let run_name = run_name.map(str::to_string).or_else(|| {
+            tags.iter()
+                .find(|tag| tag.key == "mlflow.runName")
+                .map(|tag| tag.value.clone())
+        });
+        let run_id = uuid::Uuid::new_v4().to_simple().to_string();
+        let dir = experiment_dir.join(&run_id);
+        fs::create_dir_all(dir.join("artifacts"))?;
+        for sub_dir in &["metrics", "params", "tags"] {
+            fs::create_dir_all(dir.join(sub_dir))?;
+        }
+        let meta = RunMeta {
+            artifact_uri: format!(
+                "{}/{}/artifacts",
+                experiment.artifact_location.trim_end_matches('/'),
+                run_id
+            ),
+            deleted_time: None,
+            end_time: None,
+            entry_point_name: String::new(),
+            experiment_id: experiment_id.to_string(),
+            lifecycle_stage: LifecycleStage::Active,
+            run_id: run_id.clone(),
+            run_name: run_name.clone(),
+            run_uuid: run_id.clone(),
+            source_name: String::new(),
+            source_type: SOURCE_TYPE_LOCAL,
+            source_version: String::new(),
+            start_time: Some(start_time.unwrap_or_else(now)),
+            status: status_to_int(RunStatus::Running),
+            tags: vec![],
+            user_id: tags
+                .iter()
+                .find(|tag| tag.key == "mlflow.user")
+                .map(|tag| tag.value.clone())
+                .unwrap_or_else(|| "unknown".to_string()),
+        };
+        FileStore::write_run_meta(&dir, &meta)?;
+        for tag in &tags {
+            write_key_file(&dir.join("tags"), &tag.key, &tag.value)?;
+        }
+        if let Some(run_name) = run_name {
+            write_key_file(&dir.join("tags"), "mlflow.runName", &run_name)?;
+        }
+        self.read_run(&dir)
+    }
+
+    /// Mark a run for deletion. The deletion time is recorded, like the Python client does, for
+    /// [`FileStore::get_run_deleted_time`].
+    pub fn delete_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let (dir, mut meta) = self.read_run_meta(run_id)?;
+        meta.lifecycle_stage = LifecycleStage::Deleted;
+        meta.deleted_time = Some(now());
+        FileStore::write_run_meta(&dir, &meta)
+    }
+
+    /// Restore a deleted run.
+    pub fn restore_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let (dir, mut meta) = self.read_run_meta(run_id)?;
+        meta.lifecycle_stage = LifecycleStage::Active;
+        meta.deleted_time = None;
+        FileStore::write_run_meta(&dir, &meta)
+    }
+
+    /// Time at which a deleted run was deleted, `None` for active runs and runs deleted before it was recorded.
+    pub fn get_run_deleted_time(
+        &self,
+        run_id: &str,
+    ) -> Result<Option<u64>, ClientError<GetExperimentErrorCode>> {
+        let (_, meta) = self.read_run_meta(run_id)?;
+        Ok(meta.deleted_time)
+    }
+
+    /// Permanently remove a deleted run, with its metadata, metrics, params and tags. Its artifacts are not removed
+    /// if they are stored outside of the store.
+    pub fn purge_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let (dir, meta) = self.read_run_meta(run_id)?;
+        if meta.lifecycle_stage != LifecycleStage::Deleted {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                format!("The run {} must be in the 'deleted' state.", run_id),
+            ));
+        }
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+
+    /// Get metadata, metrics, params, and tags for a run. In the case where multiple metrics with the same key are
+    /// logged for a run, return only the value with the latest step and timestamp.
+    pub fn get_run(&self, run_id: &str) -> Result<Run, ClientError<GetExperimentErrorCode>> {
+        let dir = self.run_dir(run_id)?;
+        self.read_run(&dir)
+    }
+
+    /// Update run metadata: its status, end time, and name if specified.
+    pub fn update_run(
+        &self,
+        run_id: &str,
+        status: RunStatus,
+        end_time: Option<u64>,
+        run_name: Option<&str>,
+    ) -> Result<RunInfo, ClientError<GetExperimentErrorCode>> {
+        let dir = self.active_run_dir(run_id)?;
+        let mut meta: RunMeta = read_yaml(&dir.join(META_FILE_NAME))?;
+        meta.status = status_to_int(status);
+        if end_time.is_some() {
+            meta.end_time = end_time;
+        }
+        if let Some(run_name) = run_name {
+            meta.run_name = Some(run_name.to_string());
+            write_key_file(&dir.join("tags"), "mlflow.runName", run_name)?;
+        }
+        FileStore::write_run_meta(&dir, &meta)?;
+        Ok(FileStore::read_run_info(&meta))
+    }
+
+    /// Search for runs that satisfy expressions. Search expressions can use Metric and Param keys.
+    pub fn search_runs(
+        &self,
+        experiment_ids: &[&str],
+        filter: Option<&str>,
+        run_view_type: Option<ViewType>,
+        max_results: Option<u32>,
+        order_by: Option<&[&str]>,
+        page_token: Option<&str>,
+    ) -> Result<(Vec<Run>, Option<String>), ClientError<GetExperimentErrorCode>> {
+        let invalid =
+            |message: String| api_error(GetExperimentErrorCode::InvalidParameterValue, message);
+        let search_filter = SearchFilter::parse(filter.unwrap_or("")).map_err(invalid)?;
+        let view_type = run_view_type.unwrap_or(ViewType::ActiveOnly);
+        let mut runs = vec![];
+        for experiment_id in experiment_ids {
+            let experiment_dir = match self
+                .experiment_dir(experiment_id, GetExperimentErrorCode::ResourceDoesNotExist)
+            {
+                Ok(dir) => dir,
+                Err(_) => continue,
+            };
+            for entry in fs::read_dir(experiment_dir)? {
+                let dir = entry?.path();
+                if !dir.join(META_FILE_NAME).is_file() {
+                    continue;
+                }
+                let run = self.read_run(&dir)?;
+                let visible = match (view_type, run.info.lifecycle_stage) {
+                    (ViewType::All, _) => true,
+                    (ViewType::ActiveOnly, stage) => stage == LifecycleStage::Active,
+                    (ViewType::DeletedOnly, stage) => stage == LifecycleStage::Deleted,
+                };
+                if visible && search_filter.matches(&run) {
+                    runs.push(run);
+                }
+            }
+        }
+        sort_runs(&mut runs, order_by.unwrap_or(&[])).map_err(invalid)?;
+        paginate(runs, max_results, page_token).map_err(invalid)
+    }
+
+    /// Set a tag on a run. Tags are run metadata that can be updated during a run and after a run completes.
+    pub fn set_run_tag(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        validate_key("tag", key, GetExperimentErrorCode::InvalidParameterValue)?;
+        let dir = self.active_run_dir(run_id)?;
+        write_key_file(&dir.join("tags"), key, value)?;
+        if key == "mlflow.runName" {
+            let mut meta: RunMeta = read_yaml(&dir.join(META_FILE_NAME))?;
+            meta.run_name = Some(value.to_string());
+            FileStore::write_run_meta(&dir, &meta)?;
+        }
+        Ok(())
+    }
+
+    /// Delete a tag on a run. Tags are run metadata that can be updated during a run and after a run completes.
+    pub fn delete_run_tag(
+        &self,
+        run_id: &str,
+        key: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        validate_key("tag", key, GetExperimentErrorCode::InvalidParameterValue)?;
+        let dir = self.active_run_dir(run_id)?;
+        let path = dir.join("tags").join(key);
+        if !path.is_file() {
+            return Err(api_error(
+                GetExperimentErrorCode::ResourceDoesNotExist,
+                format!("No tag with name: {} in run with id {}", key, run_id),
+            ));
+        }
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    fn append_metric(dir: &Path, metric: &Metric) -> std::io::Result<()> {
+        let path = dir.join("metrics").join(&metric.key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(
+            file,
+            "{} {} {}",
+            metric.timestamp, metric.value, metric.step
+        )
+    }
+
+    fn write_param(
+        dir: &Path,
+        run_id: &str,
+        param: &Param,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        validate_key(
+            "param",
+            &param.key,
+            GetExperimentErrorCode::InvalidParameterValue,
+        )?;
+        let path = dir.join("params").join(&param.key);
+        if path.is_file() {
+            let existing = fs::read_to_string(&path)?;
+            if existing != param.value {
+                return Err(api_error(
+                    GetExperimentErrorCode::InvalidParameterValue,
+                    format!(
+                        "Changing param values is not allowed. Param with key='{}' was already logged with value='{}' for run ID='{}'. Attempted logging new value '{}'.",
+                        param.key, existing, run_id, param.value
+                    ),
+                ));
+            }
+            return Ok(());
+        }
+        write_key_file(&dir.join("params"), &param.key, &param.value)?;
+        Ok(())
+    }
+
+    /// Log a metric for a run. A metric is a key-value pair (string key, float value) with an associated timestamp.
+    pub fn log_metric(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: f32,
+        timestamp: u64,
+        step: Option<u64>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        validate_key("metric", key, GetExperimentErrorCode::InvalidParameterValue)?;
+        let dir = self.active_run_dir(run_id)?;
+        FileStore::append_metric(
+            &dir,
+            &Metric {
+                key: key.to_string(),
+                value,
+                timestamp,
+                step: step.unwrap_or(0),
+            },
+        )?;
+        Ok(())
+    }
+
+    /// Get a list of all values for the specified metric for a given run.
+    pub fn get_metric_history(
+        &self,
+        run_id: &str,
+        metric_key: &str,
+        max_results: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<(Vec<Metric>, Option<String>), ClientError<GetExperimentErrorCode>> {
+        validate_key(
+            "metric",
+            metric_key,
+            GetExperimentErrorCode::InvalidParameterValue,
+        )?;
+        let dir = self.run_dir(run_id)?;
+        let history = FileStore::read_metric_history(&dir, metric_key)?;
+        paginate(history, max_results.or(Some(u32::MAX)), page_token)
+            .map_err(|message| api_error(GetExperimentErrorCode::InvalidParameterValue, message))
+    }
+
+    /// Get the values of a metric for several runs, between `start_step` and `end_step` if specified. When there are
+    /// more than `max_results` steps, they are sampled evenly, keeping the first and last ones.
+    pub fn get_metric_history_bulk_interval(
+        &self,
+        run_ids: &[&str],
+        metric_key: &str,
+        start_step: Option<u64>,
+        end_step: Option<u64>,
+        max_results: Option<u32>,
+    ) -> Result<Vec<MetricWithRunId>, ClientError<GetExperimentErrorCode>> {
+        validate_key(
+            "metric",
+            metric_key,
+            GetExperimentErrorCode::InvalidParameterValue,
+        )?;
+        let max_results = max_results.unwrap_or(BULK_INTERVAL_MAX_RESULTS_DEFAULT) as usize;
+        let mut metrics = vec![];
+        for run_id in run_ids {
+            let dir = self.run_dir(run_id)?;
+            let history = sample_interval(
+                FileStore::read_metric_history(&dir, metric_key)?,
+                start_step,
+                end_step,
+                max_results,
+            );
+            metrics.extend(history.into_iter().map(|metric| MetricWithRunId {
+                metric,
+                run_id: run_id.to_string(),
+            }));
+        }
+        Ok(metrics)
+    }
+
+    /// Log a param used for a run. A param is a key-value pair (string key, string value). A param can be logged only
+    /// once for a run.
+    pub fn log_param(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let dir = self.active_run_dir(run_id)?;
+        FileStore::write_param(
+            &dir,
+            run_id,
+            &Param {
+                key: key.to_string(),
+                value: value.to_string(),
+            },
+        )
+    }
+
+    /// Log a batch of metrics, params, and tags for a run.
+    pub fn log_batch(
+        &self,
+        run_id: &str,
+        metrics: Option<&[&Metric]>,
+        params: Option<&[&Param]>,
+        tags: Option<&[&RunTag]>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let dir = self.active_run_dir(run_id)?;
+        for param in params.unwrap_or(&[]) {
+            FileStore::write_param(&dir, run_id, param)?;
+        }
+        for metric in metrics.unwrap_or(&[]) {
+            validate_key(
+                "metric",
+                &metric.key,
+                GetExperimentErrorCode::InvalidParameterValue,
+            )?;
+            FileStore::append_metric(&dir, metric)?;
+        }
+        for tag in tags.unwrap_or(&[]) {
+            self.set_run_tag(run_id, &tag.key, &tag.value)?;
+        }
+        Ok(())
+    }
+
+    /// Log the datasets used by a run.
+    pub fn log_inputs(
+        &self,
+        run_id: &str,
+        datasets: Option<&[DatasetInput]>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let (dir, meta) = self.read_run_meta(run_id)?;
+        let experiment_dir = self.experiment_dir(
+            &meta.experiment_id,
+            GetExperimentErrorCode::ResourceDoesNotExist,
+        )?;
+        for input in datasets.unwrap_or(&[]) {
+            let dataset_id = format!(
+                "{:x}",
+                md5::compute(format!("{}{}", input.dataset.name, input.dataset.digest))
+            );
+            let dataset_dir = experiment_dir.join("datasets").join(&dataset_id);
+            if !dataset_dir.is_dir() {
+                fs::create_dir_all(&dataset_dir)?;
+                write_yaml(&dataset_dir.join(META_FILE_NAME), &input.dataset)?;
+            }
+            let tags: BTreeMap<String, String> = input
+                .tags
+                .iter()
+                .map(|tag| (tag.key.clone(), tag.value.clone()))
+                .collect();
+            let input_id = format!(
+                "{:x}",
+                md5::compute(format!("{}{}{:?}", dataset_id, run_id, tags))
+            );
+            let input_dir = dir.join("inputs").join(input_id);
+            fs::create_dir_all(&input_dir)?;
+            write_yaml(
+                &input_dir.join(META_FILE_NAME),
+                &InputMeta {
+                    destination_id: run_id.to_string(),
+                    destination_type: "RUN".to_string(),
+                    source_id: dataset_id,
+                    source_type: "DATASET".to_string(),
+                    tags,
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    /// List artifacts for a run. Takes an optional artifact_path prefix which if specified, the response contains only
+    /// artifacts with the specified prefix.
+    pub fn list_artifacts(
+        &self,
+        run_id: &str,
+        path: Option<&str>,
+    ) -> Result<(String, Vec<FileInfo>), ClientError<GetExperimentErrorCode>> {
+        let (_, meta) = self.read_run_meta(run_id)?;
+        let root = meta.artifact_uri.trim_start_matches("file://");
+        if root.contains(':') {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                format!("Artifacts at '{}' can not be listed", meta.artifact_uri),
+            ));
+        }
+        let dir = match path {
+            Some(path) => {
+                validate_key(
+                    "artifact",
+                    path,
+                    GetExperimentErrorCode::InvalidParameterValue,
+                )?;
+                Path::new(root).join(path)
+            }
+            None => PathBuf::from(root),
+        };
+        let files = crate::artifacts::list_local_artifacts(&dir, path)?;
+        Ok((meta.artifact_uri, files))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use spectral::prelude::*;
+
+    use super::{FileStore, META_FILE_NAME};
+    use crate::errors::{ClientError, CreateExperimentErrorCode, GetExperimentErrorCode};
+    use crate::{LifecycleStage, Metric, Param, RunStatus, RunTag, ViewType};
+
+    fn error_code<T, E: crate::errors::ErrorCode + Copy>(
+        result: Result<T, ClientError<E>>,
+    ) -> Option<E> {
+        match result {
+            Err(ClientError::ApiError { error_code, .. }) => Some(error_code),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn can_create_default_experiment() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(&format!("file://{}", dir.path().display())).unwrap();
+
+        let experiments = store.list_experiments(None).unwrap();
+        assert_that!(experiments).has_length(1);
+        assert_that!(experiments[0].experiment_id).is_equal_to("0".to_string());
+        assert_that!(experiments[0].name).is_equal_to("Default".to_string());
+        assert_that!(dir.path().join("0").join("meta.yaml").is_file()).is_true();
+    }
+
+    #[test]
+    fn can_manage_experiments() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path().to_str().unwrap()).unwrap();
+
+        let id = store.create_experiment("exp", None).unwrap();
+        assert_that!(id).is_equal_to("1".to_string());
+        assert_that!(error_code(store.create_experiment("exp", None)))
+            .is_equal_to(Some(CreateExperimentErrorCode::ResourceAlreadyExists));
+
+        store.set_experiment_tag(&id, "team", "ml").unwrap();
+        store.update_experiment(&id, "renamed").unwrap();
+        let experiment = store.get_experiment_by_name("renamed").unwrap();
+        assert_that!(experiment.experiment_id).is_equal_to(&id);
+        assert_that!(experiment.tags).is_some().has_length(1);
+
+        store.delete_experiment(&id).unwrap();
+        assert_that!(store.list_experiments(None).unwrap()).has_length(1);
+        assert_that!(store.list_experiments(Some(ViewType::DeletedOnly)).unwrap()).has_length(1);
+        assert_that!(store.get_experiment(&id).unwrap().lifecycle_stage)
+            .is_equal_to(LifecycleStage::Deleted);
+        assert_that!(dir.path().join(".trash").join(&id).is_dir()).is_true();
+
+        store.restore_experiment(&id).unwrap();
+        assert_that!(store.list_experiments(None).unwrap()).has_length(2);
+        assert_that!(error_code(store.get_experiment("42")))
+            .is_equal_to(Some(GetExperimentErrorCode::ResourceDoesNotExist));
+        let outside = format!("../{}/0", dir.path().file_name().unwrap().to_str().unwrap());
+        assert_that!(error_code(store.get_experiment(&outside)))
+            .is_equal_to(Some(GetExperimentErrorCode::ResourceDoesNotExist));
+    }
+
+    #[test]
+    fn can_purge_deleted_runs_and_experiments() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path().to_str().unwrap()).unwrap();
+
+        let run = store.create_run("0", None, None, None).unwrap();
+        let run_id = run.info.run_id;
+        assert_that!(store.get_run_deleted_time(&run_id).unwrap()).is_none();
+        assert_that!(error_code(store.purge_run(&run_id)))
+            .is_equal_to(Some(GetExperimentErrorCode::InvalidParameterValue));
+        store.delete_run(&run_id).unwrap();
+        assert_that!(store.get_run_deleted_time(&run_id).unwrap()).is_some();
+        store.purge_run(&run_id).unwrap();
+        assert_that!(error_code(store.get_run(&run_id)))
+            .is_equal_to(Some(GetExperimentErrorCode::ResourceDoesNotExist));
+
+        let id = store.create_experiment("exp", None).unwrap();
+        assert_that!(error_code(store.purge_experiment(&id)))
+            .is_equal_to(Some(GetExperimentErrorCode::ResourceDoesNotExist));
+        store.delete_experiment(&id).unwrap();
+        store.purge_experiment(&id).unwrap();
+        assert_that!(store.list_experiments(Some(ViewType::All)).unwrap()).has_length(1);
+        assert_that!(dir.path().join(".trash").join(&id).exists()).is_false();
+    }
+
+    #[test]
+    fn should_reject_experiment_ids_outside_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path().join("mlruns").to_str().unwrap()).unwrap();
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(outside.join("b")).unwrap();
+        fs::write(outside.join(META_FILE_NAME), "").unwrap();
+        fs::write(outside.join("b").join(META_FILE_NAME), "").unwrap();
+
+        for id in &["../outside", "../../outside", "outside/b", "a/b", ".", ""] {
+            assert_that!(error_code(store.delete_experiment(id)))
+                .named(id)
+                .is_equal_to(Some(GetExperimentErrorCode::ResourceDoesNotExist));
+            assert_that!(error_code(store.restore_experiment(id)))
+                .named(id)
+                .is_equal_to(Some(GetExperimentErrorCode::ResourceDoesNotExist));
+        }
+        assert_that!(outside.join(META_FILE_NAME).is_file()).is_true();
+        assert_that!(dir.path().join("mlruns").join(".trash").exists()).is_false();
+    }
+
+    #[test]
+    fn can_log_run_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path().to_str().unwrap()).unwrap();
+
+        let run = store
+            .create_run(
+                "0",
+                Some(1000),
+                Some(vec![RunTag {
+                    key: "mlflow.user".to_string(),
+                    value: "me".to_string(),
+                }]),
+                Some("my-run"),
+            )
+            .unwrap();
+        let run_id = run.info.run_id;
+        assert_that!(run.info.status).is_equal_to(RunStatus::Running);
+
+        store.log_param(&run_id, "alpha", "0.5").unwrap();
+        store.log_param(&run_id, "alpha", "0.5").unwrap();
+        assert_that!(error_code(store.log_param(&run_id, "alpha", "0.6")))
+            .is_equal_to(Some(GetExperimentErrorCode::InvalidParameterValue));
+        assert_that!(error_code(store.log_param(&run_id, "../alpha", "0.6")))
+            .is_equal_to(Some(GetExperimentErrorCode::InvalidParameterValue));
+
+        store
+            .log_metric(&run_id, "loss", 2.0, 1001, Some(0))
+            .unwrap();
+        store
+            .log_metric(&run_id, "loss", 1.0, 1002, Some(1))
+            .unwrap();
+        store
+            .log_batch(
+                &run_id,
+                Some(&[&Metric {
+                    key: "nested/acc".to_string(),
+                    value: 0.5,
+                    timestamp: 1003,
+                    step: 0,
+                }]),
+                Some(&[&Param {
+                    key: "beta".to_string(),
+                    value: "1".to_string(),
+                }]),
+                Some(&[&RunTag {
+                    key: "note".to_string(),
+                    value: "multi\nline".to_string(),
+                }]),
+            )
+            .unwrap();
+        assert_that!(fs::read_to_string(
+            dir.path()
+                .join("0")
+                .join(&run_id)
+                .join("metrics")
+                .join("loss")
+        ))
+        .is_ok()
+        .is_equal_to("1001 2 0\n1002 1 1\n".to_string());
+
+        let history = store
+            .get_metric_history(&run_id, "loss", None, None)
+            .unwrap();
+        assert_that!(history.0).has_length(2);
+        let page = store
+            .get_metric_history(&run_id, "loss", Some(1), None)
+            .unwrap();
+        assert_that!(page.0).has_length(1);
+        assert_that!(page.1).is_some();
+
+        store
+            .update_run(&run_id, RunStatus::Finished, Some(2000), None)
+            .unwrap();
+        let run = store.get_run(&run_id).unwrap();
+        assert_that!(run.info.status).is_equal_to(RunStatus::Finished);
+        assert_that!(run.info.end_time).is_equal_to(Some(2000));
+        assert_that!(run.run_name()).is_equal_to(Some("my-run"));
+        let data = run.data.unwrap();
+        assert_that!(data.metrics).contains(Metric {
+            key: "loss".to_string(),
+            value: 1.0,
+            timestamp: 1002,
+            step: 1,
+        });
+        assert_that!(data.metrics).has_length(2);
+        assert_that!(data.params).has_length(2);
+        assert_that!(data.tags).contains(RunTag {
+            key: "note".to_string(),
+            value: "multi\nline".to_string(),
+        });
+
+        store.delete_run_tag(&run_id, "note").unwrap();
+        assert_that!(error_code(store.delete_run_tag(&run_id, "note")))
+            .is_equal_to(Some(GetExperimentErrorCode::ResourceDoesNotExist));
+    }
+
+    #[test]
+    fn can_search_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path().to_str().unwrap()).unwrap();
+
+        for (index, model) in ["tree", "linear", "tree"].iter().enumerate() {
+            let run_id = store
+                .create_run("0", Some(index as u64), None, None)
+                .unwrap()
+                .info
+                .run_id;
+            store.log_param(&run_id, "model", model).unwrap();
+            store
+                .log_metric(&run_id, "rmse", index as f32, 0, None)
+                .unwrap();
+        }
+
+        let (runs, token) = store
+            .search_runs(
+                &["0"],
+                Some("params.model = 'tree'"),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_that!(runs).has_length(2);
+        assert_that!(token).is_none();
+
+        let (runs, token) = store
+            .search_runs(
+                &["0"],
+                None,
+                None,
+                Some(2),
+                Some(&["metrics.rmse DESC"]),
+                None,
+            )
+            .unwrap();
+        assert_that!(runs).has_length(2);
+        assert_that!(runs[0].info.start_time).is_equal_to(2);
+        let (runs, _) = store
+            .search_runs(
+                &["0"],
+                None,
+                None,
+                Some(2),
+                Some(&["metrics.rmse DESC"]),
+                token.as_deref(),
+            )
+            .unwrap();
+        assert_that!(runs).has_length(1);
+
+        store.delete_run(&runs[0].info.run_id).unwrap();
+        let (runs, _) = store
+            .search_runs(&["0"], None, Some(ViewType::DeletedOnly), None, None, None)
+            .unwrap();
+        assert_that!(runs).has_length(1);
+        assert_that!(error_code(store.log_param(
+            &runs[0].info.run_id,
+            "other",
+            "1"
+        )))
+        .is_equal_to(Some(GetExperimentErrorCode::InvalidParameterValue));
+
+        assert_that!(error_code(store.search_runs(
+            &["0"],
+            Some("params.model = tree"),
+            None,
+            None,
+            None,
+            None
+        )))
+        .is_equal_to(Some(GetExperimentErrorCode::InvalidParameterValue));
+    }
+
+    #[test]
+    fn can_read_python_file_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let experiment_dir = dir.path().join("3");
+        let run_dir = experiment_dir.join("8e2cb0c7a2a04c6f9d6e0f3d2c1b0a99");
+        fs::create_dir_all(run_dir.join("metrics")).unwrap();
+        fs::create_dir_all(run_dir.join("params")).unwrap();
+        fs::create_dir_all(run_dir.join("tags")).unwrap();
+        fs::write(
+            experiment_dir.join("meta.yaml"),
+            "artifact_location: file:///tmp/mlruns/3\ncreation_time: 1700000000000\nexperiment_id: '3'\nlast_update_time: 1700000000000\nlifecycle_stage: active\nname: python\n",
+        )
+        .unwrap();
+        fs::write(
+            run_dir.join("meta.yaml"),
+            "artifact_uri: file:///tmp/mlruns/3/8e2cb0c7a2a04c6f9d6e0f3d2c1b0a99/artifacts\nend_time: 1700000001000\nentry_point_name: ''\nexperiment_id: '3'\nlifecycle_stage: active\nrun_id: 8e2cb0c7a2a04c6f9d6e0f3d2c1b0a99\nrun_name: python-run\nrun_uuid: 8e2cb0c7a2a04c6f9d6e0f3d2c1b0a99\nsource_name: ''\nsource_type: 4\nsource_version: ''\nstart_time: 1700000000000\nstatus: 3\ntags: []\nuser_id: me\n",
+        )
+        .unwrap();
+        fs::write(
+            run_dir.join("metrics").join("loss"),
+            "1700000000500 0.25 3\n",
+        )
+        .unwrap();
+        fs::write(run_dir.join("params").join("alpha"), "0.5").unwrap();
+
+        let store = FileStore::new(dir.path().to_str().unwrap()).unwrap();
+        assert_that!(store.list_experiments(None).unwrap()).has_length(1);
+        let run = store.get_run("8e2cb0c7a2a04c6f9d6e0f3d2c1b0a99").unwrap();
+        assert_that!(run.info.status).is_equal_to(RunStatus::Finished);
+        assert_that!(run.info.run_name).is_equal_to(Some("python-run".to_string()));
+        assert_that!(run.data.unwrap().metrics).is_equal_to(vec![Metric {
+            key: "loss".to_string(),
+            value: 0.25,
+            timestamp: 1700000000500,
+            step: 3,
+        }]);
+    }
+}
@@ -0,0 +1,234 @@
+//! Garbage collection of deleted experiments and runs.
+//!
+//! Deleting an experiment or a run only marks it as deleted, and its artifacts are kept forever. A
+//! [`GarbageCollector`] finds the experiments and runs deleted for longer than a threshold, deletes their artifacts
+//! through the artifact backend, and removes their metadata from stores that allow it, like the `FileStore`. A
+//! [`GarbageCollector::plan`] reports what would be collected without changing anything.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::artifacts::get_artifact_repository;
+use crate::errors::GcError;
+use crate::store::TrackingStore;
+use crate::{Run, ViewType};
+
+/// A deleted run to collect.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeletedRun {
+    /// ID of the run.
+    pub run_id: String,
+    /// ID of the experiment of the run.
+    pub experiment_id: String,
+    /// URI of the artifacts of the run.
+    pub artifact_uri: String,
+    /// When the run was deleted, in milliseconds since the epoch, if known.
+    pub deleted_time: Option<u64>,
+}
+
+/// A deleted experiment to collect, with all its runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeletedExperiment {
+    /// ID of the experiment.
+    pub experiment_id: String,
+    /// Name of the experiment.
+    pub name: String,
+    /// When the experiment was deleted, in milliseconds since the epoch: its last update.
+    pub deleted_time: Option<u64>,
+    /// Runs of the experiment, deleted or not.
+    pub runs: Vec<DeletedRun>,
+}
+
+/// What a garbage collection removed, or would remove for a plan.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GcReport {
+    /// Deleted experiments, collected with their runs.
+    pub experiments: Vec<DeletedExperiment>,
+    /// Deleted runs of active experiments.
+    pub runs: Vec<DeletedRun>,
+    /// IDs of the deleted experiments and runs left alone because their deletion time is unknown.
+    pub skipped: Vec<String>,
+    /// Whether the experiments and runs were collected, `false` for a plan.
+    pub collected: bool,
+    /// Whether the metadata of the experiments and runs was removed from the tracking store, not only their
+    /// artifacts.
+    pub metadata_purged: bool,
+}
+
+/// Collector of the experiments and runs deleted for longer than a threshold.
+#[derive(Debug)]
+pub struct GarbageCollector<'a> {
+    store: &'a dyn TrackingStore,
+    tracking_uri: String,
+    older_than: Duration,
+}
+
+impl<'a> GarbageCollector<'a> {
+    /// New `GarbageCollector` for all the deleted experiments and runs of `store`. `tracking_uri` is used for
+    /// artifacts proxied by the tracking server.
+    pub fn new(store: &'a dyn TrackingStore, tracking_uri: &str) -> GarbageCollector<'a> {
+        GarbageCollector {
+            store,
+            tracking_uri: tracking_uri.to_string(),
+            older_than: Duration::from_secs(0),
+        }
+    }
+
+    /// Only collect experiments and runs deleted for longer than `older_than`. Deleted runs are then skipped when the
+    /// store doesn't record their deletion time, like the REST API.
+    pub fn older_than(mut self, older_than: Duration) -> GarbageCollector<'a> {
+        self.older_than = older_than;
+        self
+    }
+
+    /// Find the experiments and runs to collect, without changing anything.
+    pub fn plan(&self) -> Result<GcReport, GcError> {
+        let cutoff = crate::store::now().saturating_sub(self.older_than.as_millis() as u64);
+        let collectable = |deleted_time: Option<u64>| match deleted_time {
+            Some(deleted_time) => deleted_time <= cutoff,
+            None => self.older_than.as_millis() == 0,
+        };
+        let mut report = GcReport::default();
+
+        for experiment in self.store.list_experiments(Some(ViewType::DeletedOnly))? {
+            if !collectable(experiment.last_update_time) {
+                if experiment.last_update_time.is_none() {
+                    report.skipped.push(experiment.experiment_id);
+                }
+                continue;
+            }
+            let runs = self
+                .search_all_runs(&experiment.experiment_id, ViewType::All)?
+                .iter()
+                .map(|run| deleted_run(run, None))
+                .collect();
+            report.experiments.push(DeletedExperiment {
+                experiment_id: experiment.experiment_id,
+                name: experiment.name,
+                deleted_time: experiment.last_update_time,
+                runs,
+            });
+        }
+
+        for experiment in self.store.list_experiments(Some(ViewType::ActiveOnly))? {
+            for run in self.search_all_runs(&experiment.experiment_id, ViewType::DeletedOnly)? {
+                let deleted_time = self.store.get_run_deleted_time(&run.info.run_id)?;
+                if collectable(deleted_time) {
+                    report.runs.push(deleted_run(&run, deleted_time));
+                } else if deleted_time.is_none() {
+                    report.skipped.push(run.info.run_id);
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Collect the experiments and runs of a [`GarbageCollector::plan`]: delete their artifacts, then remove their
+    /// metadata if the store allows it.
+    pub fn collect(&self, plan: &GcReport) -> Result<GcReport, GcError> {
+        let mut report = plan.clone();
+        report.metadata_purged = true;
+        for experiment in &plan.experiments {
+            for run in &experiment.runs {
+                self.delete_artifacts(run)?;
+            }
+            report.metadata_purged &= self.store.purge_experiment(&experiment.experiment_id)?;
+        }
+        for run in &plan.runs {
+            self.delete_artifacts(run)?;
+            report.metadata_purged &= self.store.purge_run(&run.run_id)?;
+        }
+        report.collected = true;
+        Ok(report)
+    }
+
+    /// Plan then collect the deleted experiments and runs.
+    pub fn run(&self) -> Result<GcReport, GcError> {
+        self.collect(&self.plan()?)
+    }
+
+    fn delete_artifacts(&self, run: &DeletedRun) -> Result<(), GcError> {
+        get_artifact_repository(&run.artifact_uri, &self.tracking_uri)?.delete_artifacts(None)?;
+        Ok(())
+    }
+
+    fn search_all_runs(
+        &self,
+        experiment_id: &str,
+        view_type: ViewType,
+    ) -> Result<Vec<Run>, GcError> {
+        let mut runs = vec![];
+        let mut page_token = None;
+        loop {
+            let (page, next_page_token) = self.store.search_runs(
+                &[experiment_id],
+                None,
+                Some(view_type),
+                None,
+                None,
+                page_token.as_deref(),
+            )?;
+            runs.extend(page);
+            match next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(runs),
+            }
+        }
+    }
+}
+
+fn deleted_run(run: &Run, deleted_time: Option<u64>) -> DeletedRun {
+    DeletedRun {
+        run_id: run.info.run_id.clone(),
+        experiment_id: run.info.experiment_id.clone(),
+        artifact_uri: run.info.artifact_uri.clone(),
+        deleted_time,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use spectral::prelude::*;
+
+    use super::GarbageCollector;
+    use crate::FileStore;
+
+    #[test]
+    fn can_collect_deleted_runs_and_experiments() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path().to_str().unwrap()).unwrap();
+        let kept = store.create_run("0", None, None, None).unwrap();
+        let deleted = store.create_run("0", None, None, None).unwrap();
+        let artifacts = deleted.info.artifact_uri.trim_start_matches("file://");
+        std::fs::create_dir_all(artifacts).unwrap();
+        std::fs::write(std::path::Path::new(artifacts).join("model.bin"), "weights").unwrap();
+        store.delete_run(&deleted.info.run_id).unwrap();
+        let experiment_id = store.create_experiment("old", None).unwrap();
+        store.create_run(&experiment_id, None, None, None).unwrap();
+        store.delete_experiment(&experiment_id).unwrap();
+
+        let gc = GarbageCollector::new(&store, "").older_than(Duration::from_secs(3600));
+        let plan = gc.plan().unwrap();
+        assert_that!(plan.runs).is_empty();
+        assert_that!(plan.experiments).is_empty();
+
+        let gc = GarbageCollector::new(&store, "");
+        let plan = gc.plan().unwrap();
+        assert_that!(plan.runs).has_length(1);
+        assert_that!(plan.experiments).has_length(1);
+        assert_that!(plan.experiments[0].runs).has_length(1);
+        assert_that!(plan.collected).is_false();
+        assert_that!(store.get_run(&deleted.info.run_id)).is_ok();
+
+        let report = gc.collect(&plan).unwrap();
+        assert_that!(report.collected).is_true();
+        assert_that!(report.metadata_purged).is_true();
+        assert_that!(std::path::Path::new(artifacts).exists()).is_false();
+        assert_that!(store.get_run(&deleted.info.run_id)).is_err();
+        assert_that!(store.get_experiment(&experiment_id)).is_err();
+        assert_that!(store.get_run(&kept.info.run_id)).is_ok();
+    }
+}
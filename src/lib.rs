@@ -19,5 +19,24 @@ mod structures;
 pub use structures::*;
 pub mod errors;
 
+pub mod archive;
+pub mod artifacts;
+pub mod compare;
+pub mod gc;
+pub mod model;
+pub mod select;
+pub mod spool;
+pub mod stale;
+pub mod store;
+pub mod sweep;
+pub mod table;
+#[cfg(feature = "test-server")]
+pub mod test_server;
+
 mod client;
 pub use client::MLflowClient;
+mod file_store;
+mod search;
+pub use file_store::FileStore;
+mod memory_store;
+pub use memory_store::MemoryStore;
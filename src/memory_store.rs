@@ -0,0 +1,1025 @@
+//! Tracking store keeping everything in memory, for tests that should not depend on a tracking server.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::errors::{
+    ClientError, CreateExperimentErrorCode, GetExperimentErrorCode, ListExperimentsErrorCode,
+};
+use crate::search::{
+    latest_metric, paginate, sample_interval, sort_runs, SearchFilter,
+    BULK_INTERVAL_MAX_RESULTS_DEFAULT,
+};
+use crate::store::{
+    api_error, experiment_already_exists, experiment_name_not_found, experiment_not_found, now,
+    run_not_found, validate_key,
+};
+use crate::{
+    DatasetInput, Experiment, ExperimentTag, FileInfo, LifecycleStage, Metric, MetricWithRunId,
+    Param, Run, RunData, RunInfo, RunInputs, RunStatus, RunTag, ViewType,
+};
+
+const DEFAULT_EXPERIMENT_ID: &str = "0";
+const DEFAULT_EXPERIMENT_NAME: &str = "Default";
+
+/// Tracking store keeping experiments and runs in memory, with the same validation and errors as a tracking server.
+///
+/// Clones share the same data, so a clone can be given to an `MLflowClient` while the original is used to check
+/// what was logged.
+#[derive(Debug, Clone)]
+pub struct MemoryStore {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+#[derive(Debug)]
+struct MemoryState {
+    experiments: Vec<Experiment>,
+    runs: Vec<StoredRun>,
+    /// Root of the artifact locations of the experiments created without one.
+    artifact_root: String,
+    /// Whether a param can be logged again with another value, replacing the logged one.
+    param_changes: bool,
+}
+
+#[derive(Debug, Clone)]
+struct StoredRun {
+    info: RunInfo,
+    deleted_time: Option<u64>,
+    metrics: Vec<Metric>,
+    params: BTreeMap<String, String>,
+    tags: BTreeMap<String, String>,
+    inputs: Vec<DatasetInput>,
+}
+
+impl StoredRun {
+    fn to_run(&self) -> Run {
+        let mut latest: BTreeMap<&str, Vec<Metric>> = BTreeMap::new();
+        for metric in &self.metrics {
+            latest
+                .entry(metric.key.as_str())
+                .or_default()
+                .push(metric.clone());
+        }
+        Run {
+            info: self.info.clone(),
+            data: Some(RunData {
+                metrics: latest.into_values().filter_map(latest_metric).collect(),
+                params: self
+                    .params
+                    .iter()
+                    .map(|(key, value)| Param {
+                        key: key.clone(),
+                        value: value.clone(),
+                    })
+                    .collect(),
+                tags: self
+                    .tags
+                    .iter()
+                    .map(|(key, value)| RunTag {
+                        key: key.clone(),
+                        value: value.clone(),
+                    })
+                    .collect(),
+            }),
+            inputs: Some(RunInputs {
+                dataset_inputs: self.inputs.clone(),
+            }),
+        }
+    }
+}
+
+fn view_type_matches(view_type: ViewType, lifecycle_stage: LifecycleStage) -> bool {
+    match view_type {
+        ViewType::All => true,
+        ViewType::ActiveOnly => lifecycle_stage == LifecycleStage::Active,
+        ViewType::DeletedOnly => lifecycle_stage == LifecycleStage::Deleted,
+    }
+}
+
+impl MemoryState {
+    fn new(artifact_root: &str, param_changes: bool) -> MemoryState {
+        let now = now();
+        MemoryState {
+            experiments: vec![Experiment {
+                experiment_id: DEFAULT_EXPERIMENT_ID.to_string(),
+                name: DEFAULT_EXPERIMENT_NAME.to_string(),
+                artifact_location: format!("{}/{}", artifact_root, DEFAULT_EXPERIMENT_ID),
+                lifecycle_stage: LifecycleStage::Active,
+                last_update_time: Some(now),
+                creation_time: Some(now),
+                tags: None,
+            }],
+            runs: vec![],
+            artifact_root: artifact_root.to_string(),
+            param_changes,
+        }
+    }
+
+    fn experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<&Experiment, ClientError<GetExperimentErrorCode>> {
+        self.experiments
+            .iter()
+            .find(|experiment| experiment.experiment_id == experiment_id)
+            .ok_or_else(|| {
+                api_error(
+                    GetExperimentErrorCode::ResourceDoesNotExist,
+                    experiment_not_found(experiment_id),
+                )
+            })
+    }
+
+    fn experiment_mut(
+        &mut self,
+        experiment_id: &str,
+    ) -> Result<&mut Experiment, ClientError<GetExperimentErrorCode>> {
+        self.experiment(experiment_id)?;
+        Ok(self
+            .experiments
+            .iter_mut()
+            .find(|experiment| experiment.experiment_id == experiment_id)
+            .expect("experiment was just found"))
+    }
+
+    fn run(&self, run_id: &str) -> Result<&StoredRun, ClientError<GetExperimentErrorCode>> {
+        self.runs
+            .iter()
+            .find(|run| run.info.run_id == run_id)
+            .ok_or_else(|| {
+                api_error(
+                    GetExperimentErrorCode::ResourceDoesNotExist,
+                    run_not_found(run_id),
+                )
+            })
+    }
+
+    fn run_mut(
+        &mut self,
+        run_id: &str,
+    ) -> Result<&mut StoredRun, ClientError<GetExperimentErrorCode>> {
+        self.run(run_id)?;
+        Ok(self
+            .runs
+            .iter_mut()
+            .find(|run| run.info.run_id == run_id)
+            .expect("run was just found"))
+    }
+
+    fn active_run_mut(
+        &mut self,
+        run_id: &str,
+    ) -> Result<&mut StoredRun, ClientError<GetExperimentErrorCode>> {
+        let run = self.run_mut(run_id)?;
+        if run.info.lifecycle_stage != LifecycleStage::Active {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                format!(
+                    "The run {} must be in the 'active' state. Current state is deleted.",
+                    run_id
+                ),
+            ));
+        }
+        Ok(run)
+    }
+
+    fn set_experiment_lifecycle(
+        &mut self,
+        experiment_id: &str,
+        from: LifecycleStage,
+        to: LifecycleStage,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let experiment = self.experiment_mut(experiment_id)?;
+        if experiment.lifecycle_stage != from {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                format!(
+                    "Cannot change lifecycle stage of experiment {}, it is not {:?}",
+                    experiment_id, from
+                ),
+            ));
+        }
+        experiment.lifecycle_stage = to;
+        experiment.last_update_time = Some(now());
+        // runs follow the lifecycle of their experiment, as with the database backed stores
+        for run in self.runs.iter_mut().filter(|run| {
+            run.info.experiment_id == experiment_id && run.info.lifecycle_stage == from
+        }) {
+            run.info.lifecycle_stage = to;
+            run.deleted_time = match to {
+                LifecycleStage::Deleted => Some(now()),
+                LifecycleStage::Active => None,
+            };
+        }
+        Ok(())
+    }
+
+    fn set_run_tag(
+        &mut self,
+        run_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        validate_key("tag", key, GetExperimentErrorCode::InvalidParameterValue)?;
+        let run = self.active_run_mut(run_id)?;
+        run.tags.insert(key.to_string(), value.to_string());
+        if key == "mlflow.runName" {
+            run.info.run_name = Some(value.to_string());
+        }
+        Ok(())
+    }
+
+    fn log_param(
+        &mut self,
+        run_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        validate_key("param", key, GetExperimentErrorCode::InvalidParameterValue)?;
+        let param_changes = self.param_changes;
+        let run = self.active_run_mut(run_id)?;
+        match run.params.get(key) {
+            Some(existing) if existing != value && !param_changes => Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                format!(
+                    "Changing param values is not allowed. Param with key='{}' was already logged with value='{}' for run ID='{}'. Attempted logging new value '{}'.",
+                    key, existing, run_id, value
+                ),
+            )),
+            _ => {
+                run.params.insert(key.to_string(), value.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    fn log_metric(
+        &mut self,
+        run_id: &str,
+        metric: Metric,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        validate_key(
+            "metric",
+            &metric.key,
+            GetExperimentErrorCode::InvalidParameterValue,
+        )?;
+        self.active_run_mut(run_id)?.metrics.push(metric);
+        Ok(())
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        MemoryStore::new()
+    }
+}
+
+impl MemoryStore {
+    /// New empty `MemoryStore`, with only the `Default` experiment.
+    pub fn new() -> MemoryStore {
+        MemoryStore::with_artifact_root("memory:")
+    }
+
+    /// New empty `MemoryStore` whose experiments created without an artifact location get one under
+    /// `artifact_root`, like with the `--default-artifact-root` of a tracking server.
+    pub fn with_artifact_root(artifact_root: &str) -> MemoryStore {
+        MemoryStore {
+            state: Arc::new(Mutex::new(MemoryState::new(
+                artifact_root.trim_end_matches('/'),
+                false,
+            ))),
+        }
+    }
+
+    /// Let params be logged again with another value, replacing the logged one, as the MLflow 1.3 tracking server
+    /// does.
+    #[cfg(feature = "test-server")]
+    pub(crate) fn allow_param_changes(&self) {
+        self.state().param_changes = true;
+    }
+
+    fn state(&self) -> MutexGuard<'_, MemoryState> {
+        // a panic while holding the lock can not leave the state half updated, every change is a single assignment
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// All the experiments, including deleted ones.
+    pub fn experiments(&self) -> Vec<Experiment> {
+        self.state().experiments.clone()
+    }
+
+    /// All the runs, including deleted ones, in creation order.
+    pub fn runs(&self) -> Vec<Run> {
+        self.state().runs.iter().map(StoredRun::to_run).collect()
+    }
+
+    /// Every value logged for a metric of a run, in logging order.
+    pub fn metric_history_of(&self, run_id: &str, key: &str) -> Vec<Metric> {
+        self.state()
+            .run(run_id)
+            .map(|run| {
+                run.metrics
+                    .iter()
+                    .filter(|metric| metric.key == key)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Params of a run, by key.
+    pub fn params_of(&self, run_id: &str) -> BTreeMap<String, String> {
+        self.state()
+            .run(run_id)
+            .map(|run| run.params.clone())
+            .unwrap_or_default()
+    }
+
+    /// Tags of a run, by key.
+    pub fn tags_of(&self, run_id: &str) -> BTreeMap<String, String> {
+        self.state()
+            .run(run_id)
+            .map(|run| run.tags.clone())
+            .unwrap_or_default()
+    }
+
+    /// Remove all experiments and runs, leaving only the `Default` experiment.
+    pub fn clear(&self) {
+        let mut state = self.state();
+        *state = MemoryState::new(&state.artifact_root, state.param_changes);
+    }
+
+    /// Create an experiment with a name. Returns the ID of the newly created experiment. Validates that another
+    /// experiment with the same name does not already exist and fails if another experiment with the same name
+    /// already exists.
+    pub fn create_experiment(
+        &self,
+        name: &str,
+        artifact_location: Option<&str>,
+    ) -> Result<String, ClientError<CreateExperimentErrorCode>> {
+        if name.is_empty() {
+            return Err(api_error(
+                CreateExperimentErrorCode::InvalidParameterValue,
+                "Invalid experiment name: ''",
+            ));
+        }
+        let mut state = self.state();
+        if state
+            .experiments
+            .iter()
+            .any(|experiment| experiment.name == name)
+        {
+            return Err(api_error(
+                CreateExperimentErrorCode::ResourceAlreadyExists,
+                experiment_already_exists(name),
+            ));
+        }
+        let experiment_id = state
+            .experiments
+            .iter()
+            .filter_map(|experiment| experiment.experiment_id.parse::<u64>().ok())
+            .max()
+            .map(|id| id + 1)
+            .unwrap_or(0)
+            .to_string();
+        let default_artifact_location = format!("{}/{}", state.artifact_root, experiment_id);
+        let now = now();
+        state.experiments.push(Experiment {
+            experiment_id: experiment_id.clone(),
+            name: name.to_string(),
+            artifact_location: artifact_location
+                .map(str::to_string)
+                .unwrap_or(default_artifact_location),
+            lifecycle_stage: LifecycleStage::Active,
+            last_update_time: Some(now),
+            creation_time: Some(now),
+            tags: None,
+        });
+        Ok(experiment_id)
+    }
+
+    /// Get a list of all experiments.
+    pub fn list_experiments(
+        &self,
+        view_type: Option<ViewType>,
+    ) -> Result<Vec<Experiment>, ClientError<ListExperimentsErrorCode>> {
+        let view_type = view_type.unwrap_or(ViewType::ActiveOnly);
+        Ok(self
+            .state()
+            .experiments
+            .iter()
+            .filter(|experiment| view_type_matches(view_type, experiment.lifecycle_stage))
+            .cloned()
+            .collect())
+    }
+
+    /// Get metadata for an experiment. This method works on deleted experiments.
+    pub fn get_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<Experiment, ClientError<GetExperimentErrorCode>> {
+        self.state().experiment(experiment_id).cloned()
+    }
+
+    /// Get metadata for an experiment. This endpoint will return deleted experiments, but prefers the active
+    /// experiment if an active and deleted experiment share the same name.
+    pub fn get_experiment_by_name(
+        &self,
+        experiment_name: &str,
+    ) -> Result<Experiment, ClientError<GetExperimentErrorCode>> {
+        let state = self.state();
+        let mut found: Vec<&Experiment> = state
+            .experiments
+            .iter()
+            .filter(|experiment| experiment.name == experiment_name)
+            .collect();
+        found.sort_by_key(|experiment| experiment.lifecycle_stage != LifecycleStage::Active);
+        found
+            .first()
+            .map(|experiment| (*experiment).clone())
+            .ok_or_else(|| {
+                api_error(
+                    GetExperimentErrorCode::ResourceDoesNotExist,
+                    experiment_name_not_found(experiment_name),
+                )
+            })
+    }
+
+    /// Mark an experiment and associated metadata, runs, metrics, params, and tags for deletion.
+    pub fn delete_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.state().set_experiment_lifecycle(
+            experiment_id,
+            LifecycleStage::Active,
+            LifecycleStage::Deleted,
+        )
+    }
+
+    /// Restore an experiment marked for deletion. This also restores associated metadata, runs, metrics, params,
+    /// and tags.
+    pub fn restore_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.state().set_experiment_lifecycle(
+            experiment_id,
+            LifecycleStage::Deleted,
+            LifecycleStage::Active,
+        )
+    }
+
+    /// Permanently remove a deleted experiment with its runs.
+    pub fn purge_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        if state.experiment_mut(experiment_id)?.lifecycle_stage != LifecycleStage::Deleted {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                format!(
+                    "Cannot purge experiment {}, it is not deleted",
+                    experiment_id
+                ),
+            ));
+        }
+        state
+            .experiments
+            .retain(|experiment| experiment.experiment_id != experiment_id);
+        state
+            .runs
+            .retain(|run| run.info.experiment_id != experiment_id);
+        Ok(())
+    }
+
+    /// Update experiment metadata.
+    pub fn update_experiment(
+        &self,
+        experiment_id: &str,
+        new_name: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        if new_name.is_empty() {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                "Invalid experiment name: ''",
+            ));
+        }
+        if state.experiments.iter().any(|experiment| {
+            experiment.name == new_name && experiment.experiment_id != experiment_id
+        }) {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                experiment_already_exists(new_name),
+            ));
+        }
+        let experiment = state.experiment_mut(experiment_id)?;
+        if experiment.lifecycle_stage != LifecycleStage::Active {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                "Cannot rename a non-active experiment.",
+            ));
+        }
+        experiment.name = new_name.to_string();
+        experiment.last_update_time = Some(now());
+        Ok(())
+    }
+
+    /// Set a tag on an experiment. Experiment tags are metadata that can be updated.
+    pub fn set_experiment_tag(
+        &self,
+        experiment_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        validate_key("tag", key, GetExperimentErrorCode::InvalidParameterValue)?;
+        let mut state = self.state();
+        let experiment = state.experiment_mut(experiment_id)?;
+        if experiment.lifecycle_stage != LifecycleStage::Active {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                "The experiment must be in the 'active' state.",
+            ));
+        }
+        let tags = experiment.tags.get_or_insert_with(Vec::new);
+        tags.retain(|tag| tag.key != key);
+        tags.push(ExperimentTag {
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+        Ok(())
+    }
+
+    /// Create a new run within an experiment. A run is usually a single execution of a machine learning or data ETL
+    /// pipeline. MLflow uses runs to track `Param`, `Metric`, and `RunTag` associated with a single execution.
+    pub fn create_run(
+        &self,
+        experiment_id: &str,
+        start_time: Option<u64>,
+        tags: Option<Vec<RunTag>>,
+        run_name: Option<&str>,
+    ) -> Result<Run, ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        let experiment = state.experiment(experiment_id)?;
+        if experiment.lifecycle_stage != LifecycleStage::Active {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                format!(
+                    "Could not create run under non-active experiment with ID {}.",
+                    experiment_id
+                ),
+            ));
+        }
+        let tags = tags.unwrap_or_default();
+        for tag in &tags {
+            validate_key(
+                "tag",
+                &tag.key,
+                GetExperimentErrorCode::InvalidParameterValue,
+            )?;
+        }
+        let mut tags: BTreeMap<String, String> =
+            tags.into_iter().map(|tag| (tag.key, tag.value)).collect();
+        let run_name = run_name
+            .map(str::to_string)
+            .or_else(|| tags.get("mlflow.runName").cloned());
+        if let Some(run_name) = run_name.as_ref() {
+            tags.insert("mlflow.runName".to_string(), run_name.clone());
+        }
+        let run_id = uuid::Uuid::new_v4().to_simple().to_string();
+        let run = StoredRun {
+            info: RunInfo {
+                run_id: run_id.clone(),
+                run_name,
+                experiment_id: experiment_id.to_string(),
+                status: RunStatus::Running,
+                start_time: start_time.unwrap_or_else(now),
+                end_time: None,
+                artifact_uri: format!(
+                    "{}/{}/artifacts",
+                    experiment.artifact_location.trim_end_matches('/'),
+                    run_id
+                ),
+                lifecycle_stage: LifecycleStage::Active,
+            },
+            deleted_time: None,
+            metrics: vec![],
+            params: BTreeMap::new(),
+            tags,
+            inputs: vec![],
+        };
+        let created = run.to_run();
+        state.runs.push(run);
+        Ok(created)
+    }
+
+    /// Mark a run for deletion.
+    pub fn delete_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        let run = state.run_mut(run_id)?;
+        run.info.lifecycle_stage = LifecycleStage::Deleted;
+        run.deleted_time = Some(now());
+        Ok(())
+    }
+
+    /// Restore a deleted run.
+    pub fn restore_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        let run = state.run_mut(run_id)?;
+        run.info.lifecycle_stage = LifecycleStage::Active;
+        run.deleted_time = None;
+        Ok(())
+    }
+
+    /// Time at which a deleted run was deleted, `None` for active runs.
+    pub fn get_run_deleted_time(
+        &self,
+        run_id: &str,
+    ) -> Result<Option<u64>, ClientError<GetExperimentErrorCode>> {
+        self.state().run(run_id).map(|run| run.deleted_time)
+    }
+
+    /// Permanently remove a deleted run.
+    pub fn purge_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        if state.run(run_id)?.info.lifecycle_stage != LifecycleStage::Deleted {
+            return Err(api_error(
+                GetExperimentErrorCode::InvalidParameterValue,
+                format!("The run {} must be in the 'deleted' state.", run_id),
+            ));
+        }
+        state.runs.retain(|run| run.info.run_id != run_id);
+        Ok(())
+    }
+
+    /// Get metadata, metrics, params, and tags for a run. In the case where multiple metrics with the same key are
+    /// logged for a run, return only the value with the latest step and timestamp.
+    pub fn get_run(&self, run_id: &str) -> Result<Run, ClientError<GetExperimentErrorCode>> {
+        self.state().run(run_id).map(StoredRun::to_run)
+    }
+
+    /// Update run metadata: its status, end time, and name if specified.
+    pub fn update_run(
+        &self,
+        run_id: &str,
+        status: RunStatus,
+        end_time: Option<u64>,
+        run_name: Option<&str>,
+    ) -> Result<RunInfo, ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        let run = state.active_run_mut(run_id)?;
+        run.info.status = status;
+        if end_time.is_some() {
+            run.info.end_time = end_time;
+        }
+        if let Some(run_name) = run_name {
+            run.info.run_name = Some(run_name.to_string());
+            run.tags
+                .insert("mlflow.runName".to_string(), run_name.to_string());
+        }
+        Ok(run.info.clone())
+    }
+
+    /// Search for runs that satisfy expressions. Search expressions can use Metric and Param keys.
+    pub fn search_runs(
+        &self,
+        experiment_ids: &[&str],
+        filter: Option<&str>,
+        run_view_type: Option<ViewType>,
+        max_results: Option<u32>,
+        order_by: Option<&[&str]>,
+        page_token: Option<&str>,
+    ) -> Result<(Vec<Run>, Option<String>), ClientError<GetExperimentErrorCode>> {
+        let invalid =
+            |message: String| api_error(GetExperimentErrorCode::InvalidParameterValue, message);
+        let search_filter = SearchFilter::parse(filter.unwrap_or("")).map_err(invalid)?;
+        let view_type = run_view_type.unwrap_or(ViewType::ActiveOnly);
+        let mut runs: Vec<Run> = self
+            .state()
+            .runs
+            .iter()
+            .filter(|run| {
+                experiment_ids.contains(&run.info.experiment_id.as_str())
+                    && view_type_matches(view_type, run.info.lifecycle_stage)
+            })
+            .map(StoredRun::to_run)
+            .filter(|run| search_filter.matches(run))
+            .collect();
+        sort_runs(&mut runs, order_by.unwrap_or(&[])).map_err(invalid)?;
+        paginate(runs, max_results, page_token).map_err(invalid)
+    }
+
+    /// Set a tag on a run. Tags are run metadata that can be updated during a run and after a run completes.
+    pub fn set_run_tag(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.state().set_run_tag(run_id, key, value)
+    }
+
+    /// Delete a tag on a run. Tags are run metadata that can be updated during a run and after a run completes.
+    pub fn delete_run_tag(
+        &self,
+        run_id: &str,
+        key: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        let run = state.active_run_mut(run_id)?;
+        match run.tags.remove(key) {
+            Some(_) => Ok(()),
+            None => Err(api_error(
+                GetExperimentErrorCode::ResourceDoesNotExist,
+                format!("No tag with name: {} in run with id {}", key, run_id),
+            )),
+        }
+    }
+
+    /// Log a metric for a run. A metric is a key-value pair (string key, float value) with an associated timestamp.
+    pub fn log_metric(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: f32,
+        timestamp: u64,
+        step: Option<u64>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.state().log_metric(
+            run_id,
+            Metric {
+                key: key.to_string(),
+                value,
+                timestamp,
+                step: step.unwrap_or(0),
+            },
+        )
+    }
+
+    /// Get a list of all values for the specified metric for a given run.
+    pub fn get_metric_history(
+        &self,
+        run_id: &str,
+        metric_key: &str,
+        max_results: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<(Vec<Metric>, Option<String>), ClientError<GetExperimentErrorCode>> {
+        self.state().run(run_id)?;
+        paginate(
+            self.metric_history_of(run_id, metric_key),
+            max_results.or(Some(u32::MAX)),
+            page_token,
+        )
+        .map_err(|message| api_error(GetExperimentErrorCode::InvalidParameterValue, message))
+    }
+
+    /// Get the values of a metric for several runs, between `start_step` and `end_step` if specified. When there are
+    /// more than `max_results` steps, they are sampled evenly, keeping the first and last ones.
+    pub fn get_metric_history_bulk_interval(
+        &self,
+        run_ids: &[&str],
+        metric_key: &str,
+        start_step: Option<u64>,
+        end_step: Option<u64>,
+        max_results: Option<u32>,
+    ) -> Result<Vec<MetricWithRunId>, ClientError<GetExperimentErrorCode>> {
+        let max_results = max_results.unwrap_or(BULK_INTERVAL_MAX_RESULTS_DEFAULT) as usize;
+        let mut metrics = vec![];
+        for run_id in run_ids {
+            self.state().run(run_id)?;
+            let history = sample_interval(
+                self.metric_history_of(run_id, metric_key),
+                start_step,
+                end_step,
+                max_results,
+            );
+            metrics.extend(history.into_iter().map(|metric| MetricWithRunId {
+                metric,
+                run_id: run_id.to_string(),
+            }));
+        }
+        Ok(metrics)
+    }
+
+    /// Log a param used for a run. A param is a key-value pair (string key, string value). A param can be logged only
+    /// once for a run.
+    pub fn log_param(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.state().log_param(run_id, key, value)
+    }
+
+    /// Log a batch of metrics, params, and tags for a run.
+    pub fn log_batch(
+        &self,
+        run_id: &str,
+        metrics: Option<&[&Metric]>,
+        params: Option<&[&Param]>,
+        tags: Option<&[&RunTag]>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        state.active_run_mut(run_id)?;
+        for param in params.unwrap_or(&[]) {
+            state.log_param(run_id, &param.key, &param.value)?;
+        }
+        for metric in metrics.unwrap_or(&[]) {
+            state.log_metric(run_id, (*metric).clone())?;
+        }
+        for tag in tags.unwrap_or(&[]) {
+            state.set_run_tag(run_id, &tag.key, &tag.value)?;
+        }
+        Ok(())
+    }
+
+    /// Log the datasets used by a run.
+    pub fn log_inputs(
+        &self,
+        run_id: &str,
+        datasets: Option<&[DatasetInput]>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        let run = state.active_run_mut(run_id)?;
+        for input in datasets.unwrap_or(&[]) {
+            if !run.inputs.contains(input) {
+                run.inputs.push(input.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// List artifacts for a run. A `MemoryStore` does not store artifacts, so the list is always empty.
+    pub fn list_artifacts(
+        &self,
+        run_id: &str,
+        _path: Option<&str>,
+    ) -> Result<(String, Vec<FileInfo>), ClientError<GetExperimentErrorCode>> {
+        Ok((self.state().run(run_id)?.info.artifact_uri.clone(), vec![]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::MemoryStore;
+    use crate::errors::{
+        ClientError, CreateExperimentErrorCode, ErrorCode, GetExperimentErrorCode,
+    };
+    use crate::{LifecycleStage, Metric, RunStatus, ViewType};
+
+    fn error_code<T, E: ErrorCode + Copy>(result: Result<T, ClientError<E>>) -> Option<E> {
+        match result {
+            Err(ClientError::ApiError { error_code, .. }) => Some(error_code),
+            _ => None,
+        }
+    }
+
+    fn error_message<T, E: ErrorCode>(result: Result<T, ClientError<E>>) -> Option<String> {
+        match result {
+            Err(ClientError::ApiError { message, .. }) => Some(message),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn can_manage_experiment_lifecycle() {
+        let store = MemoryStore::new();
+        let id = store.create_experiment("exp", None).unwrap();
+        assert_that!(error_code(store.create_experiment("exp", None)))
+            .is_equal_to(Some(CreateExperimentErrorCode::ResourceAlreadyExists));
+        let run_id = store.create_run(&id, None, None, None).unwrap().info.run_id;
+
+        store.delete_experiment(&id).unwrap();
+        assert_that!(store.list_experiments(None).unwrap()).has_length(1);
+        assert_that!(store.get_run(&run_id).unwrap().info.lifecycle_stage)
+            .is_equal_to(LifecycleStage::Deleted);
+        assert_that!(error_code(store.delete_experiment(&id)))
+            .is_equal_to(Some(GetExperimentErrorCode::InvalidParameterValue));
+        assert_that!(error_code(store.create_run(&id, None, None, None)))
+            .is_equal_to(Some(GetExperimentErrorCode::InvalidParameterValue));
+
+        store.restore_experiment(&id).unwrap();
+        assert_that!(store.list_experiments(Some(ViewType::All)).unwrap()).has_length(2);
+        assert_that!(store.get_run(&run_id).unwrap().info.lifecycle_stage)
+            .is_equal_to(LifecycleStage::Active);
+        assert_that!(error_code(store.get_experiment("42")))
+            .is_equal_to(Some(GetExperimentErrorCode::ResourceDoesNotExist));
+        // same messages as the MLflow server and the FileStore
+        assert_that!(error_message(store.get_experiment("42")))
+            .is_equal_to(Some("Could not find experiment with ID 42".to_string()));
+        assert_that!(error_message(store.create_experiment("exp", None)))
+            .is_equal_to(Some("Experiment 'exp' already exists.".to_string()));
+    }
+
+    #[test]
+    fn can_log_and_inspect_run_data() {
+        let store = MemoryStore::new();
+        let client_store = store.clone();
+        let run_id = client_store
+            .create_run("0", None, None, Some("run"))
+            .unwrap()
+            .info
+            .run_id;
+
+        client_store.log_param(&run_id, "alpha", "0.5").unwrap();
+        assert_that!(error_code(client_store.log_param(&run_id, "alpha", "1")))
+            .is_equal_to(Some(GetExperimentErrorCode::InvalidParameterValue));
+        client_store
+            .log_metric(&run_id, "loss", 2.0, 10, Some(0))
+            .unwrap();
+        client_store
+            .log_metric(&run_id, "loss", 1.0, 11, Some(1))
+            .unwrap();
+        client_store
+            .update_run(&run_id, RunStatus::Finished, Some(12), None)
+            .unwrap();
+
+        assert_that!(store.metric_history_of(&run_id, "loss")).has_length(2);
+        assert_that!(store.params_of(&run_id).get("alpha")).is_equal_to(Some(&"0.5".to_string()));
+        assert_that!(store.tags_of(&run_id).get("mlflow.runName"))
+            .is_equal_to(Some(&"run".to_string()));
+        let run = store.get_run(&run_id).unwrap();
+        assert_that!(run.info.status).is_equal_to(RunStatus::Finished);
+        assert_that!(run.data.unwrap().metrics).is_equal_to(vec![Metric {
+            key: "loss".to_string(),
+            value: 1.0,
+            timestamp: 11,
+            step: 1,
+        }]);
+
+        store.delete_run(&run_id).unwrap();
+        assert_that!(error_code(
+            client_store.log_metric(&run_id, "loss", 0.5, 13, None)
+        ))
+        .is_equal_to(Some(GetExperimentErrorCode::InvalidParameterValue));
+
+        store.clear();
+        assert_that!(store.runs()).is_empty();
+        assert_that!(client_store.runs()).is_empty();
+    }
+
+    #[test]
+    fn can_search_runs() {
+        let store = MemoryStore::new();
+        for index in 0..5 {
+            let run_id = store
+                .create_run("0", Some(index), None, None)
+                .unwrap()
+                .info
+                .run_id;
+            store
+                .log_metric(&run_id, "rmse", index as f32, 0, None)
+                .unwrap();
+        }
+
+        let (runs, token) = store
+            .search_runs(
+                &["0"],
+                Some("metrics.rmse >= 2"),
+                None,
+                Some(2),
+                Some(&["metrics.rmse ASC"]),
+                None,
+            )
+            .unwrap();
+        assert_that!(runs
+            .iter()
+            .map(|run| run.info.start_time)
+            .collect::<Vec<_>>())
+        .is_equal_to(vec![2, 3]);
+        let (runs, token) = store
+            .search_runs(
+                &["0"],
+                Some("metrics.rmse >= 2"),
+                None,
+                Some(2),
+                Some(&["metrics.rmse ASC"]),
+                token.as_deref(),
+            )
+            .unwrap();
+        assert_that!(runs).has_length(1);
+        assert_that!(token).is_none();
+
+        store.delete_run(&runs[0].info.run_id).unwrap();
+        let (deleted, _) = store
+            .search_runs(&["0"], None, Some(ViewType::DeletedOnly), None, None, None)
+            .unwrap();
+        assert_that!(deleted).has_length(1);
+        assert_that!(error_code(store.search_runs(
+            &["0"],
+            Some("metrics.rmse >"),
+            None,
+            None,
+            None,
+            None
+        )))
+        .is_equal_to(Some(GetExperimentErrorCode::InvalidParameterValue));
+    }
+}
@@ -0,0 +1,315 @@
+//! MLflow models and their `MLmodel` description file.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ModelError;
+
+/// Name of the file describing a model, at the root of the model directory.
+pub const MLMODEL_FILE_NAME: &str = "MLmodel";
+
+/// Name of the file listing the pip requirements of a model.
+pub const REQUIREMENTS_FILE_NAME: &str = "requirements.txt";
+
+/// Name of the file holding the input example of a model.
+pub const INPUT_EXAMPLE_FILE_NAME: &str = "input_example.json";
+
+/// Run tag keeping track of the models logged in a run.
+pub const LOG_MODEL_HISTORY_TAG: &str = "mlflow.log-model.history";
+
+/// Format of `utc_time_created`, as written by the Python client.
+const UTC_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.6f";
+
+/// Configuration of a flavor, specific to the library loading the model.
+pub type Flavor = BTreeMap<String, serde_yaml::Value>;
+
+/// An MLflow model, as described in its `MLmodel` file.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct Model {
+    /// Path of the model, relative to the artifact root of the run that logged it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact_path: Option<String>,
+    /// Flavors the model can be loaded as, by flavor name.
+    #[serde(default)]
+    pub flavors: BTreeMap<String, Flavor>,
+    /// Version of MLflow that wrote the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mlflow_version: Option<String>,
+    /// Unique identifier of the model, as an hexadecimal UUID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_uuid: Option<String>,
+    /// ID of the run that logged the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    /// UTC creation time, as `YYYY-MM-DD HH:MM:SS.ffffff`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utc_time_created: Option<String>,
+    /// Signature of the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<crate::ModelSignature>,
+    /// Description of the input example saved with the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_input_example_info: Option<InputExampleInfo>,
+    /// Size of the model directory in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_size_bytes: Option<u64>,
+    /// Free form metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<BTreeMap<String, serde_yaml::Value>>,
+    /// Other fields, kept so that files written by other clients are not altered.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// Description of the input example saved with a model.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct InputExampleInfo {
+    /// Path of the example, relative to the model directory.
+    pub artifact_path: String,
+    /// Type of the example, like `dataframe`, `ndarray` or `json_object`.
+    #[serde(rename = "type")]
+    pub example_type: String,
+    /// Orientation of the example when it is a dataframe.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pandas_orient: Option<String>,
+}
+
+impl Model {
+    /// New `Model`, with a new UUID and the current time as creation time.
+    pub fn new() -> Model {
+        Model {
+            model_uuid: Some(uuid::Uuid::new_v4().to_simple().to_string()),
+            utc_time_created: Some(chrono::Utc::now().format(UTC_TIME_FORMAT).to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Add a flavor to the model, replacing the flavor of the same name if any.
+    pub fn add_flavor(&mut self, name: &str, flavor: Flavor) -> &mut Self {
+        self.flavors.insert(name.to_string(), flavor);
+        self
+    }
+
+    /// Parse an `MLmodel` file content.
+    pub fn from_yaml(content: &str) -> Result<Model, ModelError> {
+        Ok(serde_yaml::from_str(content)?)
+    }
+
+    /// Serialize as an `MLmodel` file content.
+    pub fn to_yaml(&self) -> Result<String, ModelError> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// Load a model from a `MLmodel` file, or from a directory containing one.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Model, ModelError> {
+        let path = path.as_ref();
+        let path = if path.is_dir() {
+            path.join(MLMODEL_FILE_NAME)
+        } else {
+            path.to_path_buf()
+        };
+        Model::from_yaml(&fs::read_to_string(path)?)
+    }
+
+    /// Write the `MLmodel` file in the model directory.
+    pub fn save<P: AsRef<Path>>(&self, model_dir: P) -> Result<(), ModelError> {
+        fs::create_dir_all(model_dir.as_ref())?;
+        fs::write(model_dir.as_ref().join(MLMODEL_FILE_NAME), self.to_yaml()?)?;
+        Ok(())
+    }
+
+    /// Write `example` in the model directory and reference it from the model. The example is checked against the
+    /// model signature if there is one.
+    pub fn save_input_example<P: AsRef<Path>>(
+        &mut self,
+        model_dir: P,
+        example: &serde_json::Value,
+    ) -> Result<(), ModelError> {
+        if let Some(signature) = self.signature.as_ref() {
+            signature.validate_input(example)?;
+        }
+        fs::create_dir_all(model_dir.as_ref())?;
+        fs::write(
+            model_dir.as_ref().join(INPUT_EXAMPLE_FILE_NAME),
+            serde_json::to_string(example)?,
+        )?;
+        self.saved_input_example_info = Some(InputExampleInfo {
+            artifact_path: INPUT_EXAMPLE_FILE_NAME.to_string(),
+            example_type: "json_object".to_string(),
+            pandas_orient: None,
+        });
+        Ok(())
+    }
+
+    /// Write the pip requirements of the model in its directory.
+    pub fn save_requirements<P: AsRef<Path>>(
+        model_dir: P,
+        requirements: &[&str],
+    ) -> Result<(), ModelError> {
+        fs::create_dir_all(model_dir.as_ref())?;
+        let mut content = requirements.join("\n");
+        content.push('\n');
+        fs::write(model_dir.as_ref().join(REQUIREMENTS_FILE_NAME), content)?;
+        Ok(())
+    }
+
+    /// Check that the model is consistent, and could be loaded by other MLflow clients.
+    pub fn validate(&self) -> Result<(), ModelError> {
+        if self.flavors.is_empty() {
+            return Err(ModelError::Invalid("model has no flavor".to_string()));
+        }
+        if let Some(model_uuid) = self.model_uuid.as_ref() {
+            if model_uuid.len() != 32 || !model_uuid.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(ModelError::Invalid(format!(
+                    "invalid model_uuid '{}'",
+                    model_uuid
+                )));
+            }
+        }
+        if let Some(utc_time_created) = self.utc_time_created.as_ref() {
+            if chrono::NaiveDateTime::parse_from_str(utc_time_created, UTC_TIME_FORMAT).is_err() {
+                return Err(ModelError::Invalid(format!(
+                    "invalid utc_time_created '{}'",
+                    utc_time_created
+                )));
+            }
+        }
+        if self.run_id.is_some() != self.artifact_path.is_some() {
+            return Err(ModelError::Invalid(
+                "run_id and artifact_path must be set together".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Entry recorded in the `mlflow.log-model.history` run tag. The signature and input example are left out, as
+    /// tag values are limited in size.
+    pub(crate) fn history_entry(&self) -> Result<serde_json::Value, ModelError> {
+        let mut entry = serde_json::to_value(self)?;
+        if let Some(entry) = entry.as_object_mut() {
+            entry.remove("signature");
+            entry.remove("saved_input_example_info");
+        }
+        Ok(entry)
+    }
+}
+
+/// Copy the directory `from`, with its sub directories, to `to`.
+pub(crate) fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::{Flavor, Model};
+
+    const PYTHON_MLMODEL: &str = r#"artifact_path: model
+flavors:
+  python_function:
+    env:
+      conda: conda.yaml
+      virtualenv: python_env.yaml
+    loader_module: mlflow.sklearn
+    model_path: model.pkl
+    predict_fn: predict
+    python_version: 3.10.12
+  sklearn:
+    code: null
+    pickled_model: model.pkl
+    serialization_format: cloudpickle
+    sklearn_version: 1.3.0
+mlflow_version: 2.8.0
+model_size_bytes: 1024
+model_uuid: 5f3c0a5a0e1e4b5f8f6f3a3c9d1e2b7a
+run_id: 8e2cb0c7a2a04c6f9d6e0f3d2c1b0a99
+saved_input_example_info:
+  artifact_path: input_example.json
+  pandas_orient: split
+  type: dataframe
+signature:
+  inputs: '[{"type": "double", "name": "x", "required": true}]'
+  outputs: '[{"type": "tensor", "tensor-spec": {"dtype": "float64", "shape": [-1]}}]'
+  params: null
+utc_time_created: '2023-11-02 10:21:43.123456'
+databricks_runtime: 14.0
+"#;
+
+    #[test]
+    fn can_parse_python_mlmodel() {
+        let model = Model::from_yaml(PYTHON_MLMODEL);
+        assert_that!(model).is_ok();
+        let model = model.unwrap();
+
+        assert_that!(model.artifact_path).is_equal_to(Some("model".to_string()));
+        assert_that!(model.flavors.keys().collect::<Vec<_>>())
+            .is_equal_to(vec![&"python_function".to_string(), &"sklearn".to_string()]);
+        assert_that!(model.utc_time_created)
+            .is_equal_to(Some("2023-11-02 10:21:43.123456".to_string()));
+        assert_that!(model.saved_input_example_info)
+            .is_some()
+            .map(|info| &info.example_type)
+            .is_equal_to("dataframe".to_string());
+        assert_that!(model.signature)
+            .is_some()
+            .map(|signature| &signature.params)
+            .is_none();
+        assert_that!(model.extra.contains_key("databricks_runtime")).is_true();
+        assert_that!(model.validate()).is_ok();
+    }
+
+    #[test]
+    fn can_round_trip_python_mlmodel() {
+        let model = Model::from_yaml(PYTHON_MLMODEL).unwrap();
+        let written = model.to_yaml().unwrap();
+
+        assert_that!(Model::from_yaml(&written))
+            .is_ok()
+            .is_equal_to(model);
+        assert_that!(written).contains("utc_time_created: \"2023-11-02 10:21:43.123456\"");
+    }
+
+    #[test]
+    fn can_validate_new_model() {
+        let mut model = Model::new();
+        assert_that!(model.validate()).is_err();
+
+        let mut flavor = Flavor::new();
+        flavor.insert(
+            "model_path".to_string(),
+            serde_yaml::Value::String("model.bin".to_string()),
+        );
+        model.add_flavor("rust", flavor);
+        assert_that!(model.validate()).is_ok();
+
+        model.run_id = Some("8e2cb0c7a2a04c6f9d6e0f3d2c1b0a99".to_string());
+        assert_that!(model.validate()).is_err();
+        model.artifact_path = Some("model".to_string());
+        assert_that!(model.validate()).is_ok();
+    }
+
+    #[test]
+    fn should_fail_validation_with_invalid_values() {
+        let mut model = Model::from_yaml(PYTHON_MLMODEL).unwrap();
+        model.model_uuid = Some("not-a-uuid".to_string());
+        assert_that!(model.validate()).is_err();
+
+        let mut model = Model::from_yaml(PYTHON_MLMODEL).unwrap();
+        model.utc_time_created = Some("yesterday".to_string());
+        assert_that!(model.validate()).is_err();
+    }
+}
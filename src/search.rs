@@ -0,0 +1,675 @@
+//! Evaluation of run search filters and ordering, for stores that are not backed by an MLflow server.
+
+use std::cmp::Ordering;
+
+use crate::{Metric, Run, RunStatus};
+
+/// Default number of runs returned by a search.
+pub(crate) const SEARCH_MAX_RESULTS_DEFAULT: u32 = 1000;
+/// Default number of steps returned by `get_metric_history_bulk_interval`.
+pub(crate) const BULK_INTERVAL_MAX_RESULTS_DEFAULT: u32 = 320;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Entity {
+    Metric,
+    Param,
+    Tag,
+    Attribute,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Like,
+    ILike,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Clause {
+    entity: Entity,
+    key: String,
+    comparator: Comparator,
+    value: Value,
+}
+
+/// A parsed search filter, a conjunction of comparisons like `metrics.rmse < 1 and params.model = 'tree'`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct SearchFilter {
+    clauses: Vec<Clause>,
+}
+
+impl SearchFilter {
+    /// Parse a filter string. An empty filter matches every run.
+    pub(crate) fn parse(filter: &str) -> Result<SearchFilter, String> {
+        let tokens = tokenize(filter)?;
+        let mut clauses = vec![];
+        let mut tokens = tokens.into_iter().peekable();
+        while tokens.peek().is_some() {
+            let identifier = match tokens.next() {
+                Some(Token::Word(identifier)) => identifier,
+                other => return Err(format!("expected an identifier, found {:?}", other)),
+            };
+            let (entity, key) = parse_identifier(&identifier)?;
+            let comparator = match tokens.next() {
+                Some(Token::Operator(operator)) => parse_comparator(&operator)?,
+                Some(Token::Word(operator)) => parse_comparator(&operator)?,
+                other => return Err(format!("expected a comparator, found {:?}", other)),
+            };
+            let value = match tokens.next() {
+                Some(Token::Text(text)) => Value::Text(text),
+                Some(Token::Word(word)) => word
+                    .parse::<f64>()
+                    .map(Value::Number)
+                    .map_err(|_| format!("invalid value '{}'", word))?,
+                other => return Err(format!("expected a value, found {:?}", other)),
+            };
+            match (entity, &value, comparator) {
+                (Entity::Metric, Value::Text(_), _) => {
+                    return Err(format!("metric '{}' must be compared to a number", key))
+                }
+                (Entity::Param, Value::Number(_), _) | (Entity::Tag, Value::Number(_), _) => {
+                    return Err(format!("'{}' must be compared to a quoted string", key))
+                }
+                (Entity::Metric, _, Comparator::Like) | (Entity::Metric, _, Comparator::ILike) => {
+                    return Err(format!("LIKE can not be used on metric '{}'", key))
+                }
+                _ => (),
+            }
+            clauses.push(Clause {
+                entity,
+                key,
+                comparator,
+                value,
+            });
+            match tokens.next() {
+                None => (),
+                Some(Token::Word(ref word)) if word.eq_ignore_ascii_case("and") => {
+                    if tokens.peek().is_none() {
+                        return Err("filter ends with AND".to_string());
+                    }
+                }
+                other => return Err(format!("expected AND, found {:?}", other)),
+            }
+        }
+        Ok(SearchFilter { clauses })
+    }
+
+    /// Whether a run matches every clause of the filter.
+    pub(crate) fn matches(&self, run: &Run) -> bool {
+        self.clauses.iter().all(|clause| clause.matches(run))
+    }
+}
+
+impl Clause {
+    fn matches(&self, run: &Run) -> bool {
+        match (self.entity, &self.value) {
+            // metric values are stored as f32, so `metrics.loss = 0.1` compares with 0.1 rounded the same way
+            (Entity::Metric, Value::Number(expected)) => metric_value(run, &self.key)
+                .map(|value| compare_numbers(value, f64::from(*expected as f32), self.comparator))
+                .unwrap_or(false),
+            (Entity::Attribute, Value::Number(expected)) => numeric_attribute(run, &self.key)
+                .map(|value| compare_numbers(value, *expected, self.comparator))
+                .unwrap_or(false),
+            (_, Value::Text(expected)) => string_value(run, self.entity, &self.key)
+                .map(|value| compare_strings(&value, expected, self.comparator))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+}
+
+fn metric_value(run: &Run, key: &str) -> Option<f64> {
+    run.data.as_ref().and_then(|data| {
+        data.metrics
+            .iter()
+            .find(|metric| metric.key == key)
+            .map(|metric| f64::from(metric.value))
+    })
+}
+
+fn numeric_attribute(run: &Run, key: &str) -> Option<f64> {
+    match key {
+        "start_time" => Some(run.info.start_time as f64),
+        "end_time" => run.info.end_time.map(|end_time| end_time as f64),
+        _ => None,
+    }
+}
+
+fn string_value(run: &Run, entity: Entity, key: &str) -> Option<String> {
+    match entity {
+        Entity::Param => run.data.as_ref().and_then(|data| {
+            data.params
+                .iter()
+                .find(|param| param.key == key)
+                .map(|param| param.value.clone())
+        }),
+        Entity::Tag => run.data.as_ref().and_then(|data| {
+            data.tags
+                .iter()
+                .find(|tag| tag.key == key)
+                .map(|tag| tag.value.clone())
+        }),
+        Entity::Attribute => match key {
+            "run_id" => Some(run.info.run_id.clone()),
+            "run_name" => run.run_name().map(str::to_string),
+            "status" => Some(status_name(run.info.status).to_string()),
+            "artifact_uri" => Some(run.info.artifact_uri.clone()),
+            "experiment_id" => Some(run.info.experiment_id.clone()),
+            _ => None,
+        },
+        Entity::Metric => None,
+    }
+}
+
+pub(crate) fn status_name(status: RunStatus) -> &'static str {
+    match status {
+        RunStatus::Running => "RUNNING",
+        RunStatus::Scheduled => "SCHEDULED",
+        RunStatus::Finished => "FINISHED",
+        RunStatus::Failed => "FAILED",
+        RunStatus::Killed => "KILLED",
+    }
+}
+
+fn compare_numbers(value: f64, expected: f64, comparator: Comparator) -> bool {
+    match comparator {
+        Comparator::Equal => value == expected,
+        Comparator::NotEqual => value != expected,
+        Comparator::Less => value < expected,
+        Comparator::LessOrEqual => value <= expected,
+        Comparator::Greater => value > expected,
+        Comparator::GreaterOrEqual => value >= expected,
+        Comparator::Like | Comparator::ILike => false,
+    }
+}
+
+fn compare_strings(value: &str, expected: &str, comparator: Comparator) -> bool {
+    match comparator {
+        Comparator::Equal => value == expected,
+        Comparator::NotEqual => value != expected,
+        Comparator::Less => value < expected,
+        Comparator::LessOrEqual => value <= expected,
+        Comparator::Greater => value > expected,
+        Comparator::GreaterOrEqual => value >= expected,
+        Comparator::Like => like(value, expected),
+        Comparator::ILike => like(&value.to_lowercase(), &expected.to_lowercase()),
+    }
+}
+
+/// SQL `LIKE` matching, `%` matching any sequence and `_` any single character.
+fn like(value: &str, pattern: &str) -> bool {
+    fn like_chars(value: &[char], pattern: &[char]) -> bool {
+        match pattern.split_first() {
+            None => value.is_empty(),
+            Some(('%', rest)) => (0..=value.len()).any(|skip| like_chars(&value[skip..], rest)),
+            Some(('_', rest)) => !value.is_empty() && like_chars(&value[1..], rest),
+            Some((c, rest)) => value.first() == Some(c) && like_chars(&value[1..], rest),
+        }
+    }
+    let value: Vec<char> = value.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+    like_chars(&value, &pattern)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Operator(String),
+}
+
+fn tokenize(filter: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = filter.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '\'' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => text.extend(chars.next()),
+                        Some('\'') => break,
+                        Some(c) => text.push(c),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                tokens.push(Token::Text(text));
+            }
+            '=' | '!' | '<' | '>' => {
+                let mut operator = String::new();
+                while let Some(&c) = chars.peek() {
+                    if "=!<>".contains(c) {
+                        operator.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Operator(operator));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    match c {
+                        '"' | '`' => {
+                            chars.next();
+                            loop {
+                                match chars.next() {
+                                    Some(end) if end == c => break,
+                                    Some(other) => word.push(other),
+                                    None => return Err("unterminated identifier".to_string()),
+                                }
+                            }
+                        }
+                        c if c.is_whitespace() || "=!<>'".contains(c) => break,
+                        c => {
+                            word.push(c);
+                            chars.next();
+                        }
+                    }
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn parse_identifier(identifier: &str) -> Result<(Entity, String), String> {
+    let (entity, key) = match identifier.find('.') {
+        Some(index) => (&identifier[..index], &identifier[index + 1..]),
+        None => ("attributes", identifier),
+    };
+    let entity = match entity {
+        "metric" | "metrics" => Entity::Metric,
+        "param" | "params" | "parameter" | "parameters" => Entity::Param,
+        "tag" | "tags" => Entity::Tag,
+        "attribute" | "attributes" | "attr" | "run" => Entity::Attribute,
+        other => return Err(format!("invalid entity type '{}'", other)),
+    };
+    let key = match (entity, key) {
+        (Entity::Attribute, "run_name") | (Entity::Tag, "mlflow.runName") => key,
+        (Entity::Attribute, "id") => "run_id",
+        (Entity::Attribute, "created") | (Entity::Attribute, "Created") => "start_time",
+        (Entity::Attribute, key) => {
+            if ![
+                "run_id",
+                "status",
+                "artifact_uri",
+                "start_time",
+                "end_time",
+                "experiment_id",
+            ]
+            .contains(&key)
+            {
+                return Err(format!("invalid attribute key '{}'", key));
+            }
+            key
+        }
+        (_, key) => key,
+    };
+    if key.is_empty() {
+        return Err(format!("invalid identifier '{}'", identifier));
+    }
+    Ok((entity, key.to_string()))
+}
+
+fn parse_comparator(comparator: &str) -> Result<Comparator, String> {
+    match comparator.to_uppercase().as_str() {
+        "=" => Ok(Comparator::Equal),
+        "!=" => Ok(Comparator::NotEqual),
+        "<" => Ok(Comparator::Less),
+        "<=" => Ok(Comparator::LessOrEqual),
+        ">" => Ok(Comparator::Greater),
+        ">=" => Ok(Comparator::GreaterOrEqual),
+        "LIKE" => Ok(Comparator::Like),
+        "ILIKE" => Ok(Comparator::ILike),
+        other => Err(format!("invalid comparator '{}'", other)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct OrderBy {
+    entity: Entity,
+    key: String,
+    ascending: bool,
+}
+
+fn parse_order_by(order_by: &str) -> Result<OrderBy, String> {
+    let mut parts = order_by.split_whitespace().collect::<Vec<_>>();
+    let ascending = match parts.last().map(|direction| direction.to_uppercase()) {
+        Some(ref direction) if direction == "ASC" => {
+            parts.pop();
+            true
+        }
+        Some(ref direction) if direction == "DESC" => {
+            parts.pop();
+            false
+        }
+        _ => true,
+    };
+    let identifier = parts.join(" ").replace(['`', '"'], "");
+    let (entity, key) = parse_identifier(&identifier)?;
+    Ok(OrderBy {
+        entity,
+        key,
+        ascending,
+    })
+}
+
+fn order_value(run: &Run, order_by: &OrderBy) -> Option<Value> {
+    match order_by.entity {
+        Entity::Metric => metric_value(run, &order_by.key).map(Value::Number),
+        Entity::Attribute if ["start_time", "end_time"].contains(&order_by.key.as_str()) => {
+            numeric_attribute(run, &order_by.key).map(Value::Number)
+        }
+        entity => string_value(run, entity, &order_by.key).map(Value::Text),
+    }
+}
+
+fn compare_values(a: &Option<Value>, b: &Option<Value>, ascending: bool) -> Ordering {
+    // runs without the value are always last
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            let ordering = match (a, b) {
+                (Value::Number(a), Value::Number(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+                (Value::Text(a), Value::Text(b)) => a.cmp(b),
+                (Value::Number(_), Value::Text(_)) => Ordering::Less,
+                (Value::Text(_), Value::Number(_)) => Ordering::Greater,
+            };
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        }
+    }
+}
+
+/// Sort runs following `order_by` clauses, then by start time descending and run ID, like MLflow stores do.
+pub(crate) fn sort_runs(runs: &mut [Run], order_by: &[&str]) -> Result<(), String> {
+    let mut orders = order_by
+        .iter()
+        .map(|order_by| parse_order_by(order_by))
+        .collect::<Result<Vec<_>, _>>()?;
+    orders.push(OrderBy {
+        entity: Entity::Attribute,
+        key: "start_time".to_string(),
+        ascending: false,
+    });
+    orders.push(OrderBy {
+        entity: Entity::Attribute,
+        key: "run_id".to_string(),
+        ascending: true,
+    });
+    runs.sort_by(|a, b| {
+        orders
+            .iter()
+            .map(|order| {
+                compare_values(
+                    &order_value(a, order),
+                    &order_value(b, order),
+                    order.ascending,
+                )
+            })
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    });
+    Ok(())
+}
+
+/// Get a page of `items`. The page token is the offset of the page.
+pub(crate) fn paginate<T>(
+    items: Vec<T>,
+    max_results: Option<u32>,
+    page_token: Option<&str>,
+) -> Result<(Vec<T>, Option<String>), String> {
+    let offset = match page_token {
+        Some(token) if !token.is_empty() => token
+            .parse::<usize>()
+            .map_err(|_| format!("invalid page token '{}'", token))?,
+        _ => 0,
+    };
+    let max_results = max_results.unwrap_or(SEARCH_MAX_RESULTS_DEFAULT) as usize;
+    let total = items.len();
+    let page: Vec<T> = items.into_iter().skip(offset).take(max_results).collect();
+    let next_page_token = if offset + max_results < total {
+        Some((offset + max_results).to_string())
+    } else {
+        None
+    };
+    Ok((page, next_page_token))
+}
+
+/// Latest value of a metric: the one with the highest step, then timestamp, then value.
+pub(crate) fn latest_metric<I: IntoIterator<Item = Metric>>(history: I) -> Option<Metric> {
+    history.into_iter().max_by(|a, b| {
+        (a.step, a.timestamp)
+            .cmp(&(b.step, b.timestamp))
+            .then(a.value.partial_cmp(&b.value).unwrap_or(Ordering::Equal))
+    })
+}
+
+/// Values of a metric between `start_step` and `end_step` if specified. When there are more than `max_results` steps,
+/// they are sampled evenly, keeping the first and last ones.
+pub(crate) fn sample_interval(
+    history: Vec<Metric>,
+    start_step: Option<u64>,
+    end_step: Option<u64>,
+    max_results: usize,
+) -> Vec<Metric> {
+    let history: Vec<Metric> = history
+        .into_iter()
+        .filter(|metric| {
+            start_step.map(|start| metric.step >= start).unwrap_or(true)
+                && end_step.map(|end| metric.step <= end).unwrap_or(true)
+        })
+        .collect();
+    let mut steps: Vec<u64> = history.iter().map(|metric| metric.step).collect();
+    steps.sort_unstable();
+    steps.dedup();
+    let kept_steps: Vec<u64> = if steps.len() <= max_results || max_results < 2 {
+        steps
+            .iter()
+            .rev()
+            .take(max_results.max(1))
+            .cloned()
+            .collect()
+    } else {
+        (0..max_results)
+            .map(|index| steps[index * (steps.len() - 1) / (max_results - 1)])
+            .collect()
+    };
+    history
+        .into_iter()
+        .filter(|metric| kept_steps.contains(&metric.step))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::{like, paginate, sort_runs, SearchFilter};
+    use crate::{LifecycleStage, Metric, Param, Run, RunData, RunInfo, RunStatus, RunTag};
+
+    fn run(run_id: &str, start_time: u64, rmse: Option<f32>, model: &str) -> Run {
+        Run {
+            info: RunInfo {
+                run_id: run_id.to_string(),
+                run_name: Some(format!("name-{}", run_id)),
+                experiment_id: "0".to_string(),
+                status: RunStatus::Finished,
+                start_time,
+                end_time: None,
+                artifact_uri: String::new(),
+                lifecycle_stage: LifecycleStage::Active,
+            },
+            data: Some(RunData {
+                metrics: rmse
+                    .map(|rmse| {
+                        vec![Metric {
+                            key: "rmse".to_string(),
+                            value: rmse,
+                            timestamp: 0,
+                            step: 0,
+                        }]
+                    })
+                    .unwrap_or_default(),
+                params: vec![Param {
+                    key: "model".to_string(),
+                    value: model.to_string(),
+                }],
+                tags: vec![RunTag {
+                    key: "mlflow.user".to_string(),
+                    value: "me".to_string(),
+                }],
+            }),
+            inputs: None,
+        }
+    }
+
+    #[test]
+    fn can_match_filters() {
+        let run = run("a", 10, Some(0.5), "tree");
+
+        for filter in &[
+            "",
+            "metrics.rmse = 0.5",
+            "metrics.rmse < 1",
+            "metrics.rmse <= 0.5 and params.model = 'tree'",
+            "params.model LIKE 't%'",
+            "params.model ILIKE 'TR_E'",
+            "tags.`mlflow.user` != 'you'",
+            "tags.\"mlflow.user\" = 'me' AND attributes.status = 'FINISHED'",
+            "attributes.run_name = 'name-a'",
+            "attributes.start_time > 5",
+        ] {
+            assert_that!(SearchFilter::parse(filter).map(|parsed| parsed.matches(&run)))
+                .named(filter)
+                .is_ok()
+                .is_true();
+        }
+        for filter in &[
+            "metrics.rmse > 1",
+            "metrics.missing > 1",
+            "params.model = 'linear'",
+            "attributes.status = 'RUNNING'",
+        ] {
+            assert_that!(SearchFilter::parse(filter).map(|parsed| parsed.matches(&run)))
+                .named(filter)
+                .is_ok()
+                .is_false();
+        }
+    }
+
+    #[test]
+    fn can_match_metrics_stored_as_f32() {
+        let run = run("a", 10, Some(0.1), "tree");
+
+        for (filter, expected) in &[
+            ("metrics.rmse = 0.1", true),
+            ("metrics.rmse != 0.1", false),
+            ("metrics.rmse > 0.1", false),
+            ("metrics.rmse <= 0.1", true),
+            ("attributes.start_time = 10", true),
+        ] {
+            assert_that!(SearchFilter::parse(filter).map(|parsed| parsed.matches(&run)))
+                .named(filter)
+                .is_ok()
+                .is_equal_to(expected);
+        }
+    }
+
+    #[test]
+    fn should_fail_to_parse_invalid_filters() {
+        for filter in &[
+            "metrics.rmse < 'a'",
+            "params.model = tree",
+            "metrics.rmse",
+            "metrics.rmse < 1 and",
+            "metrics.rmse < 1 or params.model = 'tree'",
+            "unknown.rmse < 1",
+            "params.model = 'tree",
+            "attributes.color = 'red'",
+        ] {
+            assert_that!(SearchFilter::parse(filter))
+                .named(filter)
+                .is_err();
+        }
+    }
+
+    #[test]
+    fn can_match_like_patterns() {
+        assert_that!(like("abc", "a%")).is_true();
+        assert_that!(like("abc", "%c")).is_true();
+        assert_that!(like("abc", "a_c")).is_true();
+        assert_that!(like("abc", "a_")).is_false();
+        assert_that!(like("", "%")).is_true();
+    }
+
+    #[test]
+    fn can_sort_runs() {
+        let mut runs = vec![
+            run("a", 1, Some(0.5), "tree"),
+            run("b", 2, None, "linear"),
+            run("c", 3, Some(0.1), "tree"),
+        ];
+
+        sort_runs(&mut runs, &[]).unwrap();
+        assert_that!(runs
+            .iter()
+            .map(|run| run.info.run_id.as_str())
+            .collect::<Vec<_>>())
+        .is_equal_to(vec!["c", "b", "a"]);
+
+        sort_runs(&mut runs, &["metrics.rmse ASC"]).unwrap();
+        assert_that!(runs
+            .iter()
+            .map(|run| run.info.run_id.as_str())
+            .collect::<Vec<_>>())
+        .is_equal_to(vec!["c", "a", "b"]);
+
+        sort_runs(&mut runs, &["params.model DESC", "attributes.start_time"]).unwrap();
+        assert_that!(runs
+            .iter()
+            .map(|run| run.info.run_id.as_str())
+            .collect::<Vec<_>>())
+        .is_equal_to(vec!["a", "c", "b"]);
+
+        assert_that!(sort_runs(&mut runs, &["unknown.rmse"])).is_err();
+    }
+
+    #[test]
+    fn can_paginate() {
+        let items: Vec<u32> = (0..5).collect();
+
+        let (page, token) = paginate(items.clone(), Some(2), None).unwrap();
+        assert_that!(page).is_equal_to(vec![0, 1]);
+        let (page, token) = paginate(items.clone(), Some(2), token.as_deref()).unwrap();
+        assert_that!(page).is_equal_to(vec![2, 3]);
+        let (page, token) = paginate(items.clone(), Some(2), token.as_deref()).unwrap();
+        assert_that!(page).is_equal_to(vec![4]);
+        assert_that!(token).is_none();
+
+        assert_that!(paginate(items, None, Some("not-a-token"))).is_err();
+    }
+}
@@ -0,0 +1,246 @@
+//! Selection of the best runs by a metric, for deployment pipelines picking the model to ship.
+//!
+//! A [`RunSelector`] searches the runs of some experiments, keeps the ones with an accepted status, the required tags
+//! and the metric, and sorts them by the metric in a [`Direction`]. Runs with the same value are ordered by start
+//! time, the most recent first unless [`RunSelector::prefer_oldest`], then by run ID so that the selection is always
+//! the same. Each selected run comes with the resolved URI of its artifacts.
+
+use std::cmp::Ordering;
+
+use serde::Serialize;
+
+use crate::artifacts::resolve_artifact_uri;
+use crate::errors::{ClientError, GetExperimentErrorCode};
+use crate::store::TrackingStore;
+pub use crate::sweep::Direction;
+use crate::{Run, RunStatus, ViewType};
+
+/// A run selected by a [`RunSelector`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SelectedRun {
+    /// The run.
+    pub run: Run,
+    /// Latest value of the metric in the run.
+    pub metric_value: f64,
+    /// URI of the artifacts of the run, or of the artifact path of the selector in them, with `mlflow-artifacts:`
+    /// URIs resolved against the tracking server.
+    pub artifact_uri: String,
+}
+
+/// Selector of the best runs of some experiments by a metric.
+#[derive(Debug)]
+pub struct RunSelector<'a> {
+    store: &'a dyn TrackingStore,
+    tracking_uri: String,
+    experiment_ids: Vec<String>,
+    metric: String,
+    direction: Direction,
+    filter: Option<String>,
+    statuses: Vec<RunStatus>,
+    required_tags: Vec<(String, String)>,
+    prefer_oldest: bool,
+    artifact_path: Option<String>,
+}
+
+impl<'a> RunSelector<'a> {
+    /// New `RunSelector` of the finished runs of `experiment_ids` with the best `metric` in `direction`.
+    /// `tracking_uri` is used to resolve artifacts proxied by the tracking server.
+    pub fn new(
+        store: &'a dyn TrackingStore,
+        tracking_uri: &str,
+        experiment_ids: &[&str],
+        metric: &str,
+        direction: Direction,
+    ) -> RunSelector<'a> {
+        RunSelector {
+            store,
+            tracking_uri: tracking_uri.to_string(),
+            experiment_ids: experiment_ids.iter().map(|id| id.to_string()).collect(),
+            metric: metric.to_string(),
+            direction,
+            filter: None,
+            statuses: vec![RunStatus::Finished],
+            required_tags: vec![],
+            prefer_oldest: false,
+            artifact_path: None,
+        }
+    }
+
+    /// Only consider the runs matching a search filter, like `params.model = 'resnet'`.
+    pub fn filter(mut self, filter: &str) -> RunSelector<'a> {
+        self.filter = Some(filter.to_string());
+        self
+    }
+
+    /// Only consider the runs with one of `statuses`, instead of the finished runs only.
+    pub fn statuses(mut self, statuses: &[RunStatus]) -> RunSelector<'a> {
+        self.statuses = statuses.to_vec();
+        self
+    }
+
+    /// Only consider the runs with the tag `key` set to `value`.
+    pub fn require_tag(mut self, key: &str, value: &str) -> RunSelector<'a> {
+        self.required_tags
+            .push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Prefer the oldest run among runs with the same value, instead of the most recent one.
+    pub fn prefer_oldest(mut self) -> RunSelector<'a> {
+        self.prefer_oldest = true;
+        self
+    }
+
+    /// Point the artifact URI of the selected runs to `artifact_path` in their artifacts, like `model`.
+    pub fn artifact_path(mut self, artifact_path: &str) -> RunSelector<'a> {
+        self.artifact_path = Some(artifact_path.to_string());
+        self
+    }
+
+    /// The `k` best runs, best first.
+    pub fn top(&self, k: usize) -> Result<Vec<SelectedRun>, ClientError<GetExperimentErrorCode>> {
+        let mut candidates = vec![];
+        let experiment_ids: Vec<&str> = self.experiment_ids.iter().map(String::as_str).collect();
+        let mut page_token = None;
+        loop {
+            let (page, next_page_token) = self.store.search_runs(
+                &experiment_ids,
+                self.filter.as_deref(),
+                Some(ViewType::ActiveOnly),
+                None,
+                None,
+                page_token.as_deref(),
+            )?;
+            candidates.extend(page.into_iter().filter_map(|run| {
+                let metric_value = self.metric_value(&run)?;
+                Some((run, metric_value))
+            }));
+            match next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => break,
+            }
+        }
+
+        candidates.sort_by(|(run, value), (other, other_value)| {
+            let by_value = match self.direction {
+                Direction::Minimize => value.partial_cmp(other_value),
+                Direction::Maximize => other_value.partial_cmp(value),
+            };
+            let by_start_time = if self.prefer_oldest {
+                run.info.start_time.cmp(&other.info.start_time)
+            } else {
+                other.info.start_time.cmp(&run.info.start_time)
+            };
+            by_value
+                .unwrap_or(Ordering::Equal)
+                .then(by_start_time)
+                .then_with(|| run.info.run_id.cmp(&other.info.run_id))
+        });
+        Ok(candidates
+            .into_iter()
+            .take(k)
+            .map(|(run, metric_value)| {
+                let mut artifact_uri =
+                    resolve_artifact_uri(&run.info.artifact_uri, &self.tracking_uri);
+                if let Some(artifact_path) = &self.artifact_path {
+                    artifact_uri = format!(
+                        "{}/{}",
+                        artifact_uri.trim_end_matches('/'),
+                        artifact_path.trim_start_matches('/')
+                    );
+                }
+                SelectedRun {
+                    run,
+                    metric_value,
+                    artifact_uri,
+                }
+            })
+            .collect())
+    }
+
+    /// The best run, if any run has the metric.
+    pub fn best(&self) -> Result<Option<SelectedRun>, ClientError<GetExperimentErrorCode>> {
+        Ok(self.top(1)?.into_iter().next())
+    }
+
+    /// Latest value of the metric of an eligible run, `None` for other runs and runs without a number for it.
+    fn metric_value(&self, run: &Run) -> Option<f64> {
+        if !self.statuses.contains(&run.info.status) {
+            return None;
+        }
+        let data = run.data.as_ref()?;
+        let has_tags = self.required_tags.iter().all(|(key, value)| {
+            data.tags
+                .iter()
+                .any(|tag| &tag.key == key && &tag.value == value)
+        });
+        if !has_tags {
+            return None;
+        }
+        data.metrics
+            .iter()
+            .find(|metric| metric.key == self.metric)
+            .map(|metric| f64::from(metric.value))
+            .filter(|value| !value.is_nan())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::{Direction, RunSelector};
+    use crate::{MemoryStore, RunStatus};
+
+    #[test]
+    fn can_select_best_runs() {
+        let store = MemoryStore::new();
+        let mut run_ids = vec![];
+        for (start_time, accuracy, status, stage) in [
+            (1, 0.8, RunStatus::Finished, "production"),
+            (2, 0.9, RunStatus::Finished, "production"),
+            (3, 0.9, RunStatus::Finished, "production"),
+            (4, 0.95, RunStatus::Failed, "production"),
+            (5, 0.99, RunStatus::Finished, "staging"),
+        ] {
+            let run = store.create_run("0", Some(start_time), None, None).unwrap();
+            store
+                .log_metric(&run.info.run_id, "accuracy", accuracy, start_time, None)
+                .unwrap();
+            store.set_run_tag(&run.info.run_id, "stage", stage).unwrap();
+            store
+                .update_run(&run.info.run_id, status, Some(start_time + 1), None)
+                .unwrap();
+            run_ids.push(run.info.run_id);
+        }
+        store.create_run("0", Some(6), None, None).unwrap();
+
+        let selector = RunSelector::new(&store, "", &["0"], "accuracy", Direction::Maximize)
+            .require_tag("stage", "production")
+            .artifact_path("model");
+        let top: Vec<String> = selector
+            .top(5)
+            .unwrap()
+            .into_iter()
+            .map(|selected| selected.run.info.run_id)
+            .collect();
+        // ties go to the most recent run, failed and staging runs are left out
+        assert_that!(top).is_equal_to(vec![
+            run_ids[2].clone(),
+            run_ids[1].clone(),
+            run_ids[0].clone(),
+        ]);
+
+        let best = selector.prefer_oldest().best().unwrap().unwrap();
+        assert_that!(best.run.info.run_id).is_equal_to(run_ids[1].clone());
+        assert_that!(best.artifact_uri)
+            .is_equal_to(format!("{}/model", best.run.info.artifact_uri));
+
+        let worst = RunSelector::new(&store, "", &["0"], "accuracy", Direction::Minimize)
+            .statuses(&[RunStatus::Finished, RunStatus::Failed])
+            .best()
+            .unwrap();
+        assert_that!(worst.map(|selected| selected.run.info.run_id))
+            .is_equal_to(Some(run_ids[0].clone()));
+    }
+}
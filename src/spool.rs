@@ -0,0 +1,1030 @@
+//! Offline logging: spool logging calls to a local journal while the tracking server is unreachable, and replay them
+//! later with [`sync`].
+//!
+//! The journal is a JSON lines file, one logging call per line, flushed to disk before the call returns. Runs created
+//! while offline get a local ID starting with `local-`, replaced by the ID of the run created on the server when the
+//! journal is synced.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{
+    ClientError, CreateExperimentErrorCode, ErrorCode, GetExperimentErrorCode,
+    ListExperimentsErrorCode, SpoolError,
+};
+use crate::store::TrackingStore;
+use crate::{
+    DatasetInput, Experiment, FileInfo, LifecycleStage, Metric, MetricWithRunId, Param, Run,
+    RunInfo, RunStatus, RunTag, ViewType,
+};
+
+/// Tag holding the local ID of a spooled run on the run created when syncing, so that an interrupted sync does not
+/// create the run twice.
+pub const LOCAL_RUN_ID_TAG: &str = "mlflow.spool.localRunId";
+
+/// Prefix of the IDs of the runs created while offline.
+const LOCAL_RUN_ID_PREFIX: &str = "local-";
+
+/// A logging call recorded in the journal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Entry {
+    CreateRun {
+        run_id: String,
+        experiment_id: String,
+        /// Name of the experiment of a run created before its ID could be resolved, looked up when syncing.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        experiment_name: Option<String>,
+        start_time: u64,
+        #[serde(default)]
+        tags: Vec<RunTag>,
+        run_name: Option<String>,
+    },
+    LogBatch {
+        run_id: String,
+        #[serde(default)]
+        metrics: Vec<Metric>,
+        #[serde(default)]
+        params: Vec<Param>,
+        #[serde(default)]
+        tags: Vec<RunTag>,
+    },
+    DeleteTag {
+        run_id: String,
+        key: String,
+    },
+    UpdateRun {
+        run_id: String,
+        status: RunStatus,
+        end_time: Option<u64>,
+        run_name: Option<String>,
+    },
+}
+
+impl Entry {
+    fn run_id(&self) -> &str {
+        match self {
+            Entry::CreateRun { run_id, .. }
+            | Entry::LogBatch { run_id, .. }
+            | Entry::DeleteTag { run_id, .. }
+            | Entry::UpdateRun { run_id, .. } => run_id,
+        }
+    }
+}
+
+/// Progress of a sync, saved next to the journal so that an interrupted sync resumes where it stopped.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Progress {
+    replayed: usize,
+    run_ids: HashMap<String, String>,
+}
+
+/// Outcome of a [`sync`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SyncReport {
+    /// Number of journal entries replayed by this sync.
+    pub replayed: usize,
+    /// IDs of the runs created on the tracking store, by local ID of the run created while offline.
+    pub run_ids: HashMap<String, String>,
+}
+
+/// Replay the journal at `journal` to `store`, then remove it.
+///
+/// Progress is saved after every entry: when the sync fails, because the server is still unreachable for example,
+/// calling it again resumes after the last replayed entry. Replaying is done at least once, a metric whose entry was
+/// replayed right before a crash can be logged twice.
+pub fn sync(journal: &Path, store: &dyn TrackingStore) -> Result<SyncReport, SpoolError> {
+    let entries = read_journal(journal)?;
+    let progress_path = progress_path(journal);
+    let mut progress: Progress = match fs::read(&progress_path) {
+        Ok(content) => serde_json::from_slice(&content)?,
+        Err(error) if error.kind() == ErrorKind::NotFound => Progress::default(),
+        Err(error) => return Err(error.into()),
+    };
+    let mut replayed = 0;
+    for entry in entries.iter().skip(progress.replayed) {
+        replay(entry, store, &mut progress.run_ids)?;
+        progress.replayed += 1;
+        replayed += 1;
+        let temporary_path = progress_path.with_extension("tmp");
+        fs::write(&temporary_path, serde_json::to_vec(&progress)?)?;
+        fs::rename(&temporary_path, &progress_path)?;
+    }
+    remove_if_exists(journal)?;
+    remove_if_exists(&progress_path)?;
+    Ok(SyncReport {
+        replayed,
+        run_ids: progress.run_ids,
+    })
+}
+
+fn replay(
+    entry: &Entry,
+    store: &dyn TrackingStore,
+    run_ids: &mut HashMap<String, String>,
+) -> Result<(), SpoolError> {
+    match entry {
+        Entry::CreateRun {
+            run_id,
+            experiment_id,
+            experiment_name,
+            start_time,
+            tags,
+            run_name,
+        } => {
+            if run_ids.contains_key(run_id) {
+                return Ok(());
+            }
+            let experiment_id = match experiment_name {
+                Some(experiment_name) => resolve_experiment(experiment_name, store)?,
+                None => experiment_id.clone(),
+            };
+            let filter = format!("tags.`{}` = '{}'", LOCAL_RUN_ID_TAG, run_id);
+            let (existing, _) = store.search_runs(
+                &[&experiment_id],
+                Some(&filter),
+                Some(ViewType::All),
+                Some(1),
+                None,
+                None,
+            )?;
+            let server_run_id = match existing.into_iter().next() {
+                Some(run) => run.info.run_id,
+                None => {
+                    let mut tags = tags.clone();
+                    tags.push(RunTag {
+                        key: LOCAL_RUN_ID_TAG.to_string(),
+                        value: run_id.clone(),
+                    });
+                    store
+                        .create_run(
+                            &experiment_id,
+                            Some(*start_time),
+                            Some(tags),
+                            run_name.as_deref(),
+                        )?
+                        .info
+                        .run_id
+                }
+            };
+            run_ids.insert(run_id.clone(), server_run_id);
+        }
+        Entry::LogBatch {
+            run_id,
+            metrics,
+            params,
+            tags,
+        } => {
+            let metrics: Vec<&Metric> = metrics.iter().collect();
+            let params: Vec<&Param> = params.iter().collect();
+            let tags: Vec<&RunTag> = tags.iter().collect();
+            store.log_batch(
+                &server_run_id(run_id, run_ids)?,
+                non_empty(&metrics),
+                non_empty(&params),
+                non_empty(&tags),
+            )?;
+        }
+        Entry::DeleteTag { run_id, key } => {
+            store.delete_run_tag(&server_run_id(run_id, run_ids)?, key)?
+        }
+        Entry::UpdateRun {
+            run_id,
+            status,
+            end_time,
+            run_name,
+        } => {
+            store.update_run(
+                &server_run_id(run_id, run_ids)?,
+                *status,
+                *end_time,
+                run_name.as_deref(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// ID of the experiment named `experiment_name`, created when it does not exist yet, as `set_experiment` does.
+fn resolve_experiment(
+    experiment_name: &str,
+    store: &dyn TrackingStore,
+) -> Result<String, SpoolError> {
+    if let Ok(found) = store.get_experiment_by_name(experiment_name) {
+        Ok(found.experiment_id)
+    } else {
+        Ok(store.create_experiment(experiment_name, None)?)
+    }
+}
+
+fn server_run_id(run_id: &str, run_ids: &HashMap<String, String>) -> Result<String, SpoolError> {
+    match run_ids.get(run_id) {
+        Some(server_run_id) => Ok(server_run_id.clone()),
+        None if run_id.starts_with(LOCAL_RUN_ID_PREFIX) => {
+            Err(SpoolError::UnknownRun(run_id.to_string()))
+        }
+        None => Ok(run_id.to_string()),
+    }
+}
+
+fn non_empty<T>(items: &[T]) -> Option<&[T]> {
+    Some(items).filter(|items| !items.is_empty())
+}
+
+fn progress_path(journal: &Path) -> PathBuf {
+    let mut path = journal.as_os_str().to_owned();
+    path.push(".progress");
+    PathBuf::from(path)
+}
+
+fn remove_if_exists(path: &Path) -> std::io::Result<()> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+fn read_journal(journal: &Path) -> Result<Vec<Entry>, SpoolError> {
+    let file = match File::open(journal) {
+        Ok(file) => file,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error.into()),
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            entries.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(entries)
+}
+
+/// Drop a last line left incomplete by a crash while appending to the journal: that call never returned.
+fn truncate_incomplete_entry(journal: &Path) -> std::io::Result<()> {
+    let content = match fs::read(journal) {
+        Ok(content) => content,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error),
+    };
+    if content.is_empty() || content.ends_with(b"\n") {
+        return Ok(());
+    }
+    let complete = content
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map_or(0, |index| index + 1);
+    OpenOptions::new()
+        .write(true)
+        .open(journal)?
+        .set_len(complete as u64)
+}
+
+/// Whether a call failed without reaching the store: the connection could not be made or timed out. Other errors
+/// are answers of the store, returned rather than spooled.
+pub(crate) fn is_unreachable<E: ErrorCode>(error: &ClientError<E>) -> bool {
+    match error {
+        ClientError::QueryError(error) => {
+            error.is_timeout()
+                || error
+                    .get_ref()
+                    .and_then(|cause| cause.downcast_ref::<hyper::Error>())
+                    .is_some_and(hyper::Error::is_connect)
+        }
+        _ => false,
+    }
+}
+
+#[derive(Debug, Default)]
+struct SpoolState {
+    /// Runs with calls in the journal: their later calls are spooled too, to be replayed in order.
+    spooled_runs: HashSet<String>,
+    /// Last known metadata of the runs, to answer calls about them while offline.
+    runs: HashMap<String, RunInfo>,
+    /// Last known metadata of the experiments, to answer calls about them while offline.
+    experiments: HashMap<String, Experiment>,
+    /// IDs on the tracking store of the synced runs, by local ID.
+    run_ids: HashMap<String, String>,
+}
+
+impl SpoolState {
+    fn resolve(&self, run_id: &str) -> String {
+        self.run_ids
+            .get(run_id)
+            .cloned()
+            .unwrap_or_else(|| run_id.to_string())
+    }
+}
+
+/// Tracking store spooling logging calls to a journal when the wrapped store is unreachable.
+///
+/// Run creation, metrics, params, tags and status updates are sent to the wrapped store as long as it answers. When
+/// the store can not be connected to or times out, the call is appended to the journal instead, as are all the later
+/// calls for that run so that they are replayed in order by [`SpoolStore::sync`]. Other errors are returned as is.
+/// Other calls are forwarded to the wrapped store, except `get_experiment` and `get_run` that answer offline with the
+/// metadata of the experiments last seen online and of the runs seen or spooled. Clones share the same journal.
+#[derive(Debug, Clone)]
+pub struct SpoolStore {
+    inner: Arc<dyn TrackingStore>,
+    journal: PathBuf,
+    state: Arc<Mutex<SpoolState>>,
+}
+
+impl SpoolStore {
+    /// New `SpoolStore` wrapping `inner`, with its journal at `journal`. Calls already in an existing journal are
+    /// kept, to be synced with the new ones.
+    pub fn new(inner: Box<dyn TrackingStore>, journal: &Path) -> Result<SpoolStore, SpoolError> {
+        if let Some(parent) = journal.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        truncate_incomplete_entry(journal)?;
+        let mut state = SpoolState::default();
+        for entry in read_journal(journal)? {
+            if let Entry::CreateRun {
+                run_id,
+                experiment_id,
+                start_time,
+                run_name,
+                ..
+            } = &entry
+            {
+                state.runs.insert(
+                    run_id.clone(),
+                    local_run_info(run_id, experiment_id, *start_time, run_name.clone()),
+                );
+            }
+            state.spooled_runs.insert(entry.run_id().to_string());
+        }
+        Ok(SpoolStore {
+            inner: Arc::from(inner),
+            journal: journal.to_path_buf(),
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Path to the journal.
+    pub fn journal(&self) -> &Path {
+        &self.journal
+    }
+
+    /// Whether some calls are waiting in the journal to be synced.
+    pub fn has_pending(&self) -> bool {
+        !self.state().spooled_runs.is_empty()
+    }
+
+    /// Replay the journal to the wrapped store, see [`sync`]. Local run IDs keep working after the sync, they are
+    /// replaced by the IDs of the synced runs.
+    pub fn sync(&self) -> Result<SyncReport, SpoolError> {
+        let mut state = self.state();
+        let report = sync(&self.journal, &*self.inner)?;
+        state.spooled_runs.clear();
+        state.run_ids.extend(report.run_ids.clone());
+        Ok(report)
+    }
+
+    /// Create a run in the experiment named `experiment_name`. While the wrapped store is unreachable, the run is
+    /// spooled against the name: the experiment is looked up, and created if needed, when the journal is synced.
+    pub fn create_run_in_experiment_named(
+        &self,
+        experiment_name: &str,
+        start_time: Option<u64>,
+        tags: Option<Vec<RunTag>>,
+        run_name: Option<&str>,
+    ) -> Result<Run, ClientError<GetExperimentErrorCode>> {
+        match self.inner.get_experiment_by_name(experiment_name) {
+            Ok(experiment) => {
+                self.create_run(&experiment.experiment_id, start_time, tags, run_name)
+            }
+            Err(error) if is_unreachable(&error) => {
+                let mut state = self.state();
+                self.spool_run(
+                    &mut state,
+                    "",
+                    Some(experiment_name),
+                    start_time,
+                    tags,
+                    run_name,
+                )
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Spool the creation of a run with a new local ID.
+    fn spool_run(
+        &self,
+        state: &mut SpoolState,
+        experiment_id: &str,
+        experiment_name: Option<&str>,
+        start_time: Option<u64>,
+        tags: Option<Vec<RunTag>>,
+        run_name: Option<&str>,
+    ) -> Result<Run, ClientError<GetExperimentErrorCode>> {
+        let tags = tags.unwrap_or_default();
+        let run_name = run_name.map(str::to_string).or_else(|| {
+            tags.iter()
+                .find(|tag| tag.key == "mlflow.runName")
+                .map(|tag| tag.value.clone())
+        });
+        let run_id = format!(
+            "{}{}",
+            LOCAL_RUN_ID_PREFIX,
+            uuid::Uuid::new_v4().to_simple()
+        );
+        let info = local_run_info(
+            &run_id,
+            experiment_id,
+            start_time.unwrap_or_else(crate::store::now),
+            run_name.clone(),
+        );
+        self.append(&Entry::CreateRun {
+            run_id: run_id.clone(),
+            experiment_id: experiment_id.to_string(),
+            experiment_name: experiment_name.map(str::to_string),
+            start_time: info.start_time,
+            tags: tags.clone(),
+            run_name,
+        })?;
+        state.spooled_runs.insert(run_id.clone());
+        state.runs.insert(run_id, info.clone());
+        Ok(Run {
+            info,
+            data: Some(crate::RunData {
+                tags,
+                ..Default::default()
+            }),
+            inputs: None,
+        })
+    }
+
+    fn state(&self) -> MutexGuard<'_, SpoolState> {
+        // the journal is appended before the state is updated, a panic can not leave them out of step
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn append(&self, entry: &Entry) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.journal)?;
+        file.write_all(&line)?;
+        file.sync_data()
+    }
+
+    /// Send a call for a run to the wrapped store, or spool it when the run already has spooled calls or the store
+    /// does not answer. `spooled` builds the journal entry and the value returned in place of the store's.
+    fn forward_or_spool<T, E: ErrorCode>(
+        &self,
+        run_id: &str,
+        forward: impl FnOnce(&dyn TrackingStore, &str) -> Result<T, ClientError<E>>,
+        spooled: impl FnOnce(&mut SpoolState, &str) -> (Entry, T),
+    ) -> Result<T, ClientError<E>> {
+        let mut state = self.state();
+        let run_id = state.resolve(run_id);
+        if !state.spooled_runs.contains(&run_id) {
+            match forward(&*self.inner, &run_id) {
+                Err(error) if is_unreachable(&error) => {}
+                result => return result,
+            }
+        }
+        let (entry, value) = spooled(&mut state, &run_id);
+        self.append(&entry)?;
+        state.spooled_runs.insert(run_id);
+        Ok(value)
+    }
+
+    fn log_spooled_batch(
+        &self,
+        run_id: &str,
+        metrics: &[&Metric],
+        params: &[&Param],
+        tags: &[&RunTag],
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.forward_or_spool(
+            run_id,
+            |store, run_id| {
+                store.log_batch(
+                    run_id,
+                    non_empty(metrics),
+                    non_empty(params),
+                    non_empty(tags),
+                )
+            },
+            |_, run_id| {
+                let entry = Entry::LogBatch {
+                    run_id: run_id.to_string(),
+                    metrics: metrics.iter().map(|&metric| metric.clone()).collect(),
+                    params: params.iter().map(|&param| param.clone()).collect(),
+                    tags: tags.iter().map(|&tag| tag.clone()).collect(),
+                };
+                (entry, ())
+            },
+        )
+    }
+}
+
+fn local_run_info(
+    run_id: &str,
+    experiment_id: &str,
+    start_time: u64,
+    run_name: Option<String>,
+) -> RunInfo {
+    RunInfo {
+        run_id: run_id.to_string(),
+        run_name,
+        experiment_id: experiment_id.to_string(),
+        status: RunStatus::Running,
+        start_time,
+        end_time: None,
+        artifact_uri: String::new(),
+        lifecycle_stage: LifecycleStage::Active,
+    }
+}
+
+impl TrackingStore for SpoolStore {
+    fn create_experiment(
+        &self,
+        name: &str,
+        artifact_location: Option<&str>,
+    ) -> Result<String, ClientError<CreateExperimentErrorCode>> {
+        self.inner.create_experiment(name, artifact_location)
+    }
+
+    fn list_experiments(
+        &self,
+        view_type: Option<ViewType>,
+    ) -> Result<Vec<Experiment>, ClientError<ListExperimentsErrorCode>> {
+        self.inner.list_experiments(view_type)
+    }
+
+    fn get_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<Experiment, ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        match self.inner.get_experiment(experiment_id) {
+            Ok(experiment) => {
+                state
+                    .experiments
+                    .insert(experiment_id.to_string(), experiment.clone());
+                Ok(experiment)
+            }
+            // only experiments seen while the store was reachable are known offline
+            Err(error) if is_unreachable(&error) => {
+                state.experiments.get(experiment_id).cloned().ok_or(error)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    fn get_experiment_by_name(
+        &self,
+        experiment_name: &str,
+    ) -> Result<Experiment, ClientError<GetExperimentErrorCode>> {
+        self.inner.get_experiment_by_name(experiment_name)
+    }
+
+    fn delete_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.inner.delete_experiment(experiment_id)
+    }
+
+    fn restore_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.inner.restore_experiment(experiment_id)
+    }
+
+    fn update_experiment(
+        &self,
+        experiment_id: &str,
+        new_name: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.inner.update_experiment(experiment_id, new_name)
+    }
+
+    fn set_experiment_tag(
+        &self,
+        experiment_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.inner.set_experiment_tag(experiment_id, key, value)
+    }
+
+    fn create_run(
+        &self,
+        experiment_id: &str,
+        start_time: Option<u64>,
+        tags: Option<Vec<RunTag>>,
+        run_name: Option<&str>,
+    ) -> Result<Run, ClientError<GetExperimentErrorCode>> {
+        let mut state = self.state();
+        match self
+            .inner
+            .create_run(experiment_id, start_time, tags.clone(), run_name)
+        {
+            Err(error) if is_unreachable(&error) => {}
+            Ok(run) => {
+                state.runs.insert(run.info.run_id.clone(), run.info.clone());
+                return Ok(run);
+            }
+            Err(error) => return Err(error),
+        }
+        self.spool_run(&mut state, experiment_id, None, start_time, tags, run_name)
+    }
+
+    fn delete_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let run_id = self.state().resolve(run_id);
+        self.inner.delete_run(&run_id)
+    }
+
+    fn restore_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let run_id = self.state().resolve(run_id);
+        self.inner.restore_run(&run_id)
+    }
+
+    fn get_run(&self, run_id: &str) -> Result<Run, ClientError<GetExperimentErrorCode>> {
+        let state = self.state();
+        let run_id = state.resolve(run_id);
+        let result =
+            if state.spooled_runs.contains(&run_id) && run_id.starts_with(LOCAL_RUN_ID_PREFIX) {
+                None
+            } else {
+                match self.inner.get_run(&run_id) {
+                    Err(error) if is_unreachable(&error) => Some(Err(error)),
+                    result => return result,
+                }
+            };
+        match (state.runs.get(&run_id), result) {
+            (Some(info), _) => Ok(Run {
+                info: info.clone(),
+                data: None,
+                inputs: None,
+            }),
+            (None, Some(error)) => error,
+            (None, None) => Err(ClientError::ApiError {
+                error_code: GetExperimentErrorCode::ResourceDoesNotExist,
+                message: crate::store::run_not_found(&run_id),
+            }),
+        }
+    }
+
+    fn update_run(
+        &self,
+        run_id: &str,
+        status: RunStatus,
+        end_time: Option<u64>,
+        run_name: Option<&str>,
+    ) -> Result<RunInfo, ClientError<GetExperimentErrorCode>> {
+        self.forward_or_spool(
+            run_id,
+            |store, run_id| store.update_run(run_id, status, end_time, run_name),
+            |state, run_id| {
+                let entry = Entry::UpdateRun {
+                    run_id: run_id.to_string(),
+                    status,
+                    end_time,
+                    run_name: run_name.map(str::to_string),
+                };
+                let info = state
+                    .runs
+                    .entry(run_id.to_string())
+                    .or_insert_with(|| local_run_info(run_id, "", 0, run_name.map(str::to_string)));
+                info.status = status;
+                info.end_time = end_time.or(info.end_time);
+                if let Some(run_name) = run_name {
+                    info.run_name = Some(run_name.to_string());
+                }
+                (entry, info.clone())
+            },
+        )
+        .inspect(|info| {
+            self.state().runs.insert(info.run_id.clone(), info.clone());
+        })
+    }
+
+    fn search_runs(
+        &self,
+        experiment_ids: &[&str],
+        filter: Option<&str>,
+        run_view_type: Option<ViewType>,
+        max_results: Option<u32>,
+        order_by: Option<&[&str]>,
+        page_token: Option<&str>,
+    ) -> Result<(Vec<Run>, Option<String>), ClientError<GetExperimentErrorCode>> {
+        self.inner.search_runs(
+            experiment_ids,
+            filter,
+            run_view_type,
+            max_results,
+            order_by,
+            page_token,
+        )
+    }
+
+    fn set_run_tag(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let tag = RunTag {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        self.log_spooled_batch(run_id, &[], &[], &[&tag])
+    }
+
+    fn delete_run_tag(
+        &self,
+        run_id: &str,
+        key: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.forward_or_spool(
+            run_id,
+            |store, run_id| store.delete_run_tag(run_id, key),
+            |_, run_id| {
+                let entry = Entry::DeleteTag {
+                    run_id: run_id.to_string(),
+                    key: key.to_string(),
+                };
+                (entry, ())
+            },
+        )
+    }
+
+    fn log_metric(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: f32,
+        timestamp: u64,
+        step: Option<u64>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let metric = Metric {
+            key: key.to_string(),
+            value,
+            timestamp,
+            step: step.unwrap_or(0),
+        };
+        self.log_spooled_batch(run_id, &[&metric], &[], &[])
+    }
+
+    fn get_metric_history(
+        &self,
+        run_id: &str,
+        metric_key: &str,
+        max_results: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<(Vec<Metric>, Option<String>), ClientError<GetExperimentErrorCode>> {
+        let run_id = self.state().resolve(run_id);
+        self.inner
+            .get_metric_history(&run_id, metric_key, max_results, page_token)
+    }
+
+    fn get_metric_history_bulk_interval(
+        &self,
+        run_ids: &[&str],
+        metric_key: &str,
+        start_step: Option<u64>,
+        end_step: Option<u64>,
+        max_results: Option<u32>,
+    ) -> Result<Vec<MetricWithRunId>, ClientError<GetExperimentErrorCode>> {
+        let run_ids: Vec<String> = {
+            let state = self.state();
+            run_ids.iter().map(|run_id| state.resolve(run_id)).collect()
+        };
+        let run_ids: Vec<&str> = run_ids.iter().map(String::as_str).collect();
+        self.inner.get_metric_history_bulk_interval(
+            &run_ids,
+            metric_key,
+            start_step,
+            end_step,
+            max_results,
+        )
+    }
+
+    fn log_param(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let param = Param {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        self.log_spooled_batch(run_id, &[], &[&param], &[])
+    }
+
+    fn log_batch(
+        &self,
+        run_id: &str,
+        metrics: Option<&[&Metric]>,
+        params: Option<&[&Param]>,
+        tags: Option<&[&RunTag]>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        self.log_spooled_batch(
+            run_id,
+            metrics.unwrap_or_default(),
+            params.unwrap_or_default(),
+            tags.unwrap_or_default(),
+        )
+    }
+
+    fn log_inputs(
+        &self,
+        run_id: &str,
+        datasets: Option<&[DatasetInput]>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+        let run_id = self.state().resolve(run_id);
+        self.inner.log_inputs(&run_id, datasets)
+    }
+
+    fn list_artifacts(
+        &self,
+        run_id: &str,
+        path: Option<&str>,
+    ) -> Result<(String, Vec<FileInfo>), ClientError<GetExperimentErrorCode>> {
+        let run_id = self.state().resolve(run_id);
+        self.inner.list_artifacts(&run_id, path)
+    }
+
+    fn get_run_deleted_time(
+        &self,
+        run_id: &str,
+    ) -> Result<Option<u64>, ClientError<GetExperimentErrorCode>> {
+        let run_id = self.state().resolve(run_id);
+        self.inner.get_run_deleted_time(&run_id)
+    }
+
+    fn purge_run(&self, run_id: &str) -> Result<bool, ClientError<GetExperimentErrorCode>> {
+        let run_id = self.state().resolve(run_id);
+        self.inner.purge_run(&run_id)
+    }
+
+    fn purge_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<bool, ClientError<GetExperimentErrorCode>> {
+        self.inner.purge_experiment(experiment_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::{sync, SpoolStore, LOCAL_RUN_ID_TAG};
+    use crate::store::TrackingStore;
+    use crate::{MemoryStore, RunStatus};
+
+    fn unreachable_store() -> Box<dyn TrackingStore> {
+        Box::new(crate::MLflowAPI::new("http://127.0.0.1:1").unwrap())
+    }
+
+    #[test]
+    fn can_spool_while_unreachable_and_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("spool").join("journal.jsonl");
+        let spool = SpoolStore::new(unreachable_store(), &journal).unwrap();
+
+        // experiments are not known while offline until they were seen online
+        assert_that!(spool.get_experiment("0")).is_err();
+        let run = spool
+            .create_run("0", Some(1), None, Some("offline"))
+            .unwrap();
+        let local_run_id = run.info.run_id;
+        assert_that!(local_run_id.as_str()).starts_with("local-");
+        assert_that!(spool.get_experiment("0")).is_err();
+        spool
+            .log_metric(&local_run_id, "loss", 0.5, 2, Some(1))
+            .unwrap();
+        spool.log_param(&local_run_id, "alpha", "0.1").unwrap();
+        spool.set_run_tag(&local_run_id, "team", "core").unwrap();
+        spool
+            .update_run(&local_run_id, RunStatus::Finished, Some(3), None)
+            .unwrap();
+        assert_that!(spool.get_run(&local_run_id))
+            .is_ok()
+            .map(|run| &run.info.status)
+            .is_equal_to(RunStatus::Finished);
+        assert_that!(spool.has_pending()).is_true();
+
+        // reopening the journal keeps the spooled calls
+        drop(spool);
+        let spool = SpoolStore::new(unreachable_store(), &journal).unwrap();
+        assert_that!(spool.has_pending()).is_true();
+
+        let store = MemoryStore::new();
+        let report = sync(&journal, &store).unwrap();
+        assert_that!(report.replayed).is_equal_to(5);
+        assert_that!(journal.exists()).is_false();
+
+        let runs = store.runs();
+        assert_that!(runs).has_length(1);
+        let server_run_id = &runs[0].info.run_id;
+        assert_that!(report.run_ids.get(&local_run_id)).is_equal_to(Some(server_run_id));
+        assert_that!(runs[0].info.status).is_equal_to(RunStatus::Finished);
+        assert_that!(runs[0].info.run_name.as_deref()).is_equal_to(Some("offline"));
+        assert_that!(store.metric_history_of(server_run_id, "loss")).has_length(1);
+        assert_that!(store.params_of(server_run_id).get("alpha").cloned())
+            .is_equal_to(Some("0.1".to_string()));
+        assert_that!(store.tags_of(server_run_id).get(LOCAL_RUN_ID_TAG).cloned())
+            .is_equal_to(Some(local_run_id));
+    }
+
+    #[test]
+    fn can_sync_again_without_duplicating_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("journal.jsonl");
+        let spool = SpoolStore::new(unreachable_store(), &journal).unwrap();
+        let run = spool.create_run("0", None, None, None).unwrap();
+        spool
+            .update_run(&run.info.run_id, RunStatus::Failed, None, None)
+            .unwrap();
+        let content = std::fs::read(&journal).unwrap();
+
+        let store = MemoryStore::new();
+        sync(&journal, &store).unwrap();
+        // a sync interrupted before its progress was saved replays the journal again
+        std::fs::write(&journal, &content).unwrap();
+        let report = sync(&journal, &store).unwrap();
+
+        assert_that!(report.replayed).is_equal_to(2);
+        assert_that!(store.runs()).has_length(1);
+        assert_that!(sync(&journal, &store))
+            .is_ok()
+            .map(|report| &report.replayed)
+            .is_equal_to(0);
+    }
+
+    #[test]
+    fn can_forward_to_reachable_store_and_remap_after_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("journal.jsonl");
+        let store = MemoryStore::new();
+        let spool = SpoolStore::new(Box::new(store.clone()), &journal).unwrap();
+
+        let run = spool.create_run("0", None, None, None).unwrap();
+        spool
+            .log_metric(&run.info.run_id, "loss", 1., 0, None)
+            .unwrap();
+        assert_that!(journal.exists()).is_false();
+        assert_that!(store.metric_history_of(&run.info.run_id, "loss")).has_length(1);
+        assert_that!(spool.create_run("missing", None, None, None)).is_err();
+
+        std::fs::write(
+            &journal,
+            "{\"type\":\"create_run\",\"run_id\":\"local-1\",\"experiment_id\":\"0\",\"start_time\":1,\"run_name\":null}\n{\"type\":\"log_b",
+        )
+        .unwrap();
+        let spool = SpoolStore::new(Box::new(store.clone()), &journal).unwrap();
+        spool.log_metric("local-1", "loss", 1., 0, None).unwrap();
+        let report = spool.sync().unwrap();
+        assert_that!(report.replayed).is_equal_to(2);
+        spool.log_metric("local-1", "loss", 2., 1, None).unwrap();
+        assert_that!(journal.exists()).is_false();
+        assert_that!(store.metric_history_of(&report.run_ids["local-1"], "loss")).has_length(2);
+    }
+
+    #[test]
+    fn should_return_errors_of_reachable_store() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let uri = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 4096];
+                let _ = std::io::Read::read(&mut stream, &mut request);
+                let _ = std::io::Write::write_all(
+                    &mut stream,
+                    b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                );
+            }
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("journal.jsonl");
+        let spool =
+            SpoolStore::new(Box::new(crate::MLflowAPI::new(&uri).unwrap()), &journal).unwrap();
+
+        assert_that!(spool.create_run("0", None, None, None)).is_err();
+        assert_that!(spool.log_metric("run", "loss", 1., 0, None)).is_err();
+        assert_that!(spool.get_experiment("0")).is_err();
+        assert_that!(journal.exists()).is_false();
+        assert_that!(spool.has_pending()).is_false();
+    }
+}
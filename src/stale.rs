@@ -0,0 +1,252 @@
+//! Detection of stale runs, left running by crashed jobs.
+//!
+//! A job that crashes never ends its run, which stays `RUNNING` forever. A [`StaleRunDetector`] finds the running
+//! runs without activity for longer than a threshold, and kills them. The last activity of a run is the latest of its
+//! start time, the timestamps of its latest metrics, and its [`HEARTBEAT_TAG`], refreshed by a [`Heartbeat`] for
+//! runs that don't log metrics often, see [`crate::MLflowClient::enable_heartbeat`].
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::errors::{ClientError, GetExperimentErrorCode};
+use crate::store::TrackingStore;
+use crate::{Run, RunStatus, ViewType};
+
+/// Run tag holding the last heartbeat of the job, in milliseconds since the epoch.
+pub const HEARTBEAT_TAG: &str = "mlflow.heartbeat";
+
+/// A running run without activity for longer than the threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StaleRun {
+    /// ID of the run.
+    pub run_id: String,
+    /// Name of the run.
+    pub run_name: Option<String>,
+    /// ID of the experiment of the run.
+    pub experiment_id: String,
+    /// Last activity of the run, in milliseconds since the epoch.
+    pub last_activity: u64,
+}
+
+/// Finder of the running runs of some experiments without activity for longer than a threshold.
+#[derive(Debug)]
+pub struct StaleRunDetector<'a> {
+    store: &'a dyn TrackingStore,
+    experiment_ids: Vec<String>,
+    threshold: Duration,
+}
+
+impl<'a> StaleRunDetector<'a> {
+    /// New `StaleRunDetector` for the runs of `experiment_ids` without activity for longer than `threshold`.
+    pub fn new(
+        store: &'a dyn TrackingStore,
+        experiment_ids: &[&str],
+        threshold: Duration,
+    ) -> StaleRunDetector<'a> {
+        StaleRunDetector {
+            store,
+            experiment_ids: experiment_ids.iter().map(|id| id.to_string()).collect(),
+            threshold,
+        }
+    }
+
+    /// Find the stale runs, without changing anything.
+    pub fn find(&self) -> Result<Vec<StaleRun>, ClientError<GetExperimentErrorCode>> {
+        let cutoff = crate::store::now().saturating_sub(self.threshold.as_millis() as u64);
+        let experiment_ids: Vec<&str> = self.experiment_ids.iter().map(String::as_str).collect();
+        let mut stale = vec![];
+        let mut page_token = None;
+        loop {
+            let (page, next_page_token) = self.store.search_runs(
+                &experiment_ids,
+                Some("attributes.status = 'RUNNING'"),
+                Some(ViewType::ActiveOnly),
+                None,
+                None,
+                page_token.as_deref(),
+            )?;
+            stale.extend(
+                page.iter()
+                    // the filter is only a hint for stores ignoring it
+                    .filter(|run| run.info.status == RunStatus::Running)
+                    .map(|run| StaleRun {
+                        run_id: run.info.run_id.clone(),
+                        run_name: run.run_name().map(str::to_string),
+                        experiment_id: run.info.experiment_id.clone(),
+                        last_activity: last_activity(run),
+                    })
+                    .filter(|run| run.last_activity < cutoff),
+            );
+            match next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(stale),
+            }
+        }
+    }
+
+    /// Mark the `stale` runs as killed, ending them now. Returns the IDs of the runs killed, leaving alone the ones
+    /// that became active again since they were found.
+    pub fn kill(
+        &self,
+        stale: &[StaleRun],
+    ) -> Result<Vec<String>, ClientError<GetExperimentErrorCode>> {
+        let cutoff = crate::store::now().saturating_sub(self.threshold.as_millis() as u64);
+        let mut killed = vec![];
+        for run in stale {
+            let current = self.store.get_run(&run.run_id)?;
+            if current.info.status != RunStatus::Running || last_activity(&current) >= cutoff {
+                continue;
+            }
+            self.store.update_run(
+                &run.run_id,
+                RunStatus::Killed,
+                Some(crate::store::now()),
+                None,
+            )?;
+            killed.push(run.run_id.clone());
+        }
+        Ok(killed)
+    }
+
+    /// Find then kill the stale runs.
+    pub fn run(&self) -> Result<Vec<String>, ClientError<GetExperimentErrorCode>> {
+        self.kill(&self.find()?)
+    }
+}
+
+/// Latest of the start time, the timestamps of the latest metrics, and the heartbeat of a run.
+pub fn last_activity(run: &Run) -> u64 {
+    let data = run.data.as_ref();
+    let metrics = data
+        .into_iter()
+        .flat_map(|data| data.metrics.iter().map(|metric| metric.timestamp));
+    let heartbeat = data
+        .into_iter()
+        .flat_map(|data| data.tags.iter())
+        .filter(|tag| tag.key == HEARTBEAT_TAG)
+        .filter_map(|tag| tag.value.parse().ok());
+    metrics.chain(heartbeat).fold(run.info.start_time, u64::max)
+}
+
+/// Background thread setting the [`HEARTBEAT_TAG`] of a run at a regular interval, stopped when dropped.
+#[derive(Debug)]
+pub struct Heartbeat {
+    run_id: Arc<Mutex<Option<String>>>,
+    error: Arc<Mutex<Option<ClientError<GetExperimentErrorCode>>>>,
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Heartbeat {
+    /// Start beating every `interval` through `store`, for no run until [`Heartbeat::set_run`].
+    pub fn start(store: Arc<dyn TrackingStore>, interval: Duration) -> Heartbeat {
+        let run_id: Arc<Mutex<Option<String>>> = Arc::default();
+        let error: Arc<Mutex<Option<ClientError<GetExperimentErrorCode>>>> = Arc::default();
+        let (stop, stopped) = mpsc::channel();
+        let thread = {
+            let run_id = Arc::clone(&run_id);
+            let error = Arc::clone(&error);
+            std::thread::spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    let run_id = run_id.lock().expect("heartbeat lock poisoned").clone();
+                    if let Some(run_id) = run_id {
+                        // a missed beat is caught up by the next one, the error is kept for Heartbeat::take_error
+                        if let Err(failure) = beat(store.as_ref(), &run_id) {
+                            *error.lock().expect("heartbeat lock poisoned") = Some(failure);
+                        }
+                    }
+                }
+            })
+        };
+        Heartbeat {
+            run_id,
+            error,
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+
+    /// Beat for `run_id` from now on, or stop beating if `None`.
+    pub fn set_run(&self, run_id: Option<&str>) {
+        *self.run_id.lock().expect("heartbeat lock poisoned") = run_id.map(str::to_string);
+    }
+
+    /// Take the error of the last failed beat since the previous call, if any.
+    pub fn take_error(&self) -> Option<ClientError<GetExperimentErrorCode>> {
+        self.error.lock().expect("heartbeat lock poisoned").take()
+    }
+}
+
+impl Drop for Heartbeat {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Set the [`HEARTBEAT_TAG`] of a run to now.
+pub fn beat(
+    store: &dyn TrackingStore,
+    run_id: &str,
+) -> Result<(), ClientError<GetExperimentErrorCode>> {
+    store.set_run_tag(run_id, HEARTBEAT_TAG, &crate::store::now().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use spectral::prelude::*;
+
+    use super::{beat, Heartbeat, StaleRunDetector};
+    use crate::{MemoryStore, RunStatus};
+
+    #[test]
+    fn can_kill_stale_runs() {
+        let store = MemoryStore::new();
+        let now = crate::store::now();
+        let crashed = store.create_run("0", Some(1), None, None).unwrap();
+        let logging = store.create_run("0", Some(1), None, None).unwrap();
+        store
+            .log_metric(&logging.info.run_id, "loss", 0.5, now, Some(1))
+            .unwrap();
+        let beating = store.create_run("0", Some(1), None, None).unwrap();
+        beat(&store, &beating.info.run_id).unwrap();
+        let recent = store.create_run("0", Some(now), None, None).unwrap();
+        let finished = store.create_run("0", Some(1), None, None).unwrap();
+        store
+            .update_run(&finished.info.run_id, RunStatus::Finished, Some(2), None)
+            .unwrap();
+
+        let detector = StaleRunDetector::new(&store, &["0"], Duration::from_secs(3600));
+        let stale = detector.find().unwrap();
+        assert_that!(stale.iter().map(|run| &run.run_id).collect::<Vec<_>>())
+            .is_equal_to(vec![&crashed.info.run_id]);
+        assert_that!(stale[0].last_activity).is_equal_to(1);
+
+        assert_that!(detector.kill(&stale).unwrap()).is_equal_to(vec![crashed.info.run_id.clone()]);
+        let run = store.get_run(&crashed.info.run_id).unwrap();
+        assert_that!(run.info.status).is_equal_to(RunStatus::Killed);
+        assert_that!(run.info.end_time).is_some();
+        assert_that!(store.get_run(&recent.info.run_id).unwrap().info.status)
+            .is_equal_to(RunStatus::Running);
+        assert_that!(detector.find().unwrap().len()).is_equal_to(0);
+    }
+
+    #[test]
+    fn can_report_failed_beats() {
+        let store = MemoryStore::new();
+        let heartbeat = Heartbeat::start(std::sync::Arc::new(store), Duration::from_millis(10));
+        heartbeat.set_run(Some("missing"));
+        std::thread::sleep(Duration::from_millis(100));
+
+        heartbeat.set_run(None);
+        assert_that!(heartbeat.take_error()).is_some();
+    }
+}
@@ -0,0 +1,557 @@
+//! Tracking stores, the backends where experiments and runs are recorded.
+
+use crate::errors::{
+    ClientError, CreateExperimentErrorCode, ErrorCode, GetExperimentErrorCode,
+    ListExperimentsErrorCode, SetupError,
+};
+use crate::{
+    DatasetInput, Experiment, FileInfo, Metric, MetricWithRunId, Param, Run, RunInfo, RunStatus,
+    RunTag, ViewType,
+};
+
+/// Operations of the MLflow tracking API, implemented by every backend: the REST `MLflowAPI`, the local `FileStore` and
+/// the `MemoryStore`.
+pub trait TrackingStore: std::fmt::Debug + Send + Sync {
+    /// Create an experiment with a name. Returns the ID of the newly created experiment.
+    fn create_experiment(
+        &self,
+        name: &str,
+        artifact_location: Option<&str>,
+    ) -> Result<String, ClientError<CreateExperimentErrorCode>>;
+
+    /// Get a list of all experiments.
+    fn list_experiments(
+        &self,
+        view_type: Option<ViewType>,
+    ) -> Result<Vec<Experiment>, ClientError<ListExperimentsErrorCode>>;
+
+    /// Get metadata for an experiment. This method works on deleted experiments.
+    fn get_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<Experiment, ClientError<GetExperimentErrorCode>>;
+
+    /// Get metadata for an experiment, preferring the active experiment if an active and deleted experiment share
+    /// the same name.
+    fn get_experiment_by_name(
+        &self,
+        experiment_name: &str,
+    ) -> Result<Experiment, ClientError<GetExperimentErrorCode>>;
+
+    /// Mark an experiment and associated metadata, runs, metrics, params, and tags for deletion.
+    fn delete_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// Restore an experiment marked for deletion.
+    fn restore_experiment(
+        &self,
+        experiment_id: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// Update experiment metadata.
+    fn update_experiment(
+        &self,
+        experiment_id: &str,
+        new_name: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// Set a tag on an experiment.
+    fn set_experiment_tag(
+        &self,
+        experiment_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// Create a new run within an experiment.
+    fn create_run(
+        &self,
+        experiment_id: &str,
+        start_time: Option<u64>,
+        tags: Option<Vec<RunTag>>,
+        run_name: Option<&str>,
+    ) -> Result<Run, ClientError<GetExperimentErrorCode>>;
+
+    /// Mark a run for deletion.
+    fn delete_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// Restore a deleted run.
+    fn restore_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// Get metadata, metrics, params, and tags for a run.
+    fn get_run(&self, run_id: &str) -> Result<Run, ClientError<GetExperimentErrorCode>>;
+
+    /// Update run metadata.
+    fn update_run(
+        &self,
+        run_id: &str,
+        status: RunStatus,
+        end_time: Option<u64>,
+        run_name: Option<&str>,
+    ) -> Result<RunInfo, ClientError<GetExperimentErrorCode>>;
+
+    /// Search for runs that satisfy expressions.
+    fn search_runs(
+        &self,
+        experiment_ids: &[&str],
+        filter: Option<&str>,
+        run_view_type: Option<ViewType>,
+        max_results: Option<u32>,
+        order_by: Option<&[&str]>,
+        page_token: Option<&str>,
+    ) -> Result<(Vec<Run>, Option<String>), ClientError<GetExperimentErrorCode>>;
+
+    /// Set a tag on a run.
+    fn set_run_tag(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// Delete a tag on a run.
+    fn delete_run_tag(
+        &self,
+        run_id: &str,
+        key: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// Log a metric for a run.
+    fn log_metric(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: f32,
+        timestamp: u64,
+        step: Option<u64>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// Get a page of the values of a metric for a given run.
+    fn get_metric_history(
+        &self,
+        run_id: &str,
+        metric_key: &str,
+        max_results: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<(Vec<Metric>, Option<String>), ClientError<GetExperimentErrorCode>>;
+
+    /// Get the values of a metric for several runs, sampled over an interval of steps.
+    fn get_metric_history_bulk_interval(
+        &self,
+        run_ids: &[&str],
+        metric_key: &str,
+        start_step: Option<u64>,
+        end_step: Option<u64>,
+        max_results: Option<u32>,
+    ) -> Result<Vec<MetricWithRunId>, ClientError<GetExperimentErrorCode>>;
+
+    /// Log a param used for a run. A param can be logged only once for a run.
+    fn log_param(
+        &self,
+        run_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// Log a batch of metrics, params, and tags for a run.
+    fn log_batch(
+        &self,
+        run_id: &str,
+        metrics: Option<&[&Metric]>,
+        params: Option<&[&Param]>,
+        tags: Option<&[&RunTag]>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// Log the datasets used by a run.
+    fn log_inputs(
+        &self,
+        run_id: &str,
+        datasets: Option<&[DatasetInput]>,
+    ) -> Result<(), ClientError<GetExperimentErrorCode>>;
+
+    /// List artifacts for a run, under `path` if specified.
+    fn list_artifacts(
+        &self,
+        run_id: &str,
+        path: Option<&str>,
+    ) -> Result<(String, Vec<FileInfo>), ClientError<GetExperimentErrorCode>>;
+
+    /// Time at which a deleted run was deleted, in milliseconds since the epoch. `None` for active runs, and when the
+    /// store doesn't record it, like the REST API.
+    fn get_run_deleted_time(
+        &self,
+        run_id: &str,
+    ) -> Result<Option<u64>, ClientError<GetExperimentErrorCode>> {
+        self.get_run(run_id).map(|_| None)
+    }
+
+    /// Permanently remove a deleted run, but not its artifacts. Returns `false` when the store can't, like the REST
+    /// API.
+    fn purge_run(&self, _run_id: &str) -> Result<bool, ClientError<GetExperimentErrorCode>> {
+        Ok(false)
+    }
+
+    /// Permanently remove a deleted experiment with its runs, but not their artifacts. Returns `false` when the store
+    /// can't, like the REST API.
+    fn purge_experiment(
+        &self,
+        _experiment_id: &str,
+    ) -> Result<bool, ClientError<GetExperimentErrorCode>> {
+        Ok(false)
+    }
+}
+
+/// Implement `TrackingStore` for a type by forwarding to its inherent methods of the same names.
+macro_rules! impl_tracking_store {
+    ($store:ty) => {
+        impl_tracking_store!($store, {});
+    };
+    ($store:ty, { $($extra:tt)* }) => {
+        impl $crate::store::TrackingStore for $store {
+            fn create_experiment(
+                &self,
+                name: &str,
+                artifact_location: Option<&str>,
+            ) -> Result<String, ClientError<CreateExperimentErrorCode>> {
+                <$store>::create_experiment(self, name, artifact_location)
+            }
+
+            fn list_experiments(
+                &self,
+                view_type: Option<ViewType>,
+            ) -> Result<Vec<Experiment>, ClientError<ListExperimentsErrorCode>> {
+                <$store>::list_experiments(self, view_type)
+            }
+
+            fn get_experiment(
+                &self,
+                experiment_id: &str,
+            ) -> Result<Experiment, ClientError<GetExperimentErrorCode>> {
+                <$store>::get_experiment(self, experiment_id)
+            }
+
+            fn get_experiment_by_name(
+                &self,
+                experiment_name: &str,
+            ) -> Result<Experiment, ClientError<GetExperimentErrorCode>> {
+                <$store>::get_experiment_by_name(self, experiment_name)
+            }
+
+            fn delete_experiment(
+                &self,
+                experiment_id: &str,
+            ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::delete_experiment(self, experiment_id)
+            }
+
+            fn restore_experiment(
+                &self,
+                experiment_id: &str,
+            ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::restore_experiment(self, experiment_id)
+            }
+
+            fn update_experiment(
+                &self,
+                experiment_id: &str,
+                new_name: &str,
+            ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::update_experiment(self, experiment_id, new_name)
+            }
+
+            fn set_experiment_tag(
+                &self,
+                experiment_id: &str,
+                key: &str,
+                value: &str,
+            ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::set_experiment_tag(self, experiment_id, key, value)
+            }
+
+            fn create_run(
+                &self,
+                experiment_id: &str,
+                start_time: Option<u64>,
+                tags: Option<Vec<RunTag>>,
+                run_name: Option<&str>,
+            ) -> Result<Run, ClientError<GetExperimentErrorCode>> {
+                <$store>::create_run(self, experiment_id, start_time, tags, run_name)
+            }
+
+            fn delete_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::delete_run(self, run_id)
+            }
+
+            fn restore_run(&self, run_id: &str) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::restore_run(self, run_id)
+            }
+
+            fn get_run(&self, run_id: &str) -> Result<Run, ClientError<GetExperimentErrorCode>> {
+                <$store>::get_run(self, run_id)
+            }
+
+            fn update_run(
+                &self,
+                run_id: &str,
+                status: RunStatus,
+                end_time: Option<u64>,
+                run_name: Option<&str>,
+            ) -> Result<RunInfo, ClientError<GetExperimentErrorCode>> {
+                <$store>::update_run(self, run_id, status, end_time, run_name)
+            }
+
+            fn search_runs(
+                &self,
+                experiment_ids: &[&str],
+                filter: Option<&str>,
+                run_view_type: Option<ViewType>,
+                max_results: Option<u32>,
+                order_by: Option<&[&str]>,
+                page_token: Option<&str>,
+            ) -> Result<(Vec<Run>, Option<String>), ClientError<GetExperimentErrorCode>> {
+                <$store>::search_runs(
+                    self,
+                    experiment_ids,
+                    filter,
+                    run_view_type,
+                    max_results,
+                    order_by,
+                    page_token,
+                )
+            }
+
+            fn set_run_tag(
+                &self,
+                run_id: &str,
+                key: &str,
+                value: &str,
+            ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::set_run_tag(self, run_id, key, value)
+            }
+
+            fn delete_run_tag(
+                &self,
+                run_id: &str,
+                key: &str,
+            ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::delete_run_tag(self, run_id, key)
+            }
+
+            fn log_metric(
+                &self,
+                run_id: &str,
+                key: &str,
+                value: f32,
+                timestamp: u64,
+                step: Option<u64>,
+            ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::log_metric(self, run_id, key, value, timestamp, step)
+            }
+
+            fn get_metric_history(
+                &self,
+                run_id: &str,
+                metric_key: &str,
+                max_results: Option<u32>,
+                page_token: Option<&str>,
+            ) -> Result<(Vec<Metric>, Option<String>), ClientError<GetExperimentErrorCode>> {
+                <$store>::get_metric_history(self, run_id, metric_key, max_results, page_token)
+            }
+
+            fn get_metric_history_bulk_interval(
+                &self,
+                run_ids: &[&str],
+                metric_key: &str,
+                start_step: Option<u64>,
+                end_step: Option<u64>,
+                max_results: Option<u32>,
+            ) -> Result<Vec<MetricWithRunId>, ClientError<GetExperimentErrorCode>> {
+                <$store>::get_metric_history_bulk_interval(
+                    self,
+                    run_ids,
+                    metric_key,
+                    start_step,
+                    end_step,
+                    max_results,
+                )
+            }
+
+            fn log_param(
+                &self,
+                run_id: &str,
+                key: &str,
+                value: &str,
+            ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::log_param(self, run_id, key, value)
+            }
+
+            fn log_batch(
+                &self,
+                run_id: &str,
+                metrics: Option<&[&Metric]>,
+                params: Option<&[&Param]>,
+                tags: Option<&[&RunTag]>,
+            ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::log_batch(self, run_id, metrics, params, tags)
+            }
+
+            fn log_inputs(
+                &self,
+                run_id: &str,
+                datasets: Option<&[DatasetInput]>,
+            ) -> Result<(), ClientError<GetExperimentErrorCode>> {
+                <$store>::log_inputs(self, run_id, datasets)
+            }
+
+            fn list_artifacts(
+                &self,
+                run_id: &str,
+                path: Option<&str>,
+            ) -> Result<(String, Vec<FileInfo>), ClientError<GetExperimentErrorCode>> {
+                <$store>::list_artifacts(self, run_id, path)
+            }
+
+            $($extra)*
+        }
+    };
+}
+
+/// Forward the purge operations to the inherent methods of stores that keep their own metadata.
+macro_rules! impl_purge {
+    ($store:ty) => {
+        impl_tracking_store!($store, {
+            fn get_run_deleted_time(
+                &self,
+                run_id: &str,
+            ) -> Result<Option<u64>, ClientError<GetExperimentErrorCode>> {
+                <$store>::get_run_deleted_time(self, run_id)
+            }
+
+            fn purge_run(&self, run_id: &str) -> Result<bool, ClientError<GetExperimentErrorCode>> {
+                <$store>::purge_run(self, run_id).map(|_| true)
+            }
+
+            fn purge_experiment(
+                &self,
+                experiment_id: &str,
+            ) -> Result<bool, ClientError<GetExperimentErrorCode>> {
+                <$store>::purge_experiment(self, experiment_id).map(|_| true)
+            }
+        });
+    };
+}
+
+impl_tracking_store!(crate::MLflowAPI);
+impl_purge!(crate::FileStore);
+impl_purge!(crate::MemoryStore);
+
+/// Current time in milliseconds since the epoch, the unit of the timestamps of MLflow.
+pub fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("time went strange there")
+        .as_millis() as u64
+}
+
+pub(crate) fn api_error<E: ErrorCode, M: Into<String>>(
+    error_code: E,
+    message: M,
+) -> ClientError<E> {
+    ClientError::ApiError {
+        error_code,
+        message: message.into(),
+    }
+}
+
+/// Message of the MLflow server for an unknown experiment ID, shared by the local stores.
+pub(crate) fn experiment_not_found(experiment_id: &str) -> String {
+    format!("Could not find experiment with ID {}", experiment_id)
+}
+
+/// Message of the MLflow server for an unknown experiment name.
+pub(crate) fn experiment_name_not_found(experiment_name: &str) -> String {
+    format!("Could not find experiment with name '{}'", experiment_name)
+}
+
+/// Message of the MLflow server when creating or renaming an experiment to a name already used.
+pub(crate) fn experiment_already_exists(experiment_name: &str) -> String {
+    format!("Experiment '{}' already exists.", experiment_name)
+}
+
+/// Message of the MLflow server for an unknown run ID.
+pub(crate) fn run_not_found(run_id: &str) -> String {
+    format!("Run '{}' not found", run_id)
+}
+
+/// Check that a key can be used as a file name, possibly in sub directories.
+pub(crate) fn validate_key<E: ErrorCode>(
+    kind: &str,
+    key: &str,
+    invalid_parameter_value: E,
+) -> Result<(), ClientError<E>> {
+    let valid = !key.is_empty()
+        && !key.starts_with('/')
+        && key
+            .split('/')
+            .all(|part| !part.is_empty() && part != "." && part != "..")
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || "_-. /".contains(c));
+    if valid {
+        Ok(())
+    } else {
+        Err(api_error(
+            invalid_parameter_value,
+            format!("Invalid {} name: '{}'", kind, key),
+        ))
+    }
+}
+
+/// Open the tracking store pointed to by `uri`: the REST API of a tracking server for `http://` and `https://`, a
+/// `FileStore` for `file:` URIs and plain paths, a new empty `MemoryStore` for `memory:`.
+pub fn open_store(uri: &str) -> Result<Box<dyn TrackingStore>, SetupError> {
+    if uri.starts_with("http://") || uri.starts_with("https://") {
+        Ok(Box::new(crate::MLflowAPI::new(uri)?))
+    } else if uri.starts_with("memory:") {
+        Ok(Box::new(crate::MemoryStore::new()))
+    } else if uri.starts_with("file:")
+        || !uri.contains(':')
+        || std::path::Path::new(uri).is_absolute()
+    {
+        Ok(Box::new(crate::FileStore::new(uri)?))
+    } else {
+        Err(SetupError::UnsupportedScheme(uri.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::open_store;
+    use crate::errors::SetupError;
+
+    #[test]
+    fn can_open_store_from_uri() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert_that!(format!(
+            "{:?}",
+            open_store("http://localhost:5000").unwrap()
+        ))
+        .contains("MLflowAPI");
+        let store = open_store(&format!("file://{}", dir.path().display()));
+        assert_that!(store).is_ok();
+        assert_that!(store.unwrap().list_experiments(None))
+            .is_ok()
+            .has_length(1);
+        assert_that!(format!("{:?}", open_store("memory:").unwrap())).contains("MemoryStore");
+        assert_that!(open_store("s3://bucket/mlruns").map(|_| ()))
+            .is_err()
+            .is_equal_to(SetupError::UnsupportedScheme(
+                "s3://bucket/mlruns".to_string(),
+            ));
+    }
+}
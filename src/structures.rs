@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::errors::SchemaError;
+
 fn u64_deserializer_in_string<'de, D>(deserializer: D) -> Result<u64, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -41,7 +43,7 @@ where
 }
 
 /// Experiment.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Experiment {
     /// Unique identifier for the experiment.
     pub experiment_id: String,
@@ -64,7 +66,7 @@ pub struct Experiment {
 }
 
 /// Tag for an experiment.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ExperimentTag {
     /// The tag key.
     pub key: String,
@@ -73,7 +75,7 @@ pub struct ExperimentTag {
 }
 
 /// Metadata of a single artifact file or directory.
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct FileInfo {
     /// Path relative to the root artifact directory run.
     pub path: String,
@@ -100,6 +102,16 @@ pub struct Metric {
     pub step: u64,
 }
 
+/// Metric with the ID of the run it is associated with.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct MetricWithRunId {
+    /// The metric.
+    #[serde(flatten)]
+    pub metric: Metric,
+    /// ID of the run the metric is associated with.
+    pub run_id: String,
+}
+
 /// Param associated with a run.
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Param {
@@ -110,16 +122,79 @@ pub struct Param {
 }
 
 /// A single run.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Run {
     /// Run metadata.
     pub info: RunInfo,
     /// Run data.
     pub data: Option<RunData>,
+    /// Run inputs.
+    #[serde(default)]
+    pub inputs: Option<RunInputs>,
+}
+
+impl Run {
+    /// The name of the run, from its metadata or from the `mlflow.runName` tag for older servers.
+    pub fn run_name(&self) -> Option<&str> {
+        self.info.run_name.as_deref().or_else(|| {
+            self.data.as_ref().and_then(|data| {
+                data.tags
+                    .iter()
+                    .find(|tag| tag.key == "mlflow.runName")
+                    .map(|tag| tag.value.as_str())
+            })
+        })
+    }
+}
+
+/// Run inputs (datasets).
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default)]
+pub struct RunInputs {
+    /// Datasets used by the run.
+    pub dataset_inputs: Vec<DatasetInput>,
+}
+
+/// A dataset used by a run, with tags describing how it was used.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct DatasetInput {
+    /// Tags of the input, like `mlflow.data.context`.
+    #[serde(default)]
+    pub tags: Vec<InputTag>,
+    /// The dataset.
+    pub dataset: Dataset,
+}
+
+/// Tag for a dataset input.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct InputTag {
+    /// The tag key.
+    pub key: String,
+    /// The tag value.
+    pub value: String,
+}
+
+/// A dataset, identified by its name and digest.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Dataset {
+    /// Name of the dataset.
+    pub name: String,
+    /// Digest of the dataset content.
+    pub digest: String,
+    /// Type of the source, like `local`, `http` or `s3`.
+    pub source_type: String,
+    /// Source of the dataset, as a JSON string specific to the source type.
+    pub source: String,
+    /// Schema of the dataset, as a JSON string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    /// Profile of the dataset (size, statistics...), as a JSON string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
 }
 
 /// Run data (metrics, params, and tags).
-#[derive(Debug, Deserialize, Clone, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 #[serde(default)]
 pub struct RunData {
     /// Run metrics.
@@ -131,10 +206,13 @@ pub struct RunData {
 }
 
 /// Metadata of a single run.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct RunInfo {
     /// Unique identifier for the run.
     pub run_id: String,
+    /// The name of the run. Not returned by servers older than MLflow 1.28.
+    #[serde(default)]
+    pub run_name: Option<String>,
     /// The experiment ID.
     pub experiment_id: String,
     /// Current status of the run.
@@ -155,7 +233,7 @@ pub struct RunInfo {
 }
 
 /// Life cycle stage of a experiment.
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum LifecycleStage {
     /// Run is active.
@@ -201,12 +279,520 @@ pub enum ViewType {
     All,
 }
 
+/// Type of a column or of a parameter in a model signature.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DataType {
+    /// Boolean value.
+    Boolean,
+    /// 32 bits signed integer.
+    Integer,
+    /// 64 bits signed integer.
+    Long,
+    /// 32 bits floating point number.
+    Float,
+    /// 64 bits floating point number.
+    Double,
+    /// Text.
+    String,
+    /// Sequence of bytes, base64 encoded in JSON.
+    Binary,
+    /// Date and time.
+    Datetime,
+}
+
+impl DataType {
+    /// Name of the type in MLflow schemas, like `long`.
+    fn name(self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|name| name.as_str().map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    fn accepts(self, value: &serde_json::Value) -> bool {
+        match self {
+            DataType::Boolean => value.is_boolean(),
+            DataType::Integer | DataType::Long => value.is_i64() || value.is_u64(),
+            DataType::Float | DataType::Double => value.is_number(),
+            DataType::String | DataType::Binary | DataType::Datetime => value.is_string(),
+        }
+    }
+}
+
+/// Specification of a column in a column-based schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColSpec {
+    /// Name of the column. Columns are matched by position when unnamed.
+    pub name: Option<String>,
+    /// Type of the column.
+    pub data_type: DataType,
+    /// Whether the column must be present.
+    pub required: bool,
+}
+
+/// Specification of a tensor in a tensor-based schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TensorSpec {
+    /// Name of the tensor. Only needed when the schema has multiple tensors.
+    pub name: Option<String>,
+    /// Numpy type of the tensor elements, like `float64` or `int32`.
+    pub dtype: String,
+    /// Shape of the tensor, `-1` marking a dimension of variable size.
+    pub shape: Vec<i64>,
+}
+
+impl TensorSpec {
+    fn accepts(&self, value: &serde_json::Value) -> bool {
+        match self.dtype.as_str() {
+            "bool" => value.is_boolean(),
+            dtype if dtype.starts_with("int") || dtype.starts_with("uint") => {
+                value.is_i64() || value.is_u64()
+            }
+            dtype if dtype.starts_with("float") => value.is_number(),
+            "str" | "object" | "bytes" => value.is_string(),
+            _ => true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RawTensorSpec {
+    dtype: String,
+    shape: Vec<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RawSpec {
+    #[serde(rename = "type")]
+    spec_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    required: Option<bool>,
+    #[serde(
+        rename = "tensor-spec",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    tensor_spec: Option<RawTensorSpec>,
+}
+
+/// Schema of the inputs or the outputs of a model, either column-based or tensor-based.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    /// Tabular data, like a dataframe.
+    Columns(Vec<ColSpec>),
+    /// Tensors, like numpy arrays.
+    Tensors(Vec<TensorSpec>),
+}
+
+impl Serialize for Schema {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let specs: Vec<RawSpec> = match self {
+            Schema::Columns(columns) => columns
+                .iter()
+                .map(|column| RawSpec {
+                    spec_type: serde_json::to_value(column.data_type)
+                        .ok()
+                        .and_then(|value| value.as_str().map(str::to_string))
+                        .unwrap_or_default(),
+                    name: column.name.clone(),
+                    required: Some(column.required),
+                    tensor_spec: None,
+                })
+                .collect(),
+            Schema::Tensors(tensors) => tensors
+                .iter()
+                .map(|tensor| RawSpec {
+                    spec_type: "tensor".to_string(),
+                    name: tensor.name.clone(),
+                    required: None,
+                    tensor_spec: Some(RawTensorSpec {
+                        dtype: tensor.dtype.clone(),
+                        shape: tensor.shape.clone(),
+                    }),
+                })
+                .collect(),
+        };
+        specs.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Schema {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let specs = Vec::<RawSpec>::deserialize(deserializer)?;
+        if specs.iter().any(|spec| spec.spec_type == "tensor") {
+            specs
+                .into_iter()
+                .map(|spec| match spec.tensor_spec {
+                    Some(tensor_spec) if spec.spec_type == "tensor" => Ok(TensorSpec {
+                        name: spec.name,
+                        dtype: tensor_spec.dtype,
+                        shape: tensor_spec.shape,
+                    }),
+                    _ => Err(serde::de::Error::custom(
+                        "a schema can not mix columns and tensors",
+                    )),
+                })
+                .collect::<Result<_, _>>()
+                .map(Schema::Tensors)
+        } else {
+            specs
+                .into_iter()
+                .map(|spec| {
+                    let data_type =
+                        serde_json::from_value(serde_json::Value::String(spec.spec_type.clone()))
+                            .map_err(|_| {
+                            serde::de::Error::unknown_variant(
+                                &spec.spec_type,
+                                &[
+                                    "boolean", "integer", "long", "float", "double", "string",
+                                    "binary", "datetime", "tensor",
+                                ],
+                            )
+                        })?;
+                    Ok(ColSpec {
+                        name: spec.name,
+                        data_type,
+                        required: spec.required.unwrap_or(true),
+                    })
+                })
+                .collect::<Result<_, _>>()
+                .map(Schema::Columns)
+        }
+    }
+}
+
+/// Specification of an inference parameter.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ParamSpec {
+    /// Name of the parameter.
+    pub name: String,
+    /// Type of the parameter.
+    #[serde(rename = "type")]
+    pub data_type: DataType,
+    /// Default value of the parameter.
+    pub default: serde_json::Value,
+    /// Shape of the parameter, `None` for a scalar, `Some(vec![-1])` for a list.
+    #[serde(default)]
+    pub shape: Option<Vec<i64>>,
+}
+
+/// Schema of the inference parameters of a model.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct ParamSchema(pub Vec<ParamSpec>);
+
+/// Signature of a model: schemas of its inputs, outputs and inference parameters. Each schema is encoded as a JSON
+/// string, as in `MLmodel` files and in the Python client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelSignature {
+    /// Schema of the model inputs.
+    pub inputs: Schema,
+    /// Schema of the model outputs.
+    pub outputs: Option<Schema>,
+    /// Schema of the inference parameters.
+    pub params: Option<ParamSchema>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RawModelSignature {
+    inputs: String,
+    #[serde(default)]
+    outputs: Option<String>,
+    #[serde(default)]
+    params: Option<String>,
+}
+
+impl Serialize for ModelSignature {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        fn to_json<S: serde::Serializer, T: Serialize>(value: &T) -> Result<String, S::Error> {
+            serde_json::to_string(value).map_err(serde::ser::Error::custom)
+        }
+        RawModelSignature {
+            inputs: to_json::<S, _>(&self.inputs)?,
+            outputs: self.outputs.as_ref().map(to_json::<S, _>).transpose()?,
+            params: self.params.as_ref().map(to_json::<S, _>).transpose()?,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ModelSignature {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        fn from_json<'de, D: serde::Deserializer<'de>, T: serde::de::DeserializeOwned>(
+            value: &str,
+        ) -> Result<T, D::Error> {
+            serde_json::from_str(value).map_err(serde::de::Error::custom)
+        }
+        let raw = RawModelSignature::deserialize(deserializer)?;
+        Ok(ModelSignature {
+            inputs: from_json::<D, _>(&raw.inputs)?,
+            outputs: raw.outputs.as_deref().map(from_json::<D, _>).transpose()?,
+            params: raw.params.as_deref().map(from_json::<D, _>).transpose()?,
+        })
+    }
+}
+
+impl ModelSignature {
+    /// Check that a JSON input, in one of the formats accepted by MLflow scoring servers (`dataframe_split`,
+    /// `dataframe_records`, `instances`, `inputs`, optionally with `params`), matches the signature.
+    pub fn validate_input(&self, input: &serde_json::Value) -> Result<(), SchemaError> {
+        let (data, params) = match input.as_object() {
+            Some(object) if object.contains_key("params") => {
+                let mut data = object.clone();
+                let params = data.remove("params");
+                (serde_json::Value::Object(data), params)
+            }
+            _ => (input.clone(), None),
+        };
+        self.inputs.validate(&data)?;
+        match (params, self.params.as_ref()) {
+            (Some(params), Some(schema)) => schema.validate(&params),
+            (Some(_), None) => Err(SchemaError::UnsupportedFormat(
+                "the signature does not accept params".to_string(),
+            )),
+            (None, _) => Ok(()),
+        }
+    }
+}
+
+impl Schema {
+    /// Check that a JSON input matches the schema.
+    pub fn validate(&self, input: &serde_json::Value) -> Result<(), SchemaError> {
+        match self {
+            Schema::Columns(columns) => {
+                let records = match input {
+                    serde_json::Value::Object(object) if object.contains_key("dataframe_split") => {
+                        split_to_records(&object["dataframe_split"], columns)?
+                    }
+                    serde_json::Value::Object(object)
+                        if object.contains_key("dataframe_records") =>
+                    {
+                        as_records(&object["dataframe_records"])?
+                    }
+                    serde_json::Value::Object(object) if object.contains_key("instances") => {
+                        as_records(&object["instances"])?
+                    }
+                    serde_json::Value::Object(object) if object.contains_key("inputs") => {
+                        as_records(&object["inputs"])?
+                    }
+                    other => as_records(other)?,
+                };
+                records
+                    .iter()
+                    .try_for_each(|record| validate_record(record, columns))
+            }
+            Schema::Tensors(tensors) => {
+                let data = match input {
+                    serde_json::Value::Object(object) if object.contains_key("instances") => {
+                        &object["instances"]
+                    }
+                    serde_json::Value::Object(object) if object.contains_key("inputs") => {
+                        &object["inputs"]
+                    }
+                    other => other,
+                };
+                match (tensors.as_slice(), data) {
+                    ([tensor], serde_json::Value::Array(_)) if tensor.name.is_none() => {
+                        validate_tensor(data, tensor, "input")
+                    }
+                    (_, serde_json::Value::Object(object)) => {
+                        tensors.iter().try_for_each(|tensor| {
+                            let name = tensor.name.clone().unwrap_or_default();
+                            match object.get(&name) {
+                                Some(value) => validate_tensor(value, tensor, &name),
+                                None => Err(SchemaError::MissingInput(name)),
+                            }
+                        })
+                    }
+                    (_, serde_json::Value::Array(instances)) => {
+                        instances.iter().try_for_each(|instance| {
+                            tensors.iter().try_for_each(|tensor| {
+                                let name = tensor.name.clone().unwrap_or_default();
+                                let value = instance
+                                    .get(&name)
+                                    .ok_or_else(|| SchemaError::MissingInput(name.clone()))?;
+                                let instance_spec = TensorSpec {
+                                    name: tensor.name.clone(),
+                                    dtype: tensor.dtype.clone(),
+                                    shape: tensor.shape.iter().skip(1).cloned().collect(),
+                                };
+                                validate_tensor(value, &instance_spec, &name)
+                            })
+                        })
+                    }
+                    _ => Err(SchemaError::UnsupportedFormat(
+                        "expected an array or an object of tensors".to_string(),
+                    )),
+                }
+            }
+        }
+    }
+}
+
+impl ParamSchema {
+    /// Check that JSON params match the schema.
+    pub fn validate(&self, params: &serde_json::Value) -> Result<(), SchemaError> {
+        let params = params.as_object().ok_or_else(|| {
+            SchemaError::UnsupportedFormat("params must be an object".to_string())
+        })?;
+        params.iter().try_for_each(|(name, value)| {
+            let spec = self
+                .0
+                .iter()
+                .find(|spec| &spec.name == name)
+                .ok_or_else(|| SchemaError::UnknownInput(name.clone()))?;
+            let valid = match (spec.shape.as_ref(), value) {
+                (Some(_), serde_json::Value::Array(values)) => {
+                    values.iter().all(|value| spec.data_type.accepts(value))
+                }
+                (Some(_), _) => false,
+                (None, value) => spec.data_type.accepts(value),
+            };
+            if valid {
+                Ok(())
+            } else {
+                Err(SchemaError::TypeMismatch {
+                    input: name.clone(),
+                    expected: spec.data_type.name(),
+                })
+            }
+        })
+    }
+}
+
+fn as_records(
+    value: &serde_json::Value,
+) -> Result<Vec<serde_json::Map<String, serde_json::Value>>, SchemaError> {
+    match value {
+        serde_json::Value::Array(records) => records
+            .iter()
+            .map(|record| {
+                record.as_object().cloned().ok_or_else(|| {
+                    SchemaError::UnsupportedFormat("records must be objects".to_string())
+                })
+            })
+            .collect(),
+        // an object of columns, each with a list of values
+        serde_json::Value::Object(columns) if columns.values().all(|value| value.is_array()) => {
+            let length = columns
+                .values()
+                .filter_map(|value| value.as_array().map(Vec::len))
+                .max()
+                .unwrap_or(0);
+            Ok((0..length)
+                .map(|index| {
+                    columns
+                        .iter()
+                        .filter_map(|(name, values)| {
+                            values.get(index).map(|value| (name.clone(), value.clone()))
+                        })
+                        .collect()
+                })
+                .collect())
+        }
+        serde_json::Value::Object(record) => Ok(vec![record.clone()]),
+        _ => Err(SchemaError::UnsupportedFormat(
+            "expected records or columns".to_string(),
+        )),
+    }
+}
+
+fn split_to_records(
+    split: &serde_json::Value,
+    columns: &[ColSpec],
+) -> Result<Vec<serde_json::Map<String, serde_json::Value>>, SchemaError> {
+    let names: Vec<String> = match split.get("columns").and_then(|names| names.as_array()) {
+        Some(names) => names
+            .iter()
+            .map(|name| name.as_str().map(str::to_string).unwrap_or_default())
+            .collect(),
+        None => columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| column.name.clone().unwrap_or_else(|| index.to_string()))
+            .collect(),
+    };
+    let rows = split
+        .get("data")
+        .and_then(|rows| rows.as_array())
+        .ok_or_else(|| SchemaError::UnsupportedFormat("dataframe_split needs data".to_string()))?;
+    rows.iter()
+        .map(|row| {
+            row.as_array()
+                .map(|values| names.iter().cloned().zip(values.iter().cloned()).collect())
+                .ok_or_else(|| SchemaError::UnsupportedFormat("rows must be arrays".to_string()))
+        })
+        .collect()
+}
+
+fn validate_record(
+    record: &serde_json::Map<String, serde_json::Value>,
+    columns: &[ColSpec],
+) -> Result<(), SchemaError> {
+    columns.iter().enumerate().try_for_each(|(index, column)| {
+        let name = column.name.clone().unwrap_or_else(|| index.to_string());
+        match record.get(&name) {
+            None | Some(serde_json::Value::Null) if column.required => {
+                Err(SchemaError::MissingInput(name))
+            }
+            None | Some(serde_json::Value::Null) => Ok(()),
+            Some(value) if column.data_type.accepts(value) => Ok(()),
+            Some(_) => Err(SchemaError::TypeMismatch {
+                input: name,
+                expected: column.data_type.name(),
+            }),
+        }
+    })
+}
+
+fn validate_tensor(
+    value: &serde_json::Value,
+    tensor: &TensorSpec,
+    name: &str,
+) -> Result<(), SchemaError> {
+    fn check(
+        value: &serde_json::Value,
+        shape: &[i64],
+        tensor: &TensorSpec,
+        name: &str,
+    ) -> Result<(), SchemaError> {
+        match (shape.split_first(), value) {
+            (None, serde_json::Value::Array(_)) => Err(SchemaError::ShapeMismatch {
+                input: name.to_string(),
+                expected: tensor.shape.clone(),
+            }),
+            (None, value) if tensor.accepts(value) => Ok(()),
+            (None, _) => Err(SchemaError::TypeMismatch {
+                input: name.to_string(),
+                expected: tensor.dtype.clone(),
+            }),
+            (Some((&dimension, rest)), serde_json::Value::Array(values))
+                if dimension < 0 || dimension as usize == values.len() =>
+            {
+                values
+                    .iter()
+                    .try_for_each(|value| check(value, rest, tensor, name))
+            }
+            (Some(_), _) => Err(SchemaError::ShapeMismatch {
+                input: name.to_string(),
+                expected: tensor.shape.clone(),
+            }),
+        }
+    }
+    check(value, &tensor.shape, tensor, name)
+}
+
 #[cfg(test)]
 mod tests {
     use serde::Deserialize;
     use spectral::prelude::*;
 
     use super::{u64_deserializer_in_string, u64_deserializer_in_string_opt};
+    use super::{ColSpec, DataType, ModelSignature, Run, Schema, TensorSpec};
+    use crate::errors::SchemaError;
 
     #[test]
     fn can_deserialize_string_to_u64() {
@@ -276,4 +862,185 @@ mod tests {
         ))
         .is_err();
     }
+
+    #[test]
+    fn can_deserialize_signature() {
+        let signature = serde_json::from_str::<ModelSignature>(
+            r#"{
+                "inputs": "[{\"type\": \"double\", \"name\": \"x\"}, {\"type\": \"string\", \"name\": \"y\", \"required\": false}]",
+                "outputs": "[{\"type\": \"tensor\", \"tensor-spec\": {\"dtype\": \"float64\", \"shape\": [-1]}}]",
+                "params": "[{\"name\": \"temperature\", \"type\": \"float\", \"default\": 0.5, \"shape\": null}]"
+            }"#,
+        );
+        assert_that!(signature).is_ok();
+        let signature = signature.unwrap();
+
+        assert_that!(signature.inputs).is_equal_to(Schema::Columns(vec![
+            ColSpec {
+                name: Some("x".to_string()),
+                data_type: DataType::Double,
+                required: true,
+            },
+            ColSpec {
+                name: Some("y".to_string()),
+                data_type: DataType::String,
+                required: false,
+            },
+        ]));
+        assert_that!(signature.outputs).is_equal_to(Some(Schema::Tensors(vec![TensorSpec {
+            name: None,
+            dtype: "float64".to_string(),
+            shape: vec![-1],
+        }])));
+        assert_that!(signature.params)
+            .is_some()
+            .map(|params| &params.0)
+            .has_length(1);
+
+        let written = serde_json::to_string(&signature).unwrap();
+        assert_that!(serde_json::from_str::<ModelSignature>(&written))
+            .is_ok()
+            .is_equal_to(signature);
+    }
+
+    #[test]
+    fn should_fail_deserialize_invalid_schema() {
+        assert_that!(serde_json::from_str::<Schema>(r#"[{"type": "decimal"}]"#)).is_err();
+        assert_that!(serde_json::from_str::<Schema>(
+            r#"[{"type": "double"}, {"type": "tensor", "tensor-spec": {"dtype": "int32", "shape": [1]}}]"#
+        ))
+        .is_err();
+    }
+
+    #[test]
+    fn can_validate_column_input() {
+        let signature = serde_json::from_str::<ModelSignature>(
+            r#"{
+                "inputs": "[{\"type\": \"long\", \"name\": \"x\"}, {\"type\": \"string\", \"name\": \"y\", \"required\": false}]",
+                "params": "[{\"name\": \"top_k\", \"type\": \"long\", \"default\": 1, \"shape\": null}]"
+            }"#,
+        )
+        .unwrap();
+
+        assert_that!(signature.validate_input(&serde_json::json!({
+            "dataframe_split": {"columns": ["x", "y"], "data": [[1, "a"], [2, null]]}
+        })))
+        .is_ok();
+        assert_that!(signature.validate_input(&serde_json::json!({
+            "dataframe_records": [{"x": 1}, {"x": 2, "y": "b"}],
+            "params": {"top_k": 3}
+        })))
+        .is_ok();
+        assert_that!(signature.validate_input(&serde_json::json!({"x": [1, 2], "y": ["a", "b"]})))
+            .is_ok();
+
+        assert_that!(signature.validate_input(&serde_json::json!([{"y": "a"}])))
+            .is_err()
+            .is_equal_to(SchemaError::MissingInput("x".to_string()));
+        assert_that!(signature.validate_input(&serde_json::json!([{"x": 1.5}])))
+            .is_err()
+            .is_equal_to(SchemaError::TypeMismatch {
+                input: "x".to_string(),
+                expected: "long".to_string(),
+            });
+        assert_that!(signature.validate_input(&serde_json::json!({
+            "x": [1, 2],
+            "y": ["a", 3],
+            "params": {"top_k": 3}
+        })))
+        .is_err()
+        .is_equal_to(SchemaError::TypeMismatch {
+            input: "y".to_string(),
+            expected: "string".to_string(),
+        });
+        assert_that!(signature.validate_input(&serde_json::json!({
+            "dataframe_records": [{"x": 1}],
+            "params": {"temperature": 0.5}
+        })))
+        .is_err()
+        .is_equal_to(SchemaError::UnknownInput("temperature".to_string()));
+    }
+
+    #[test]
+    fn can_validate_tensor_input() {
+        let signature = serde_json::from_str::<ModelSignature>(
+            r#"{"inputs": "[{\"type\": \"tensor\", \"tensor-spec\": {\"dtype\": \"float32\", \"shape\": [-1, 2]}}]"}"#,
+        )
+        .unwrap();
+
+        assert_that!(
+            signature.validate_input(&serde_json::json!({"instances": [[1.0, 2.0], [3.0, 4.0]]}))
+        )
+        .is_ok();
+        assert_that!(signature.validate_input(&serde_json::json!({"inputs": [[1.0, 2.0, 3.0]]})))
+            .is_err()
+            .is_equal_to(SchemaError::ShapeMismatch {
+                input: "input".to_string(),
+                expected: vec![-1, 2],
+            });
+        assert_that!(signature.validate_input(&serde_json::json!([["a", "b"]])))
+            .is_err()
+            .is_equal_to(SchemaError::TypeMismatch {
+                input: "input".to_string(),
+                expected: "float32".to_string(),
+            });
+    }
+
+    #[test]
+    fn can_deserialize_run_with_inputs() {
+        let run = serde_json::from_str::<Run>(
+            r#"{
+                "info": {
+                    "run_id": "abc",
+                    "experiment_id": "0",
+                    "status": "FINISHED",
+                    "start_time": "1700000000000",
+                    "artifact_uri": "/mlruns/0/abc/artifacts",
+                    "lifecycle_stage": "active"
+                },
+                "inputs": {
+                    "dataset_inputs": [{
+                        "tags": [{"key": "mlflow.data.context", "value": "training"}],
+                        "dataset": {
+                            "name": "iris",
+                            "digest": "1a2b3c4d",
+                            "source_type": "local",
+                            "source": "{\"uri\": \"/data/iris.csv\"}"
+                        }
+                    }]
+                }
+            }"#,
+        );
+        assert_that!(run)
+            .is_ok()
+            .map(|run| &run.inputs)
+            .is_some()
+            .map(|inputs| &inputs.dataset_inputs)
+            .has_length(1);
+
+        let run = serde_json::from_str::<Run>(
+            r#"{"info": {"run_id": "abc", "experiment_id": "0", "status": "RUNNING", "start_time": 1,
+                "artifact_uri": "", "lifecycle_stage": "active"}}"#,
+        );
+        assert_that!(run).is_ok().map(|run| &run.inputs).is_none();
+    }
+
+    #[test]
+    fn can_get_run_name_from_info_or_tag() {
+        let run = serde_json::from_str::<Run>(
+            r#"{"info": {"run_id": "abc", "run_name": "info-name", "experiment_id": "0", "status": "RUNNING",
+                "start_time": 1, "artifact_uri": "", "lifecycle_stage": "active"},
+                "data": {"tags": [{"key": "mlflow.runName", "value": "tag-name"}]}}"#,
+        )
+        .unwrap();
+        assert_that!(run.run_name()).is_equal_to(Some("info-name"));
+
+        let run = serde_json::from_str::<Run>(
+            r#"{"info": {"run_id": "abc", "experiment_id": "0", "status": "RUNNING",
+                "start_time": 1, "artifact_uri": "", "lifecycle_stage": "active"},
+                "data": {"tags": [{"key": "mlflow.runName", "value": "tag-name"}]}}"#,
+        )
+        .unwrap();
+        assert_that!(run.run_name()).is_equal_to(Some("tag-name"));
+    }
 }
@@ -0,0 +1,695 @@
+//! Hyperparameter sweeps over nested runs.
+//!
+//! A [`Sweep`] samples trials from a [`ParameterSpace`], on a grid or at random, and runs each of them in a child run
+//! of a parent run, with the params of the trial logged. The trial closure trains and reports metrics through the
+//! [`Trial`]. At the end, the parent run is tagged with the best trial according to the objective metric, whose
+//! value is also logged on the parent.
+//!
+//! ```no_run
+//! use mlflow_api::sweep::{Direction, ParameterSpace, Strategy, Sweep};
+//! use mlflow_api::MLflowClient;
+//!
+//! let mut mlflow = MLflowClient::new().unwrap();
+//! let space = ParameterSpace::new()
+//!     .log_uniform("learning_rate", 1e-4, 1e-1)
+//!     .choice("optimizer", vec!["adam", "sgd"]);
+//! let sweep = Sweep::new(space, Strategy::Random { trials: 20, seed: 7 }, "val_loss", Direction::Minimize);
+//! let report = sweep
+//!     .run(&mut mlflow, "lr-search", |trial| {
+//!         let learning_rate = trial.param("learning_rate").and_then(|value| value.as_f64()).unwrap();
+//!         trial.log_metric("val_loss", (learning_rate - 0.01).abs() as f32, 0)
+//!     })
+//!     .unwrap();
+//! println!("best run: {:?}", report.best_run_id);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+
+use crate::errors::SweepError;
+use crate::{MLflowClient, Param, RunStatus, RunTag};
+
+/// Run tag of the child runs with the ID of their parent run, shown as nested runs by MLflow.
+pub const PARENT_RUN_TAG: &str = "mlflow.parentRunId";
+/// Parent run tag with the ID of the best trial run.
+pub const BEST_RUN_TAG: &str = "mlflow.sweep.bestRunId";
+/// Parent run tag with the objective metric of the best trial.
+pub const BEST_VALUE_TAG: &str = "mlflow.sweep.bestValue";
+/// Parent run tag with the objective of the sweep, like `val_loss:minimize`.
+pub const OBJECTIVE_TAG: &str = "mlflow.sweep.objective";
+/// Child run tag with the error of a failed trial.
+pub const ERROR_TAG: &str = "mlflow.sweep.error";
+
+/// Value of a parameter in a trial.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ParamValue {
+    /// An integer.
+    Int(i64),
+    /// A floating point number.
+    Float(f64),
+    /// A string.
+    Text(String),
+}
+
+impl ParamValue {
+    /// The value as a float, for integers and floats.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ParamValue::Int(value) => Some(*value as f64),
+            ParamValue::Float(value) => Some(*value),
+            ParamValue::Text(_) => None,
+        }
+    }
+
+    /// The value as an integer, for integers.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            ParamValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The value as a string, for strings.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ParamValue::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ParamValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamValue::Int(value) => value.fmt(f),
+            ParamValue::Float(value) => value.fmt(f),
+            ParamValue::Text(value) => value.fmt(f),
+        }
+    }
+}
+
+impl From<i64> for ParamValue {
+    fn from(value: i64) -> ParamValue {
+        ParamValue::Int(value)
+    }
+}
+
+impl From<f64> for ParamValue {
+    fn from(value: f64) -> ParamValue {
+        ParamValue::Float(value)
+    }
+}
+
+impl From<&str> for ParamValue {
+    fn from(value: &str) -> ParamValue {
+        ParamValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for ParamValue {
+    fn from(value: String) -> ParamValue {
+        ParamValue::Text(value)
+    }
+}
+
+/// Values a parameter can take.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Domain {
+    /// One of a list of values.
+    Choice(Vec<ParamValue>),
+    /// A float between `low` and `high`.
+    Uniform {
+        /// Lowest value.
+        low: f64,
+        /// Highest value.
+        high: f64,
+    },
+    /// A float between `low` and `high`, uniform on a log scale, for learning rates and regularization strengths.
+    LogUniform {
+        /// Lowest value, above zero.
+        low: f64,
+        /// Highest value.
+        high: f64,
+    },
+    /// An integer between `low` and `high`, both included.
+    IntUniform {
+        /// Lowest value.
+        low: i64,
+        /// Highest value.
+        high: i64,
+    },
+}
+
+impl Domain {
+    fn validate(&self, name: &str) -> Result<(), SweepError> {
+        let valid = match self {
+            Domain::Choice(values) => !values.is_empty(),
+            Domain::Uniform { low, high } => low <= high,
+            Domain::LogUniform { low, high } => 0. < *low && low <= high,
+            Domain::IntUniform { low, high } => low <= high,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(SweepError::InvalidSpace(format!(
+                "{} has no values: {:?}",
+                name, self
+            )))
+        }
+    }
+
+    /// `points` values evenly spread over the domain, all the values of a choice.
+    fn grid(&self, points: usize) -> Vec<ParamValue> {
+        let spread = |low: f64, high: f64| -> Vec<f64> {
+            if points <= 1 {
+                return vec![low];
+            }
+            (0..points)
+                .map(|index| low + (high - low) * index as f64 / (points - 1) as f64)
+                .collect()
+        };
+        match self {
+            Domain::Choice(values) => values.clone(),
+            Domain::Uniform { low, high } => spread(*low, *high)
+                .into_iter()
+                .map(ParamValue::Float)
+                .collect(),
+            Domain::LogUniform { low, high } => spread(low.ln(), high.ln())
+                .into_iter()
+                .map(|value| ParamValue::Float(value.exp()))
+                .collect(),
+            Domain::IntUniform { low, high } => {
+                let mut values: Vec<i64> = spread(*low as f64, *high as f64)
+                    .into_iter()
+                    .map(|value| value.round() as i64)
+                    .collect();
+                values.dedup();
+                values.into_iter().map(ParamValue::Int).collect()
+            }
+        }
+    }
+
+    fn sample<R: Rng>(&self, rng: &mut R) -> ParamValue {
+        match self {
+            Domain::Choice(values) => values[rng.gen_range(0, values.len())].clone(),
+            Domain::Uniform { low, high } if low < high => {
+                ParamValue::Float(rng.gen_range(*low, *high))
+            }
+            Domain::LogUniform { low, high } if low < high => {
+                ParamValue::Float(rng.gen_range(low.ln(), high.ln()).exp().clamp(*low, *high))
+            }
+            Domain::Uniform { low, .. } | Domain::LogUniform { low, .. } => ParamValue::Float(*low),
+            Domain::IntUniform { low, high } => ParamValue::Int(rng.gen_range(*low, *high + 1)),
+        }
+    }
+}
+
+/// Parameters of a sweep, with the values each of them can take.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParameterSpace {
+    parameters: Vec<(String, Domain)>,
+}
+
+impl ParameterSpace {
+    /// New empty `ParameterSpace`.
+    pub fn new() -> ParameterSpace {
+        ParameterSpace::default()
+    }
+
+    /// Add a parameter taking values in `domain`.
+    pub fn parameter(mut self, name: &str, domain: Domain) -> ParameterSpace {
+        self.parameters.push((name.to_string(), domain));
+        self
+    }
+
+    /// Add a parameter taking one of `values`.
+    pub fn choice<V: Into<ParamValue>>(self, name: &str, values: Vec<V>) -> ParameterSpace {
+        self.parameter(
+            name,
+            Domain::Choice(values.into_iter().map(Into::into).collect()),
+        )
+    }
+
+    /// Add a float parameter between `low` and `high`.
+    pub fn uniform(self, name: &str, low: f64, high: f64) -> ParameterSpace {
+        self.parameter(name, Domain::Uniform { low, high })
+    }
+
+    /// Add a float parameter between `low` and `high`, uniform on a log scale.
+    pub fn log_uniform(self, name: &str, low: f64, high: f64) -> ParameterSpace {
+        self.parameter(name, Domain::LogUniform { low, high })
+    }
+
+    /// Add an integer parameter between `low` and `high`, both included.
+    pub fn int_uniform(self, name: &str, low: i64, high: i64) -> ParameterSpace {
+        self.parameter(name, Domain::IntUniform { low, high })
+    }
+}
+
+/// How trials are sampled from the parameter space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Strategy {
+    /// Every combination of the values of the parameters. Ranges are split in `points` evenly spread values, on a log
+    /// scale for log-uniform parameters.
+    Grid {
+        /// Number of values of each range.
+        points: usize,
+    },
+    /// Independent random values for each parameter.
+    Random {
+        /// Number of trials.
+        trials: usize,
+        /// Seed of the random generator, for reproducible sweeps.
+        seed: u64,
+    },
+}
+
+/// Whether the objective metric is better low or high.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// Lower values are better, for losses and errors.
+    Minimize,
+    /// Higher values are better, for accuracies and scores.
+    Maximize,
+}
+
+impl Direction {
+    /// Whether `value` is better than `other`. NaN is never better.
+    pub fn is_better(self, value: f64, other: f64) -> bool {
+        match self {
+            Direction::Minimize => value < other,
+            Direction::Maximize => value > other,
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Direction::Minimize => write!(f, "minimize"),
+            Direction::Maximize => write!(f, "maximize"),
+        }
+    }
+}
+
+/// A trial running in its child run, given to the trial closure.
+#[derive(Debug)]
+pub struct Trial<'a> {
+    client: &'a mut MLflowClient,
+    index: usize,
+    run_id: String,
+    params: &'a BTreeMap<String, ParamValue>,
+    metric: &'a str,
+    objective: Option<f64>,
+}
+
+impl Trial<'_> {
+    /// Position of the trial in the sweep, from 0.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// ID of the child run of the trial.
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// Params of the trial.
+    pub fn params(&self) -> &BTreeMap<String, ParamValue> {
+        self.params
+    }
+
+    /// Value of a param of the trial.
+    pub fn param(&self, name: &str) -> Option<&ParamValue> {
+        self.params.get(name)
+    }
+
+    /// Log a metric to the run of the trial. The last value of the objective metric is the result of the trial.
+    // same error as `MLflowClient::log_metric_at_step`, that the metric is logged with
+    #[allow(clippy::result_unit_err)]
+    pub fn log_metric(&mut self, key: &str, value: f32, step: u64) -> Result<(), ()> {
+        self.client.log_metric_at_step(key, value, step)?;
+        if key == self.metric {
+            self.objective = Some(f64::from(value));
+        }
+        Ok(())
+    }
+
+    /// Client with the run of the trial active, to log params, tags and artifacts.
+    pub fn client(&mut self) -> &mut MLflowClient {
+        self.client
+    }
+}
+
+/// Outcome of a trial.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrialResult {
+    /// ID of the child run of the trial.
+    pub run_id: String,
+    /// Params of the trial.
+    pub params: BTreeMap<String, ParamValue>,
+    /// Last value of the objective metric, if reported.
+    pub objective: Option<f64>,
+    /// Final status of the run: finished, or failed when the closure returned an error.
+    pub status: RunStatus,
+}
+
+/// Outcome of a sweep.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SweepReport {
+    /// ID of the parent run.
+    pub parent_run_id: String,
+    /// Trials, in the order they ran.
+    pub trials: Vec<TrialResult>,
+    /// ID of the run of the best finished trial reporting the objective, if any.
+    pub best_run_id: Option<String>,
+    /// Objective of the best trial.
+    pub best_value: Option<f64>,
+}
+
+impl SweepReport {
+    /// The best trial, if any.
+    pub fn best(&self) -> Option<&TrialResult> {
+        let best_run_id = self.best_run_id.as_ref()?;
+        self.trials
+            .iter()
+            .find(|trial| &trial.run_id == best_run_id)
+    }
+}
+
+/// Hyperparameter sweep, optimizing a metric over a parameter space.
+#[derive(Debug, Clone)]
+pub struct Sweep {
+    space: ParameterSpace,
+    strategy: Strategy,
+    metric: String,
+    direction: Direction,
+}
+
+impl Sweep {
+    /// New `Sweep` sampling `space` with `strategy`, looking for the best `metric` in `direction`.
+    pub fn new(
+        space: ParameterSpace,
+        strategy: Strategy,
+        metric: &str,
+        direction: Direction,
+    ) -> Sweep {
+        Sweep {
+            space,
+            strategy,
+            metric: metric.to_string(),
+            direction,
+        }
+    }
+
+    /// Params of each trial, without running anything.
+    pub fn trials(&self) -> Result<Vec<BTreeMap<String, ParamValue>>, SweepError> {
+        if self.space.parameters.is_empty() {
+            return Err(SweepError::InvalidSpace("no parameters".to_string()));
+        }
+        for (name, domain) in &self.space.parameters {
+            domain.validate(name)?;
+        }
+        match self.strategy {
+            Strategy::Grid { points } => {
+                let mut trials = vec![BTreeMap::new()];
+                for (name, domain) in &self.space.parameters {
+                    let values = domain.grid(points);
+                    trials = trials
+                        .into_iter()
+                        .flat_map(|trial| {
+                            values.iter().map(move |value| {
+                                let mut trial = trial.clone();
+                                trial.insert(name.clone(), value.clone());
+                                trial
+                            })
+                        })
+                        .collect();
+                }
+                Ok(trials)
+            }
+            Strategy::Random { trials, seed } => {
+                let mut rng = StdRng::seed_from_u64(seed);
+                Ok((0..trials)
+                    .map(|_| {
+                        self.space
+                            .parameters
+                            .iter()
+                            .map(|(name, domain)| (name.clone(), domain.sample(&mut rng)))
+                            .collect()
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Run the sweep: start a parent run named `run_name` with `client`, then run `trial` for each trial in a child
+    /// run with the params of the trial logged. A trial returning an error fails its run, tagged with the error,
+    /// without stopping the sweep. The parent run ends after tagging it with the best trial, and fails if no trial
+    /// finished.
+    pub fn run<F, E>(
+        &self,
+        client: &mut MLflowClient,
+        run_name: &str,
+        mut trial: F,
+    ) -> Result<SweepReport, SweepError>
+    where
+        F: FnMut(&mut Trial<'_>) -> Result<(), E>,
+        E: std::fmt::Debug,
+    {
+        let trials = self.trials()?;
+        let tracking = |action: &str| SweepError::Tracking(format!("could not {}", action));
+        client
+            .start_run(run_name)
+            .map_err(|_| tracking("start the parent run"))?;
+        let parent = client
+            .active_run()
+            .map_err(|_| tracking("get the parent run"))?;
+        let parent_run_id = parent.info.run_id;
+        client
+            .set_tag(
+                OBJECTIVE_TAG,
+                &format!("{}:{}", self.metric, self.direction),
+            )
+            .map_err(|_| tracking("tag the parent run"))?;
+
+        let mut report = SweepReport {
+            parent_run_id: parent_run_id.clone(),
+            trials: Vec::with_capacity(trials.len()),
+            best_run_id: None,
+            best_value: None,
+        };
+        for (index, params) in trials.iter().enumerate() {
+            let trial_name = format!("{}-{}", run_name, index);
+            let run = client.store.create_run(
+                &parent.info.experiment_id,
+                Some(crate::store::now()),
+                Some(vec![
+                    RunTag {
+                        key: PARENT_RUN_TAG.to_string(),
+                        value: parent_run_id.clone(),
+                    },
+                    RunTag {
+                        key: "mlflow.user".to_string(),
+                        value: whoami::username(),
+                    },
+                    RunTag {
+                        key: "mlflow.runName".to_string(),
+                        value: trial_name.clone(),
+                    },
+                ]),
+                Some(&trial_name),
+            )?;
+            let run_id = run.info.run_id;
+            client
+                .resume_run(Some(&run_id))
+                .map_err(|_| tracking("start a trial run"))?;
+            let logged: Vec<Param> = params
+                .iter()
+                .map(|(key, value)| Param {
+                    key: key.clone(),
+                    value: value.to_string(),
+                })
+                .collect();
+            client
+                .log_params(&logged.iter().collect::<Vec<_>>())
+                .map_err(|_| tracking("log the params of a trial"))?;
+
+            let mut context = Trial {
+                client: &mut *client,
+                index,
+                run_id: run_id.clone(),
+                params,
+                metric: &self.metric,
+                objective: None,
+            };
+            let outcome = trial(&mut context);
+            let objective = context.objective;
+            let status = match outcome {
+                Ok(()) => RunStatus::Finished,
+                Err(error) => {
+                    client
+                        .set_tag(ERROR_TAG, &format!("{:?}", error))
+                        .map_err(|_| tracking("tag a failed trial"))?;
+                    RunStatus::Failed
+                }
+            };
+            client
+                .update_run_status(status)
+                .map_err(|_| tracking("end a trial run"))?;
+
+            if let (RunStatus::Finished, Some(value)) = (status, objective) {
+                let better = report.best_value.map_or(!value.is_nan(), |best| {
+                    self.direction.is_better(value, best)
+                });
+                if better {
+                    report.best_run_id = Some(run_id.clone());
+                    report.best_value = Some(value);
+                }
+            }
+            report.trials.push(TrialResult {
+                run_id,
+                params: params.clone(),
+                objective,
+                status,
+            });
+        }
+
+        client
+            .resume_run(Some(&parent_run_id))
+            .map_err(|_| tracking("resume the parent run"))?;
+        if let (Some(best_run_id), Some(best_value)) = (&report.best_run_id, report.best_value) {
+            client
+                .set_tag(BEST_RUN_TAG, best_run_id)
+                .and_then(|_| client.set_tag(BEST_VALUE_TAG, &best_value.to_string()))
+                .and_then(|_| client.log_metric(&self.metric, best_value as f32))
+                .map_err(|_| tracking("tag the parent run with the best trial"))?;
+        }
+        let status = if report
+            .trials
+            .iter()
+            .any(|trial| trial.status == RunStatus::Finished)
+        {
+            RunStatus::Finished
+        } else {
+            RunStatus::Failed
+        };
+        client
+            .update_run_status(status)
+            .map_err(|_| tracking("end the parent run"))?;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::{
+        Direction, ParamValue, ParameterSpace, Strategy, Sweep, BEST_RUN_TAG, PARENT_RUN_TAG,
+    };
+    use crate::errors::SweepError;
+    use crate::{MLflowClient, MemoryStore, RunStatus};
+
+    #[test]
+    fn can_sample_grids_and_random_trials() {
+        let space = ParameterSpace::new()
+            .choice("optimizer", vec!["adam", "sgd"])
+            .log_uniform("learning_rate", 1e-3, 1e-1)
+            .int_uniform("layers", 1, 2);
+
+        let grid = Sweep::new(
+            space.clone(),
+            Strategy::Grid { points: 3 },
+            "loss",
+            Direction::Minimize,
+        );
+        let trials = grid.trials().unwrap();
+        assert_that!(trials.len()).is_equal_to(2 * 3 * 2);
+        let rates: Vec<f64> = trials[..6]
+            .iter()
+            .step_by(2)
+            .map(|trial| trial["learning_rate"].as_f64().unwrap())
+            .collect();
+        assert_that!((rates[1] - 1e-2).abs()).is_less_than(1e-9);
+        assert_that!(trials[0]["optimizer"]).is_equal_to(ParamValue::from("adam"));
+
+        let random = Sweep::new(
+            space,
+            Strategy::Random {
+                trials: 50,
+                seed: 3,
+            },
+            "loss",
+            Direction::Minimize,
+        );
+        let trials = random.trials().unwrap();
+        assert_that!(trials.len()).is_equal_to(50);
+        assert_that!(trials
+            .iter()
+            .all(|trial| (1e-3..=1e-1).contains(&trial["learning_rate"].as_f64().unwrap())))
+        .is_true();
+        assert_that!(trials
+            .iter()
+            .all(|trial| (1..=2).contains(&trial["layers"].as_i64().unwrap())))
+        .is_true();
+        assert_that!(random.trials().unwrap()).is_equal_to(trials);
+
+        let invalid = ParameterSpace::new().log_uniform("learning_rate", 0., 1.);
+        assert_that!(Sweep::new(
+            invalid,
+            Strategy::Grid { points: 3 },
+            "loss",
+            Direction::Minimize
+        )
+        .trials())
+        .is_err_containing(SweepError::InvalidSpace(
+            "learning_rate has no values: LogUniform { low: 0.0, high: 1.0 }".to_string(),
+        ));
+    }
+
+    #[test]
+    fn can_run_a_sweep_in_nested_runs() {
+        let store = MemoryStore::new();
+        let mut mlflow = MLflowClient::new_with_store(Box::new(store.clone()));
+        let space = ParameterSpace::new().choice("alpha", vec![0.1, 0.5, 0.9]);
+        let sweep = Sweep::new(
+            space,
+            Strategy::Grid { points: 3 },
+            "accuracy",
+            Direction::Maximize,
+        );
+
+        let report = sweep
+            .run(&mut mlflow, "search", |trial| {
+                let alpha = trial.param("alpha").unwrap().as_f64().unwrap();
+                if alpha > 0.8 {
+                    return Err("diverged");
+                }
+                trial.log_metric("accuracy", 1. - alpha as f32, 0).unwrap();
+                Ok(())
+            })
+            .unwrap();
+
+        assert_that!(report.trials.len()).is_equal_to(3);
+        assert_that!(report.trials[2].status).is_equal_to(RunStatus::Failed);
+        let best = report.best().unwrap();
+        assert_that!(best.params["alpha"]).is_equal_to(ParamValue::Float(0.1));
+        assert_that!(report.best_value).is_equal_to(Some(f64::from(0.9f32)));
+
+        let parent = store.tags_of(&report.parent_run_id);
+        assert_that!(parent.get(BEST_RUN_TAG)).is_equal_to(Some(&best.run_id));
+        assert_that!(store.params_of(&best.run_id).get("alpha"))
+            .is_equal_to(Some(&"0.1".to_string()));
+        assert_that!(store.tags_of(&best.run_id).get(PARENT_RUN_TAG))
+            .is_equal_to(Some(&report.parent_run_id));
+        assert_that!(store.get_run(&report.parent_run_id).unwrap().info.status)
+            .is_equal_to(RunStatus::Finished);
+    }
+}
@@ -0,0 +1,511 @@
+//! Tables of runs, one row per run and one column per attribute, metric, param and tag, like the DataFrame returned
+//! by `mlflow.search_runs()` in Python.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+use crate::errors::TableError;
+use crate::search::status_name;
+use crate::Run;
+
+/// Columns of the run attributes, before the metrics, params and tags.
+const INFO_COLUMNS: [&str; 6] = [
+    "run_id",
+    "experiment_id",
+    "status",
+    "artifact_uri",
+    "start_time",
+    "end_time",
+];
+
+/// A value in a table of runs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    /// No value, for a metric, param or tag the run does not have.
+    Null,
+    /// A metric value or a time in milliseconds since the epoch.
+    Number(f64),
+    /// Any other value.
+    Text(String),
+}
+
+/// Kind of values of a column, picked from its name.
+#[cfg(any(feature = "parquet", feature = "polars"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ColumnKind {
+    /// `start_time` and `end_time`, in milliseconds since the epoch.
+    Time,
+    /// `metrics.*` columns.
+    Metric,
+    /// Everything else.
+    Text,
+}
+
+#[cfg(any(feature = "parquet", feature = "polars"))]
+impl ColumnKind {
+    pub(crate) fn of(column: &str) -> ColumnKind {
+        match column {
+            "start_time" | "end_time" => ColumnKind::Time,
+            _ if column.starts_with("metrics.") => ColumnKind::Metric,
+            _ => ColumnKind::Text,
+        }
+    }
+}
+
+/// A table of runs: the run attributes, then `metrics.<key>` with the latest value of each metric, `params.<key>`
+/// and `tags.<key>` columns, sorted by key. Runs without a metric, param or tag have a `Cell::Null` in its column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunsTable {
+    columns: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl RunsTable {
+    /// Table of `runs`, in the same order.
+    pub fn from_runs(runs: &[Run]) -> RunsTable {
+        let mut metrics = BTreeSet::new();
+        let mut params = BTreeSet::new();
+        let mut tags = BTreeSet::new();
+        for data in runs.iter().filter_map(|run| run.data.as_ref()) {
+            metrics.extend(data.metrics.iter().map(|metric| metric.key.as_str()));
+            params.extend(data.params.iter().map(|param| param.key.as_str()));
+            tags.extend(data.tags.iter().map(|tag| tag.key.as_str()));
+        }
+        let columns: Vec<String> = INFO_COLUMNS
+            .iter()
+            .map(|column| column.to_string())
+            .chain(metrics.iter().map(|key| format!("metrics.{}", key)))
+            .chain(params.iter().map(|key| format!("params.{}", key)))
+            .chain(tags.iter().map(|key| format!("tags.{}", key)))
+            .collect();
+        let rows = runs
+            .iter()
+            .map(|run| {
+                let values = run_values(run);
+                columns
+                    .iter()
+                    .map(|column| values.get(column).cloned().unwrap_or(Cell::Null))
+                    .collect()
+            })
+            .collect();
+        RunsTable { columns, rows }
+    }
+
+    /// Names of the columns.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Rows, one per run, with one cell per column.
+    pub fn rows(&self) -> &[Vec<Cell>] {
+        &self.rows
+    }
+
+    /// Table with only the given columns, in that order. A name ending with `*`, like `metrics.*`, selects all the
+    /// columns starting with the rest of the name. Columns that are not in the table are kept, filled with
+    /// `Cell::Null`, so that tables of different runs have the same columns.
+    pub fn select(&self, columns: &[&str]) -> RunsTable {
+        let mut selected = vec![];
+        for column in columns {
+            match column.strip_suffix('*') {
+                Some(prefix) => selected.extend(
+                    self.columns
+                        .iter()
+                        .filter(|name| name.starts_with(prefix))
+                        .cloned(),
+                ),
+                None => selected.push(column.to_string()),
+            }
+        }
+        let indices: Vec<Option<usize>> = selected
+            .iter()
+            .map(|column| self.columns.iter().position(|name| name == column))
+            .collect();
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                indices
+                    .iter()
+                    .map(|index| index.map_or(Cell::Null, |index| row[index].clone()))
+                    .collect()
+            })
+            .collect();
+        RunsTable {
+            columns: selected,
+            rows,
+        }
+    }
+
+    /// Write the table as CSV with a header line. Missing values are empty.
+    pub fn write_csv<W: Write>(&self, writer: W) -> Result<(), TableError> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(&self.columns)?;
+        for row in &self.rows {
+            writer.write_record(row.iter().map(|cell| match cell {
+                Cell::Null => String::new(),
+                Cell::Number(number) => number.to_string(),
+                Cell::Text(text) => text.clone(),
+            }))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Write the table as JSON Lines, one object per run with its columns in order. Missing values are `null`.
+    pub fn write_jsonl<W: Write>(&self, mut writer: W) -> Result<(), TableError> {
+        for row in &self.rows {
+            writer.write_all(b"{")?;
+            for (index, (column, cell)) in self.columns.iter().zip(row).enumerate() {
+                if index > 0 {
+                    writer.write_all(b",")?;
+                }
+                serde_json::to_writer(&mut writer, column)?;
+                writer.write_all(b":")?;
+                match cell {
+                    Cell::Null => writer.write_all(b"null")?,
+                    // times and integer metrics are written without a fractional part
+                    Cell::Number(number) if number.fract() == 0. && number.abs() < 1e15 => {
+                        serde_json::to_writer(&mut writer, &(*number as i64))?
+                    }
+                    Cell::Number(number) => serde_json::to_writer(&mut writer, number)?,
+                    Cell::Text(text) => serde_json::to_writer(&mut writer, text)?,
+                }
+            }
+            writer.write_all(b"}\n")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Arrow record batch of the table: times are UTC timestamps in milliseconds, metrics are 64 bits floats and
+    /// the other columns are strings.
+    #[cfg(feature = "parquet")]
+    pub fn to_record_batch(&self) -> Result<arrow_array::RecordBatch, TableError> {
+        use std::sync::Arc;
+
+        use arrow_array::{ArrayRef, Float64Array, StringArray, TimestampMillisecondArray};
+        use arrow_schema::{DataType, Field, Schema, TimeUnit};
+
+        let mut fields = vec![];
+        let mut arrays: Vec<ArrayRef> = vec![];
+        for (index, column) in self.columns.iter().enumerate() {
+            let cells = self.rows.iter().map(|row| &row[index]);
+            let (data_type, array): (DataType, ArrayRef) = match ColumnKind::of(column) {
+                ColumnKind::Time => (
+                    DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+                    Arc::new(
+                        cells
+                            .map(|cell| match cell {
+                                Cell::Number(number) => Some(*number as i64),
+                                _ => None,
+                            })
+                            .collect::<TimestampMillisecondArray>()
+                            .with_timezone("UTC"),
+                    ),
+                ),
+                ColumnKind::Metric => (
+                    DataType::Float64,
+                    Arc::new(
+                        cells
+                            .map(|cell| match cell {
+                                Cell::Number(number) => Some(*number),
+                                _ => None,
+                            })
+                            .collect::<Float64Array>(),
+                    ),
+                ),
+                ColumnKind::Text => (
+                    DataType::Utf8,
+                    Arc::new(
+                        cells
+                            .map(|cell| match cell {
+                                Cell::Null => None,
+                                Cell::Number(number) => Some(number.to_string()),
+                                Cell::Text(text) => Some(text.clone()),
+                            })
+                            .collect::<StringArray>(),
+                    ),
+                ),
+            };
+            fields.push(Field::new(column, data_type, true));
+            arrays.push(array);
+        }
+        Ok(arrow_array::RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            arrays,
+        )?)
+    }
+
+    /// Write the table as a Parquet file, with the types of [`RunsTable::to_record_batch`].
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: Write + Send>(&self, writer: W) -> Result<(), TableError> {
+        let batch = self.to_record_batch()?;
+        let mut writer = parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+
+    /// Polars DataFrame of the table: times are datetimes in milliseconds, metrics are 64 bits floats and the other
+    /// columns are strings.
+    #[cfg(feature = "polars")]
+    pub fn to_dataframe(&self) -> Result<polars::frame::DataFrame, TableError> {
+        use polars::prelude::{DataType, IntoColumn, NamedFrom, Series, TimeUnit};
+
+        let mut columns = vec![];
+        for (index, column) in self.columns.iter().enumerate() {
+            let cells = self.rows.iter().map(|row| &row[index]);
+            let name = column.as_str().into();
+            let series = match ColumnKind::of(column) {
+                ColumnKind::Time => Series::new(
+                    name,
+                    cells
+                        .map(|cell| match cell {
+                            Cell::Number(number) => Some(*number as i64),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?,
+                ColumnKind::Metric => Series::new(
+                    name,
+                    cells
+                        .map(|cell| match cell {
+                            Cell::Number(number) => Some(*number),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                ColumnKind::Text => Series::new(
+                    name,
+                    cells
+                        .map(|cell| match cell {
+                            Cell::Null => None,
+                            Cell::Number(number) => Some(number.to_string()),
+                            Cell::Text(text) => Some(text.clone()),
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+            };
+            columns.push(series.into_column());
+        }
+        Ok(polars::frame::DataFrame::new(columns)?)
+    }
+}
+
+/// Polars DataFrame of metric histories by run ID, like the ones of `MLflowClient::get_metric_histories`, with one
+/// row per value and the columns `run_id`, `key`, `step`, `timestamp` and `value`. Rows are sorted by run ID, then
+/// in the order of the histories.
+#[cfg(feature = "polars")]
+pub fn metric_histories_to_dataframe(
+    histories: &std::collections::HashMap<String, Vec<crate::Metric>>,
+) -> Result<polars::frame::DataFrame, TableError> {
+    use polars::prelude::{DataType, IntoColumn, NamedFrom, Series, TimeUnit};
+
+    let histories: BTreeMap<&String, &Vec<crate::Metric>> = histories.iter().collect();
+    let mut run_ids = vec![];
+    let mut keys = vec![];
+    let mut steps = vec![];
+    let mut timestamps = vec![];
+    let mut values = vec![];
+    for (run_id, history) in histories {
+        for metric in history {
+            run_ids.push(run_id.as_str());
+            keys.push(metric.key.as_str());
+            steps.push(metric.step as i64);
+            timestamps.push(metric.timestamp as i64);
+            values.push(f64::from(metric.value));
+        }
+    }
+    Ok(polars::frame::DataFrame::new(vec![
+        Series::new("run_id".into(), run_ids).into_column(),
+        Series::new("key".into(), keys).into_column(),
+        Series::new("step".into(), steps).into_column(),
+        Series::new("timestamp".into(), timestamps)
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?
+            .into_column(),
+        Series::new("value".into(), values).into_column(),
+    ])?)
+}
+
+/// Values of a run by column name.
+fn run_values(run: &Run) -> BTreeMap<String, Cell> {
+    let info = &run.info;
+    let mut values = BTreeMap::new();
+    values.insert("run_id".to_string(), Cell::Text(info.run_id.clone()));
+    values.insert(
+        "experiment_id".to_string(),
+        Cell::Text(info.experiment_id.clone()),
+    );
+    values.insert(
+        "status".to_string(),
+        Cell::Text(status_name(info.status).to_string()),
+    );
+    values.insert(
+        "artifact_uri".to_string(),
+        Cell::Text(info.artifact_uri.clone()),
+    );
+    values.insert(
+        "start_time".to_string(),
+        Cell::Number(info.start_time as f64),
+    );
+    if let Some(end_time) = info.end_time {
+        values.insert("end_time".to_string(), Cell::Number(end_time as f64));
+    }
+    if let Some(data) = run.data.as_ref() {
+        for metric in &data.metrics {
+            values.insert(
+                format!("metrics.{}", metric.key),
+                Cell::Number(f64::from(metric.value)),
+            );
+        }
+        for param in &data.params {
+            values.insert(
+                format!("params.{}", param.key),
+                Cell::Text(param.value.clone()),
+            );
+        }
+        for tag in &data.tags {
+            values.insert(format!("tags.{}", tag.key), Cell::Text(tag.value.clone()));
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::{Cell, RunsTable};
+    use crate::{MemoryStore, RunStatus};
+
+    fn runs() -> Vec<crate::Run> {
+        let store = MemoryStore::new();
+        let first = store.create_run("0", Some(1000), None, None).unwrap();
+        store
+            .log_metric(&first.info.run_id, "loss", 0.5, 1, Some(0))
+            .unwrap();
+        store
+            .log_metric(&first.info.run_id, "loss", 0.25, 2, Some(1))
+            .unwrap();
+        store
+            .update_run(&first.info.run_id, RunStatus::Finished, Some(2000), None)
+            .unwrap();
+        let second = store.create_run("0", Some(3000), None, None).unwrap();
+        store
+            .log_param(&second.info.run_id, "model", "tree, deep")
+            .unwrap();
+        store.runs()
+    }
+
+    #[test]
+    fn can_build_table_from_runs() {
+        let table = RunsTable::from_runs(&runs());
+
+        assert_that!(table.columns().to_vec()).is_equal_to(
+            [
+                "run_id",
+                "experiment_id",
+                "status",
+                "artifact_uri",
+                "start_time",
+                "end_time",
+                "metrics.loss",
+                "params.model",
+            ]
+            .iter()
+            .map(|column| column.to_string())
+            .collect::<Vec<_>>(),
+        );
+        assert_that!(table.rows()[0][6]).is_equal_to(Cell::Number(0.25));
+        assert_that!(table.rows()[0][7]).is_equal_to(Cell::Null);
+        assert_that!(table.rows()[1][5]).is_equal_to(Cell::Null);
+    }
+
+    #[test]
+    fn can_select_columns() {
+        let table = RunsTable::from_runs(&runs()).select(&["metrics.*", "params.alpha", "status"]);
+
+        assert_that!(table.columns().to_vec()).is_equal_to(vec![
+            "metrics.loss".to_string(),
+            "params.alpha".to_string(),
+            "status".to_string(),
+        ]);
+        assert_that!(table.rows()[1].clone()).is_equal_to(vec![
+            Cell::Null,
+            Cell::Null,
+            Cell::Text("RUNNING".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn can_write_csv_and_jsonl() {
+        let table =
+            RunsTable::from_runs(&runs()).select(&["start_time", "metrics.loss", "params.model"]);
+
+        let mut csv = vec![];
+        table.write_csv(&mut csv).unwrap();
+        assert_that!(String::from_utf8(csv).unwrap()).is_equal_to(
+            "start_time,metrics.loss,params.model\n1000,0.25,\n3000,,\"tree, deep\"\n".to_string(),
+        );
+        let mut jsonl = vec![];
+        table.write_jsonl(&mut jsonl).unwrap();
+        assert_that!(String::from_utf8(jsonl).unwrap()).is_equal_to(
+            "{\"start_time\":1000,\"metrics.loss\":0.25,\"params.model\":null}\n\
+             {\"start_time\":3000,\"metrics.loss\":null,\"params.model\":\"tree, deep\"}\n"
+                .to_string(),
+        );
+    }
+
+    #[cfg(feature = "polars")]
+    #[test]
+    fn can_convert_to_dataframes() {
+        use polars::prelude::DataType;
+
+        let runs = runs();
+        let frame = RunsTable::from_runs(&runs).to_dataframe().unwrap();
+        assert_that!(frame.shape()).is_equal_to((2, 8));
+        assert_that!(frame.column("metrics.loss").unwrap().dtype()).is_equal_to(&DataType::Float64);
+        assert_that!(frame.column("end_time").unwrap().null_count()).is_equal_to(1);
+
+        let store = MemoryStore::new();
+        let run = store.create_run("0", None, None, None).unwrap();
+        for step in 0..3 {
+            store
+                .log_metric(&run.info.run_id, "loss", 0.5, 10 + step, Some(step))
+                .unwrap();
+        }
+        let histories = vec![(
+            run.info.run_id.clone(),
+            store.metric_history_of(&run.info.run_id, "loss"),
+        )]
+        .into_iter()
+        .collect();
+        let frame = super::metric_histories_to_dataframe(&histories).unwrap();
+        assert_that!(frame.shape()).is_equal_to((3, 5));
+        assert_that!(frame.get_column_names_str()).is_equal_to(vec![
+            "run_id",
+            "key",
+            "step",
+            "timestamp",
+            "value",
+        ]);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn can_write_parquet() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let table = RunsTable::from_runs(&runs());
+        let batch = table.to_record_batch().unwrap();
+        assert_that!(batch.num_rows()).is_equal_to(2);
+        assert_that!(batch.num_columns()).is_equal_to(8);
+
+        let file = tempfile::tempfile().unwrap();
+        table.write_parquet(file.try_clone().unwrap()).unwrap();
+        let reader = SerializedFileReader::new(file).unwrap();
+        assert_that!(reader.metadata().file_metadata().num_rows()).is_equal_to(2);
+    }
+}
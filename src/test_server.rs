@@ -0,0 +1,674 @@
+//! In-process fake of the MLflow tracking server, for tests that should exercise the HTTP client without a Python
+//! MLflow instance.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::errors::{ClientError, ErrorCode, GetExperimentErrorCode};
+use crate::store::{validate_key, TrackingStore};
+use crate::{DatasetInput, MemoryStore, Metric, Param, RunStatus, RunTag, ViewType};
+
+/// Artifact root of the experiments, relative to the working directory, as for `mlflow server` without
+/// `--default-artifact-root`.
+const DEFAULT_ARTIFACT_ROOT: &str = "./mlruns";
+
+/// A fake MLflow tracking server, serving the `/api/2.0/mlflow/*` endpoints used by `MLflowAPI` from a
+/// `MemoryStore`, on an ephemeral port of the local host. The server is stopped when dropped.
+///
+/// It behaves like the MLflow 1.3 server of the integration tests: params can be logged again with another value,
+/// and the artifacts of a run are listed from its artifact directory, relative to the working directory of the
+/// server when the artifact URI is a relative path.
+pub struct TestServer {
+    url: String,
+    store: MemoryStore,
+    working_dir: PathBuf,
+    /// Whether the working directory was created for the server, to be removed with it.
+    temporary: bool,
+    server: Arc<tiny_http::Server>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Failed request, with its HTTP status and MLflow error body.
+type Failure = (u16, Value);
+
+impl TestServer {
+    /// Start a server over a new empty `MemoryStore`, in a new temporary working directory removed when the server
+    /// is dropped.
+    pub fn start() -> std::io::Result<TestServer> {
+        TestServer::start_with_store(mlflow_store())
+    }
+
+    /// Start a server over a new empty `MemoryStore`, in the working directory `working_dir`: the artifacts of the
+    /// experiments are stored under its `mlruns` sub directory, like for `mlflow server` started from there.
+    pub fn start_in(working_dir: &Path) -> std::io::Result<TestServer> {
+        fs::create_dir_all(working_dir)?;
+        TestServer::serve(mlflow_store(), working_dir.to_path_buf(), false)
+    }
+
+    /// Start a server over `store`, in a new temporary working directory removed when the server is dropped. A
+    /// clone of the store can be kept to check what was logged through the server.
+    pub fn start_with_store(store: MemoryStore) -> std::io::Result<TestServer> {
+        let working_dir = std::env::temp_dir().join(format!(
+            "mlflow-test-server-{}",
+            uuid::Uuid::new_v4().to_simple()
+        ));
+        fs::create_dir_all(&working_dir)?;
+        TestServer::serve(store, working_dir, true)
+    }
+
+    fn serve(
+        store: MemoryStore,
+        working_dir: PathBuf,
+        temporary: bool,
+    ) -> std::io::Result<TestServer> {
+        let server = tiny_http::Server::http("127.0.0.1:0").map_err(std::io::Error::other)?;
+        let address = server
+            .server_addr()
+            .to_ip()
+            .ok_or_else(|| std::io::Error::other("server is not listening on IP"))?;
+        let server = Arc::new(server);
+        let thread = {
+            let server = server.clone();
+            let store = store.clone();
+            let working_dir = working_dir.clone();
+            std::thread::spawn(move || {
+                while let Ok(request) = server.recv() {
+                    handle_request(&store, &working_dir, request);
+                }
+            })
+        };
+        Ok(TestServer {
+            url: format!("http://{}", address),
+            store,
+            working_dir,
+            temporary,
+            server,
+            thread: Some(thread),
+        })
+    }
+
+    /// Tracking URI of the server, like `http://127.0.0.1:34567`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The store backing the server.
+    pub fn store(&self) -> &MemoryStore {
+        &self.store
+    }
+
+    /// Working directory of the server, that relative artifact URIs are resolved from.
+    pub fn working_dir(&self) -> &Path {
+        &self.working_dir
+    }
+}
+
+/// New empty `MemoryStore` behaving like the MLflow 1.3 server.
+fn mlflow_store() -> MemoryStore {
+    let store = MemoryStore::with_artifact_root(DEFAULT_ARTIFACT_ROOT);
+    store.allow_param_changes();
+    store
+}
+
+impl std::fmt::Debug for TestServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestServer")
+            .field("url", &self.url)
+            .field("store", &self.store)
+            .field("working_dir", &self.working_dir)
+            .finish()
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        if self.temporary {
+            let _ = fs::remove_dir_all(&self.working_dir);
+        }
+    }
+}
+
+fn handle_request(store: &MemoryStore, working_dir: &Path, mut request: tiny_http::Request) {
+    let mut body = String::new();
+    let parsed = request
+        .as_reader()
+        .read_to_string(&mut body)
+        .map_err(|error| failure(500, "INTERNAL_ERROR", &error.to_string()))
+        .and_then(|_| {
+            reqwest::Url::parse(&format!("http://localhost{}", request.url()))
+                .map_err(|error| failure(400, "MALFORMED_REQUEST", &error.to_string()))
+        })
+        .and_then(|url| {
+            let body = if body.trim().is_empty() {
+                Value::Null
+            } else {
+                serde_json::from_str(&body)
+                    .map_err(|error| failure(400, "MALFORMED_REQUEST", &error.to_string()))?
+            };
+            Ok(Arguments {
+                body,
+                query: url.query_pairs().into_owned().collect(),
+                path: url.path().to_string(),
+            })
+        });
+    let method = request.method().as_str().to_uppercase();
+    let (status, response) = match parsed {
+        Ok(arguments) => match route(store, working_dir, &method, &arguments) {
+            Ok(response) => (200, response),
+            Err(failure) => failure,
+        },
+        Err(failure) => failure,
+    };
+    let content_type =
+        tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+            .expect("header is valid");
+    let _ = request.respond(
+        tiny_http::Response::from_string(response.to_string())
+            .with_status_code(status)
+            .with_header(content_type),
+    );
+}
+
+/// Arguments of a request, from its JSON body for `POST` and its query string for `GET`.
+struct Arguments {
+    path: String,
+    body: Value,
+    query: Vec<(String, String)>,
+}
+
+impl Arguments {
+    fn value(&self, name: &str) -> Option<Value> {
+        match self.body.get(name) {
+            Some(Value::Null) | None => self
+                .query
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| Value::String(value.clone())),
+            Some(value) => Some(value.clone()),
+        }
+    }
+
+    fn string(&self, name: &str) -> Option<String> {
+        self.value(name).map(|value| match value {
+            Value::String(value) => value,
+            other => other.to_string(),
+        })
+    }
+
+    fn required(&self, name: &str) -> Result<String, Failure> {
+        self.string(name).ok_or_else(|| {
+            failure(
+                400,
+                "INVALID_PARAMETER_VALUE",
+                &format!("Missing value for required parameter '{}'.", name),
+            )
+        })
+    }
+
+    fn number(&self, name: &str) -> Result<Option<u64>, Failure> {
+        self.string(name)
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    failure(
+                        400,
+                        "INVALID_PARAMETER_VALUE",
+                        &format!("Invalid value '{}' for parameter '{}'", value, name),
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    fn parse<T: serde::de::DeserializeOwned>(&self, name: &str) -> Result<Option<T>, Failure> {
+        self.value(name)
+            .map(|value| {
+                serde_json::from_value(value).map_err(|error| {
+                    failure(
+                        400,
+                        "INVALID_PARAMETER_VALUE",
+                        &format!("Invalid value for parameter '{}': {}", name, error),
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    fn strings(&self, name: &str) -> Result<Vec<String>, Failure> {
+        match self.body.get(name) {
+            Some(_) => Ok(self.parse(name)?.unwrap_or_default()),
+            None => Ok(self
+                .query
+                .iter()
+                .filter(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+                .collect()),
+        }
+    }
+}
+
+fn failure(status: u16, error_code: &str, message: &str) -> Failure {
+    (
+        status,
+        json!({ "error_code": error_code, "message": message }),
+    )
+}
+
+/// Convert the result of a store operation to a response, with the same HTTP status codes as the tracking server.
+fn reply<T, E: ErrorCode + Serialize, F: FnOnce(T) -> Value>(
+    result: Result<T, ClientError<E>>,
+    to_json: F,
+) -> Result<Value, Failure> {
+    match result {
+        Ok(value) => Ok(to_json(value)),
+        Err(ClientError::ApiError {
+            error_code,
+            message,
+        }) => {
+            let error_code = serde_json::to_value(error_code).unwrap_or(Value::Null);
+            let status = match error_code.as_str() {
+                Some("RESOURCE_DOES_NOT_EXIST") => 404,
+                Some("INTERNAL_ERROR") => 500,
+                _ => 400,
+            };
+            Err((
+                status,
+                json!({ "error_code": error_code, "message": message }),
+            ))
+        }
+        Err(error) => Err(failure(500, "INTERNAL_ERROR", &error.to_string())),
+    }
+}
+
+fn empty<T>(_: T) -> Value {
+    json!({})
+}
+
+fn route(
+    store: &MemoryStore,
+    working_dir: &Path,
+    method: &str,
+    arguments: &Arguments,
+) -> Result<Value, Failure> {
+    let store: &dyn TrackingStore = store;
+    let endpoint = arguments
+        .path
+        .trim_start_matches("/api/2.0/mlflow/")
+        .trim_end_matches('/');
+    match (method, endpoint) {
+        ("POST", "experiments/create") => reply(
+            store.create_experiment(
+                &arguments.required("name")?,
+                arguments.string("artifact_location").as_deref(),
+            ),
+            |experiment_id| json!({ "experiment_id": experiment_id }),
+        ),
+        ("GET", "experiments/list") => reply(
+            store.list_experiments(arguments.parse::<ViewType>("view_type")?),
+            |experiments| json!({ "experiments": experiments }),
+        ),
+        ("GET", "experiments/get") => reply(
+            store.get_experiment(&arguments.required("experiment_id")?),
+            |experiment| json!({ "experiment": experiment }),
+        ),
+        ("GET", "experiments/get-by-name") => reply(
+            store.get_experiment_by_name(&arguments.required("experiment_name")?),
+            |experiment| json!({ "experiment": experiment }),
+        ),
+        ("POST", "experiments/delete") => reply(
+            store.delete_experiment(&arguments.required("experiment_id")?),
+            empty,
+        ),
+        ("POST", "experiments/restore") => reply(
+            store.restore_experiment(&arguments.required("experiment_id")?),
+            empty,
+        ),
+        ("POST", "experiments/update") => reply(
+            store.update_experiment(
+                &arguments.required("experiment_id")?,
+                &arguments.required("new_name")?,
+            ),
+            empty,
+        ),
+        ("POST", "experiments/set-experiment-tag") => reply(
+            store.set_experiment_tag(
+                &arguments.required("experiment_id")?,
+                &arguments.required("key")?,
+                &arguments.required("value")?,
+            ),
+            empty,
+        ),
+        ("POST", "runs/create") => reply(
+            store
+                .create_run(
+                    &arguments.required("experiment_id")?,
+                    arguments.number("start_time")?,
+                    arguments.parse::<Vec<RunTag>>("tags")?,
+                    arguments.string("run_name").as_deref(),
+                )
+                .and_then(|run| {
+                    // the server creates the artifact directory of the run with the run
+                    if let Some(dir) = local_artifact_dir(working_dir, &run.info.artifact_uri) {
+                        fs::create_dir_all(dir)?;
+                    }
+                    Ok(run)
+                }),
+            |run| json!({ "run": run }),
+        ),
+        ("POST", "runs/delete") => reply(store.delete_run(&arguments.required("run_id")?), empty),
+        ("POST", "runs/restore") => reply(store.restore_run(&arguments.required("run_id")?), empty),
+        ("GET", "runs/get") => reply(
+            store.get_run(&arguments.required("run_id")?),
+            |run| json!({ "run": run }),
+        ),
+        ("POST", "runs/update") => reply(
+            store.update_run(
+                &arguments.required("run_id")?,
+                arguments
+                    .parse::<RunStatus>("status")?
+                    .unwrap_or(RunStatus::Running),
+                arguments.number("end_time")?,
+                arguments.string("run_name").as_deref(),
+            ),
+            |run_info| json!({ "run_info": run_info }),
+        ),
+        ("POST", "runs/search") => {
+            let experiment_ids = arguments.strings("experiment_ids")?;
+            let order_by = arguments.strings("order_by")?;
+            reply(
+                store.search_runs(
+                    &experiment_ids
+                        .iter()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>(),
+                    arguments.string("filter").as_deref(),
+                    arguments.parse::<ViewType>("run_view_type")?,
+                    arguments.number("max_results")?.map(|value| value as u32),
+                    Some(&order_by.iter().map(String::as_str).collect::<Vec<_>>()),
+                    arguments.string("page_token").as_deref(),
+                ),
+                |(runs, next_page_token)| json!({ "runs": runs, "next_page_token": next_page_token }),
+            )
+        }
+        ("POST", "runs/set-tag") => reply(
+            store.set_run_tag(
+                &arguments.required("run_id")?,
+                &arguments.required("key")?,
+                &arguments.required("value")?,
+            ),
+            empty,
+        ),
+        ("POST", "runs/delete-tag") => reply(
+            store.delete_run_tag(&arguments.required("run_id")?, &arguments.required("key")?),
+            empty,
+        ),
+        ("POST", "runs/log-metric") => reply(
+            store.log_metric(
+                &arguments.required("run_id")?,
+                &arguments.required("key")?,
+                arguments.parse::<f32>("value")?.unwrap_or_default(),
+                arguments.number("timestamp")?.unwrap_or_default(),
+                arguments.number("step")?,
+            ),
+            empty,
+        ),
+        ("GET", "metrics/get-history") => reply(
+            store.get_metric_history(
+                &arguments.required("run_id")?,
+                &arguments.required("metric_key")?,
+                arguments.number("max_results")?.map(|value| value as u32),
+                arguments.string("page_token").as_deref(),
+            ),
+            |(metrics, next_page_token)| json!({ "metrics": metrics, "next_page_token": next_page_token }),
+        ),
+        ("GET", "metrics/get-history-bulk-interval") => {
+            let run_ids = arguments.strings("run_ids")?;
+            reply(
+                store.get_metric_history_bulk_interval(
+                    &run_ids.iter().map(String::as_str).collect::<Vec<_>>(),
+                    &arguments.required("metric_key")?,
+                    arguments.number("start_step")?,
+                    arguments.number("end_step")?,
+                    arguments.number("max_results")?.map(|value| value as u32),
+                ),
+                |metrics| json!({ "metrics": metrics }),
+            )
+        }
+        ("POST", "runs/log-parameter") => reply(
+            store.log_param(
+                &arguments.required("run_id")?,
+                &arguments.required("key")?,
+                &arguments.required("value")?,
+            ),
+            empty,
+        ),
+        ("POST", "runs/log-batch") => {
+            let metrics = arguments.parse::<Vec<Metric>>("metrics")?;
+            let params = arguments.parse::<Vec<Param>>("params")?;
+            let tags = arguments.parse::<Vec<RunTag>>("tags")?;
+            reply(
+                store.log_batch(
+                    &arguments.required("run_id")?,
+                    metrics
+                        .as_ref()
+                        .map(|metrics| metrics.iter().collect::<Vec<_>>())
+                        .as_deref(),
+                    params
+                        .as_ref()
+                        .map(|params| params.iter().collect::<Vec<_>>())
+                        .as_deref(),
+                    tags.as_ref()
+                        .map(|tags| tags.iter().collect::<Vec<_>>())
+                        .as_deref(),
+                ),
+                empty,
+            )
+        }
+        ("POST", "runs/log-inputs") => reply(
+            store.log_inputs(
+                &arguments.required("run_id")?,
+                arguments.parse::<Vec<DatasetInput>>("datasets")?.as_deref(),
+            ),
+            empty,
+        ),
+        ("GET", "artifacts/list") => {
+            let path = arguments.string("path").filter(|path| !path.is_empty());
+            reply(
+                list_artifacts(
+                    store,
+                    working_dir,
+                    &arguments.required("run_id")?,
+                    path.as_deref(),
+                ),
+                |(root_uri, files)| json!({ "root_uri": root_uri, "files": files }),
+            )
+        }
+        _ => Err(failure(
+            404,
+            "ENDPOINT_NOT_FOUND",
+            &format!("No endpoint {} {}", method, arguments.path),
+        )),
+    }
+}
+
+/// Local directory of the artifacts at `artifact_uri`, relative paths being resolved from `working_dir`. `None` for
+/// the artifacts not stored on the local file system.
+fn local_artifact_dir(working_dir: &Path, artifact_uri: &str) -> Option<PathBuf> {
+    let path = artifact_uri.strip_prefix("file://").unwrap_or(artifact_uri);
+    if path.contains(':') {
+        None
+    } else {
+        Some(working_dir.join(path))
+    }
+}
+
+/// List the artifacts of a run from its artifact directory, as the server does for artifacts on its file system.
+fn list_artifacts(
+    store: &dyn TrackingStore,
+    working_dir: &Path,
+    run_id: &str,
+    path: Option<&str>,
+) -> Result<(String, Vec<crate::FileInfo>), ClientError<GetExperimentErrorCode>> {
+    let (root_uri, files) = store.list_artifacts(run_id, path)?;
+    let dir = match local_artifact_dir(working_dir, &root_uri) {
+        Some(dir) => dir,
+        None => return Ok((root_uri, files)),
+    };
+    let dir = match path {
+        Some(path) => {
+            validate_key(
+                "artifact",
+                path,
+                GetExperimentErrorCode::InvalidParameterValue,
+            )?;
+            dir.join(path)
+        }
+        None => dir,
+    };
+    let files = crate::artifacts::list_local_artifacts(&dir, path)?;
+    Ok((root_uri, files))
+}
+
+#[cfg(test)]
+mod tests {
+    use spectral::prelude::*;
+
+    use super::TestServer;
+    use crate::errors::{ClientError, CreateExperimentErrorCode, GetExperimentErrorCode};
+    use crate::{FileInfo, MLflowAPI, RunStatus};
+
+    #[test]
+    fn can_serve_tracking_api() {
+        let server = TestServer::start().unwrap();
+        let api = MLflowAPI::new(server.url()).unwrap();
+
+        let experiment_id = api.create_experiment("served", None).unwrap();
+        let run = api
+            .create_run(&experiment_id, Some(1), None, Some("run"))
+            .unwrap();
+        let run_id = run.info.run_id;
+        api.log_param(&run_id, "alpha", "0.5").unwrap();
+        for step in 0..3 {
+            api.log_metric(&run_id, "loss", step as f32, 10 + step, Some(step))
+                .unwrap();
+        }
+        api.update_run(&run_id, RunStatus::Finished, Some(20), None)
+            .unwrap();
+
+        let (history, _) = api.get_metric_history(&run_id, "loss", None, None).unwrap();
+        assert_that!(history).has_length(3);
+        let (runs, _) = api
+            .search_runs(
+                &[&experiment_id],
+                Some("params.alpha = '0.5'"),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        assert_that!(runs).has_length(1);
+        assert_that!(runs[0].info.status).is_equal_to(RunStatus::Finished);
+        assert_that!(server.store().params_of(&run_id).get("alpha"))
+            .is_equal_to(Some(&"0.5".to_string()));
+    }
+
+    #[test]
+    fn can_serve_api_errors() {
+        let server = TestServer::start().unwrap();
+        let api = MLflowAPI::new(server.url()).unwrap();
+
+        // same codes and messages as the MLflow server, which the integration tests check
+        match api.get_experiment("42") {
+            Err(ClientError::ApiError {
+                error_code,
+                message,
+            }) => {
+                assert_that!(error_code).is_equal_to(GetExperimentErrorCode::ResourceDoesNotExist);
+                assert_that!(message)
+                    .is_equal_to("Could not find experiment with ID 42".to_string());
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        api.create_experiment("served", None).unwrap();
+        match api.create_experiment("served", None) {
+            Err(ClientError::ApiError {
+                error_code,
+                message,
+            }) => {
+                assert_that!(error_code)
+                    .is_equal_to(CreateExperimentErrorCode::ResourceAlreadyExists);
+                assert_that!(message)
+                    .is_equal_to("Experiment 'served' already exists.".to_string());
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn can_change_params_like_mlflow_1_3() {
+        let server = TestServer::start().unwrap();
+        let api = MLflowAPI::new(server.url()).unwrap();
+
+        let run = api.create_run("0", None, None, None).unwrap();
+        let run_id = run.info.run_id;
+        api.log_param(&run_id, "alpha", "0.5").unwrap();
+        api.log_param(&run_id, "alpha", "0.25").unwrap();
+
+        assert_that!(server.store().params_of(&run_id).get("alpha"))
+            .is_equal_to(Some(&"0.25".to_string()));
+    }
+
+    #[test]
+    fn can_list_artifacts_from_working_dir() {
+        let server = TestServer::start().unwrap();
+        let api = MLflowAPI::new(server.url()).unwrap();
+
+        let experiment_id = api.create_experiment("served", None).unwrap();
+        let run = api.create_run(&experiment_id, None, None, None).unwrap();
+        let (root_uri, files) = api.list_artifacts(&run.info.run_id, None).unwrap();
+        assert_that!(root_uri).is_equal_to(format!(
+            "./mlruns/{}/{}/artifacts",
+            experiment_id, run.info.run_id
+        ));
+        assert_that!(files).is_empty();
+
+        // the artifact directory is created with the run, and read when listing
+        let artifact_dir = server.working_dir().join(&root_uri);
+        std::fs::write(artifact_dir.join("model.txt"), "weights").unwrap();
+        std::fs::create_dir(artifact_dir.join("plots")).unwrap();
+        std::fs::write(artifact_dir.join("plots").join("loss.png"), "png").unwrap();
+        let (_, files) = api.list_artifacts(&run.info.run_id, None).unwrap();
+        assert_that!(files).is_equal_to(vec![
+            FileInfo {
+                path: "model.txt".to_string(),
+                is_dir: false,
+                file_size: Some(7),
+            },
+            FileInfo {
+                path: "plots".to_string(),
+                is_dir: true,
+                file_size: None,
+            },
+        ]);
+        let (_, files) = api.list_artifacts(&run.info.run_id, Some("plots")).unwrap();
+        assert_that!(files).has_length(1);
+        assert_that!(api
+            .list_artifacts(&run.info.run_id, Some("../.."))
+            .map(|_| ()))
+        .is_err();
+
+        // the temporary working directory goes with the server
+        let working_dir = server.working_dir().to_path_buf();
+        drop(server);
+        assert_that!(working_dir.exists()).is_false();
+    }
+}
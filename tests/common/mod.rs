@@ -0,0 +1,24 @@
+/// Tracking server used by the tests: the one at `MLFLOW_TRACKING_URL` if set, otherwise an in-process fake server
+/// with the `test-server` feature, or a local server on port 5000. The fake server runs from `MLFLOW_PATH`, where the
+/// tests write artifacts, like the MLflow container that mounts it.
+pub fn tracking_url() -> String {
+    if let Ok(url) = std::env::var("MLFLOW_TRACKING_URL") {
+        return url;
+    }
+    #[cfg(feature = "test-server")]
+    {
+        static SERVER: std::sync::OnceLock<mlflow_api::test_server::TestServer> =
+            std::sync::OnceLock::new();
+        SERVER
+            .get_or_init(|| {
+                let storage =
+                    std::env::var("MLFLOW_PATH").unwrap_or_else(|_| "/tmp/mlruns".to_string());
+                mlflow_api::test_server::TestServer::start_in(std::path::Path::new(&storage))
+                    .unwrap()
+            })
+            .url()
+            .to_string()
+    }
+    #[cfg(not(feature = "test-server"))]
+    "http://127.0.0.1:5000".to_string()
+}
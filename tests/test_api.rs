@@ -1,11 +1,10 @@
+mod common;
+
 use spectral::prelude::*;
 
 #[test]
 fn can_create_with_valid_url() {
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    );
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url());
 
     assert_that!(mlflow).is_ok();
 }
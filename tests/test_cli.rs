@@ -0,0 +1,324 @@
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output};
+
+use spectral::prelude::*;
+
+fn mlflow_api(store: &std::path::Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mlflow-api"))
+        .arg("--tracking-uri")
+        .arg(format!("file://{}", store.display()))
+        .args(["--output", "json"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn json(output: &Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn can_log_to_a_run() {
+    let store = tempfile::tempdir().unwrap();
+
+    let output = mlflow_api(store.path(), &["experiment", "create", "cli"]);
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    let experiment_id = json(&output)["experiment_id"].as_str().unwrap().to_string();
+
+    let output = mlflow_api(
+        store.path(),
+        &["run", "create", &experiment_id, "--tag", "team=search"],
+    );
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    let run_id = json(&output)["info"]["run_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let batch = store.path().join("batch.json");
+    std::fs::write(
+        &batch,
+        r#"{"metrics": [{"key": "rmse", "value": 0.5, "timestamp": 1, "step": 0}],
+            "params": [{"key": "depth", "value": "4"}]}"#,
+    )
+    .unwrap();
+    let output = mlflow_api(
+        store.path(),
+        &["run", "log-batch", &run_id, batch.to_str().unwrap()],
+    );
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+
+    let output = mlflow_api(store.path(), &["run", "get", &run_id]);
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    let run = json(&output);
+    assert_that!(run["data"]["metrics"][0]["value"]).is_equal_to(serde_json::json!(0.5));
+    assert_that!(run["data"]["params"][0]["value"]).is_equal_to(serde_json::json!("4"));
+    assert_that!(run["data"]["tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|tag| tag["key"] == "team"))
+    .is_true();
+}
+
+#[test]
+fn exit_codes_reflect_errors() {
+    let store = tempfile::tempdir().unwrap();
+
+    let output = mlflow_api(store.path(), &["run", "get", "does-not-exist"]);
+    assert_that!(output.status.code()).is_equal_to(Some(3));
+
+    let output = mlflow_api(
+        store.path(),
+        &["run", "update", "some-run", "--status", "done"],
+    );
+    assert_that!(output.status.code()).is_equal_to(Some(2));
+
+    let output = mlflow_api(store.path(), &["experiment", "create", "twice"]);
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    let output = mlflow_api(store.path(), &["experiment", "create", "twice"]);
+    assert_that!(output.status.code()).is_equal_to(Some(4));
+}
+
+#[test]
+fn can_print_csv() {
+    let store = tempfile::tempdir().unwrap();
+
+    let output = mlflow_api(store.path(), &["experiment", "list", "--output", "csv"]);
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    let csv = String::from_utf8(output.stdout).unwrap();
+    let mut lines = csv.lines();
+    assert_that!(lines.next().unwrap()).contains("experiment_id");
+    assert_that!(lines.next().unwrap()).contains("Default");
+}
+
+#[test]
+fn can_track_a_process() {
+    let store = tempfile::tempdir().unwrap();
+
+    let output = mlflow_api(
+        store.path(),
+        &[
+            "exec",
+            "--run-name",
+            "wrapped",
+            "--",
+            "sh",
+            "-c",
+            "echo $MLFLOW_RUN_ID; echo oops >&2; exit 3",
+        ],
+    );
+    assert_that!(output.status.code()).is_equal_to(Some(3));
+    let run_id = String::from_utf8(output.stdout).unwrap().trim().to_string();
+
+    let output = mlflow_api(store.path(), &["run", "get", &run_id]);
+    let run = json(&output);
+    assert_that!(run["info"]["status"]).is_equal_to(serde_json::json!("FAILED"));
+    let tags = run["data"]["tags"].as_array().unwrap();
+    assert_that!(tags
+        .iter()
+        .any(|tag| tag["key"] == "mlflow.exec.exitCode" && tag["value"] == "3"))
+    .is_true();
+
+    let artifacts = run["info"]["artifact_uri"]
+        .as_str()
+        .unwrap()
+        .trim_start_matches("file://")
+        .to_string();
+    let stderr = std::fs::read_to_string(std::path::Path::new(&artifacts).join("stderr.txt"));
+    assert_that!(stderr).is_ok_containing("oops\n".to_string());
+}
+
+#[test]
+fn can_tail_a_finished_run() {
+    let store = tempfile::tempdir().unwrap();
+
+    let output = mlflow_api(store.path(), &["run", "create", "0"]);
+    let run_id = json(&output)["info"]["run_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    for (step, value) in &[("0", "0.5"), ("1", "0.25")] {
+        let output = mlflow_api(
+            store.path(),
+            &["run", "log-metric", &run_id, "loss", value, "--step", step],
+        );
+        assert_that!(output.status.code()).is_equal_to(Some(0));
+    }
+    mlflow_api(
+        store.path(),
+        &["run", "update", &run_id, "--status", "finished"],
+    );
+
+    let output = mlflow_api(store.path(), &["tail", &run_id]);
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    let points: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_that!(points).has_length(2);
+    assert_that!(points[1]["value"]).is_equal_to(serde_json::json!(0.25));
+}
+
+#[test]
+fn can_collect_deleted_runs() {
+    let store = tempfile::tempdir().unwrap();
+
+    let output = mlflow_api(store.path(), &["run", "create", "0"]);
+    let run_id = json(&output)["info"]["run_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    mlflow_api(store.path(), &["run", "delete", &run_id]);
+
+    let output = mlflow_api(store.path(), &["gc", "--older-than", "1d"]);
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    assert_that!(json(&output)["runs"].as_array().unwrap().len()).is_equal_to(0);
+
+    let output = mlflow_api(store.path(), &["gc"]);
+    assert_that!(json(&output)["runs"][0]["run_id"]).is_equal_to(serde_json::json!(run_id));
+    assert_that!(json(&output)["collected"]).is_equal_to(serde_json::json!(false));
+    let output = mlflow_api(store.path(), &["run", "get", &run_id]);
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+
+    let output = mlflow_api(store.path(), &["gc", "--confirm"]);
+    assert_that!(json(&output)["collected"]).is_equal_to(serde_json::json!(true));
+    let output = mlflow_api(store.path(), &["run", "get", &run_id]);
+    assert_that!(output.status.code()).is_equal_to(Some(3));
+}
+
+#[test]
+fn can_update_runs_in_bulk() {
+    let store = tempfile::tempdir().unwrap();
+
+    for _ in 0..3 {
+        mlflow_api(
+            store.path(),
+            &["run", "create", "0", "--start-time", "1000"],
+        );
+    }
+    let selection = [
+        "--experiment-id",
+        "0",
+        "--filter",
+        "attributes.status = 'RUNNING'",
+        "--started-before",
+        "1d",
+    ];
+
+    let output = mlflow_api(
+        store.path(),
+        &[&["bulk", "update"][..], &selection, &["--status", "killed"]].concat(),
+    );
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    assert_that!(json(&output).as_array().unwrap().len()).is_equal_to(3);
+
+    let output = mlflow_api(
+        store.path(),
+        &[
+            &["bulk", "update"][..],
+            &selection,
+            &["--status", "killed", "--confirm"],
+        ]
+        .concat(),
+    );
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    assert_that!(json(&output)["succeeded"].as_array().unwrap().len()).is_equal_to(3);
+
+    let output = mlflow_api(
+        store.path(),
+        &[&["bulk", "delete"][..], &selection].concat(),
+    );
+    assert_that!(json(&output).as_array().unwrap().len()).is_equal_to(0);
+}
+
+#[test]
+fn can_default_to_a_local_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_mlflow-api"))
+        .args(["--output", "json", "experiment", "create", "local"])
+        .env_remove("MLFLOW_TRACKING_URI")
+        .current_dir(dir.path())
+        .output()
+        .unwrap();
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    assert_that!(dir.path().join("mlruns").join("0")).is_a_directory();
+}
+
+#[test]
+fn can_complete_experiments() {
+    let store = tempfile::tempdir().unwrap();
+    mlflow_api(store.path(), &["experiment", "create", "search"]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mlflow-api"))
+        .args(["completions", "bash", "--dynamic"])
+        .env_remove("MLFLOW_TRACKING_URI")
+        .output()
+        .unwrap();
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    assert_that!(String::from_utf8(output.stdout).unwrap()).contains("_mlflow_api_dynamic");
+
+    let output = mlflow_api(store.path(), &["complete", "experiment-names", "se"]);
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    assert_that!(String::from_utf8(output.stdout).unwrap()).is_equal_to("search\n".to_string());
+}
+
+#[test]
+fn can_kill_stale_runs() {
+    let store = tempfile::tempdir().unwrap();
+    let output = mlflow_api(store.path(), &["run", "create", "0", "--start-time", "1"]);
+    let run_id = json(&output)["info"]["run_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    mlflow_api(store.path(), &["run", "create", "0"]);
+    let args = ["kill-stale", "--experiment-id", "0", "--older-than", "1h"];
+
+    let output = mlflow_api(store.path(), &args);
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    assert_that!(json(&output)[0]["run_id"].as_str()).is_equal_to(Some(run_id.as_str()));
+
+    let output = mlflow_api(store.path(), &[&args[..], &["--confirm"]].concat());
+    assert_that!(json(&output).as_array().unwrap().len()).is_equal_to(1);
+    let output = mlflow_api(store.path(), &["run", "get", &run_id]);
+    assert_that!(json(&output)["info"]["status"].as_str()).is_equal_to(Some("KILLED"));
+}
+
+#[test]
+fn can_select_best_runs() {
+    let store = tempfile::tempdir().unwrap();
+    let mut run_ids = vec![];
+    for loss in &["0.5", "0.25", "0.75"] {
+        let output = mlflow_api(store.path(), &["run", "create", "0"]);
+        let run_id = json(&output)["info"]["run_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        mlflow_api(store.path(), &["run", "log-metric", &run_id, "loss", loss]);
+        mlflow_api(
+            store.path(),
+            &["run", "update", &run_id, "--status", "finished"],
+        );
+        run_ids.push(run_id);
+    }
+
+    let output = mlflow_api(
+        store.path(),
+        &[
+            "best-runs",
+            "--experiment-id",
+            "0",
+            "--metric",
+            "loss",
+            "--top",
+            "2",
+        ],
+    );
+    assert_that!(output.status.code()).is_equal_to(Some(0));
+    let best = json(&output);
+    assert_that!(best[0]["run_id"].as_str()).is_equal_to(Some(run_ids[1].as_str()));
+    assert_that!(best[1]["run_id"].as_str()).is_equal_to(Some(run_ids[0].as_str()));
+    assert_that!(best.as_array().unwrap().len()).is_equal_to(2);
+}
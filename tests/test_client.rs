@@ -1,13 +1,12 @@
+mod common;
+
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use spectral::prelude::*;
 
 #[test]
 fn can_create_with_valid_url() {
-    let mlflow = mlflow_api::MLflowClient::new_with_tracking_uri(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    );
+    let mlflow = mlflow_api::MLflowClient::new_with_tracking_uri(&common::tracking_url());
 
     assert_that!(mlflow).is_ok();
 }
@@ -18,11 +17,8 @@ fn can_run_basic_flow() {
     let metric_value_1 = thread_rng().gen::<f32>();
     let metric_value_2 = thread_rng().gen::<f32>();
 
-    let mut mlflow = mlflow_api::MLflowClient::new_with_tracking_uri(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mut mlflow =
+        mlflow_api::MLflowClient::new_with_tracking_uri(&common::tracking_url()).unwrap();
 
     let start = mlflow.start_run(&run_name);
     assert_that!(start).is_ok();
@@ -58,11 +54,8 @@ fn can_create_everything_on_the_fly() {
 
     std::env::set_var("MLFLOW_EXPERIMENT_NAME", &experiment_name);
 
-    let mut mlflow = mlflow_api::MLflowClient::new_with_tracking_uri(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mut mlflow =
+        mlflow_api::MLflowClient::new_with_tracking_uri(&common::tracking_url()).unwrap();
 
     let log = mlflow.log_metric("metric1", metric_value_1);
 
@@ -86,9 +79,78 @@ fn can_create_everything_on_the_fly() {
         .has_length(1);
     assert_that!(run_data).map(|data| &data.tags).has_length(1);
 
-    let experiment = mlflow.api.get_experiment(&run.info.experiment_id);
+    let experiment = mlflow.store.get_experiment(&run.info.experiment_id);
     assert_that!(experiment)
         .is_ok()
         .map(|experiment| &experiment.name)
         .is_equal_to(experiment_name);
 }
+
+#[test]
+fn can_get_metric_histories_of_several_runs() {
+    let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
+
+    let mut mlflow =
+        mlflow_api::MLflowClient::new_with_tracking_uri(&common::tracking_url()).unwrap();
+    let experiment_id = mlflow.create_experiment(&experiment_name, None).unwrap();
+
+    let mut run_ids = vec![];
+    for run in 0..3 {
+        mlflow
+            .start_run_in_experiment(&experiment_id, &format!("run-{}", run))
+            .unwrap();
+        for step in 0..10 {
+            mlflow
+                .log_metric_at_step("loss", (run * step) as f32, step)
+                .unwrap();
+        }
+        mlflow.end_run().unwrap();
+        run_ids.push(mlflow.active_run().unwrap().info.run_id);
+    }
+    let run_ids: Vec<&str> = run_ids.iter().map(String::as_str).collect();
+
+    let histories = mlflow.get_metric_histories(&run_ids, "loss", None);
+    assert_that!(histories).is_ok().has_length(3);
+    let histories = histories.unwrap();
+    for run_id in &run_ids {
+        assert_that!(histories.get(*run_id).map(Vec::len)).is_equal_to(Some(10));
+    }
+
+    let histories = mlflow.get_metric_histories(&run_ids, "loss", Some(4));
+    assert_that!(histories).is_ok();
+    assert_that!(histories.unwrap().get(run_ids[0]))
+        .is_some()
+        .map(|history| &history[3].step)
+        .is_equal_to(9);
+
+    mlflow.delete_experiment(&experiment_id).unwrap();
+}
+
+#[test]
+fn can_find_run_by_name() {
+    let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
+    let run_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
+
+    let mut mlflow =
+        mlflow_api::MLflowClient::new_with_tracking_uri(&common::tracking_url()).unwrap();
+    let experiment_id = mlflow.create_experiment(&experiment_name, None).unwrap();
+
+    mlflow
+        .start_run_in_experiment(&experiment_id, &run_name)
+        .unwrap();
+    let run_id = mlflow.active_run().unwrap().info.run_id;
+    mlflow.end_run().unwrap();
+
+    let found = mlflow.get_run_by_name(&experiment_id, &run_name);
+    assert_that!(found)
+        .is_ok()
+        .is_some()
+        .map(|run| &run.info.run_id)
+        .is_equal_to(&run_id);
+    assert_that!(found.unwrap().unwrap().run_name()).is_equal_to(Some(run_name.as_str()));
+
+    let not_found = mlflow.get_run_by_name(&experiment_id, "it's not there");
+    assert_that!(not_found).is_ok().is_none();
+
+    mlflow.delete_experiment(&experiment_id).unwrap();
+}
@@ -1,3 +1,5 @@
+mod common;
+
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use spectral::prelude::*;
@@ -6,11 +8,7 @@ use spectral::prelude::*;
 fn can_create_experiment() {
     let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
@@ -23,11 +21,7 @@ fn can_create_experiment() {
 fn cant_create_2_experiments_with_same_name() {
     let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
@@ -47,11 +41,7 @@ fn cant_create_2_experiments_with_same_name() {
 fn can_get_experiment() {
     let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
@@ -71,11 +61,7 @@ fn can_get_experiment() {
 fn cant_get_unknown_experiment() {
     let experiment_id: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let experiment = mlflow.get_experiment(&experiment_id);
     assert_that!(experiment)
@@ -90,11 +76,7 @@ fn cant_get_unknown_experiment() {
 fn can_get_experiment_by_name() {
     let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
@@ -114,11 +96,7 @@ fn can_update_experiment() {
     let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
     let new_experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
@@ -143,14 +121,12 @@ fn can_update_experiment() {
 }
 
 #[test]
+// the lifecycle stages are compared by reference, as the assertions were first written
+#[allow(clippy::needless_borrows_for_generic_args)]
 fn can_delete_and_restore_experiment() {
     let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
@@ -189,11 +165,7 @@ fn can_tag_experiment() {
     let tag_key: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
     let tag_value: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
@@ -222,12 +194,10 @@ fn can_tag_experiment() {
 }
 
 #[test]
+// the expected IDs are built in a `vec!`, as the assertions were first written
+#[allow(clippy::useless_vec)]
 fn can_list_experiments() {
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let list = mlflow.list_experiments(None);
     assert_that!(list).is_ok();
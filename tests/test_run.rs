@@ -1,3 +1,5 @@
+mod common;
+
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 use spectral::prelude::*;
@@ -6,17 +8,13 @@ use spectral::prelude::*;
 fn can_create_run() {
     let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
     let id = id.unwrap();
 
-    let run = mlflow.create_run(&id, Some(514425600000), None);
+    let run = mlflow.create_run(&id, Some(514425600000), None, None);
     assert_that!(run).is_ok();
     let run = run.unwrap();
 
@@ -37,17 +35,13 @@ fn can_create_run() {
 fn can_get_run() {
     let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
     let id = id.unwrap();
 
-    let create = mlflow.create_run(&id, Some(514425600000), None);
+    let create = mlflow.create_run(&id, Some(514425600000), None, None);
     assert_that!(create).is_ok();
 
     let run = mlflow.get_run(&create.unwrap().info.run_id);
@@ -71,17 +65,13 @@ fn can_get_run() {
 fn can_delete_and_restore_run() {
     let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
     let id = id.unwrap();
 
-    let run = mlflow.create_run(&id, Some(514425600000), None);
+    let run = mlflow.create_run(&id, Some(514425600000), None, None);
     assert_that!(run).is_ok();
     let run_id = run.unwrap().info.run_id;
 
@@ -108,36 +98,37 @@ fn can_delete_and_restore_run() {
 fn can_update_run() {
     let experiment_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
     let id = id.unwrap();
 
-    let run = mlflow.create_run(&id, Some(514425600000), None);
+    let run = mlflow.create_run(&id, Some(514425600000), None, None);
     assert_that!(run).is_ok();
     let run_id = run.unwrap().info.run_id;
 
     // Not changing the status
-    let updated = mlflow.update_run(&run_id, mlflow_api::RunStatus::Running, None);
+    let updated = mlflow.update_run(&run_id, mlflow_api::RunStatus::Running, None, None);
     assert_that!(updated)
         .is_ok()
         .map(|run_info| &run_info.status)
         .is_equal_to(mlflow_api::RunStatus::Running);
 
     // Changing to scheduled
-    let updated = mlflow.update_run(&run_id, mlflow_api::RunStatus::Scheduled, None);
+    let updated = mlflow.update_run(&run_id, mlflow_api::RunStatus::Scheduled, None, None);
     assert_that!(updated)
         .is_ok()
         .map(|run_info| &run_info.status)
         .is_equal_to(mlflow_api::RunStatus::Scheduled);
 
     // Changing to finished with an end time
-    let updated = mlflow.update_run(&run_id, mlflow_api::RunStatus::Finished, Some(514425600001));
+    let updated = mlflow.update_run(
+        &run_id,
+        mlflow_api::RunStatus::Finished,
+        Some(514425600001),
+        None,
+    );
     assert_that!(updated).is_ok();
     let updated = updated.unwrap();
     assert_that!(updated)
@@ -155,11 +146,7 @@ fn can_search_for_runs() {
     let experiment_name_1: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
     let experiment_name_2: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name_1, None);
     assert_that!(id).is_ok();
@@ -178,6 +165,7 @@ fn can_search_for_runs() {
                 .as_millis() as u64,
         ),
         None,
+        None,
     );
     assert_that!(run).is_ok();
 
@@ -190,6 +178,7 @@ fn can_search_for_runs() {
                 .as_millis() as u64,
         ),
         None,
+        None,
     );
     assert_that!(run).is_ok();
 
@@ -202,6 +191,7 @@ fn can_search_for_runs() {
                 .as_millis() as u64,
         ),
         None,
+        None,
     );
     assert_that!(run).is_ok();
 
@@ -1,3 +1,5 @@
+mod common;
+
 use std::fs::File;
 use std::io::prelude::*;
 
@@ -12,11 +14,7 @@ fn can_manage_run_tags() {
     let value1: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
     let value2: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
@@ -31,6 +29,7 @@ fn can_manage_run_tags() {
                 .as_millis() as u64,
         ),
         None,
+        None,
     );
     assert_that!(run).is_ok();
     let run_id = run.unwrap().info.run_id;
@@ -93,11 +92,7 @@ fn can_log_params() {
     let value1: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
     let value2: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
@@ -112,6 +107,7 @@ fn can_log_params() {
                 .as_millis() as u64,
         ),
         None,
+        None,
     );
     assert_that!(run).is_ok();
     let run_id = run.unwrap().info.run_id;
@@ -168,17 +164,13 @@ fn can_log_metrics() {
     let value_1 = thread_rng().gen::<f32>();
     let value_2 = thread_rng().gen::<f32>();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
     let id = id.unwrap();
 
-    let run = mlflow.create_run(&id, Some(ts_0), None);
+    let run = mlflow.create_run(&id, Some(ts_0), None, None);
     assert_that!(run).is_ok();
     let run_id = run.unwrap().info.run_id;
 
@@ -224,7 +216,9 @@ fn can_log_metrics() {
             step: 0,
         });
 
-    let metrics = mlflow.get_metric_history(&run_id, &key);
+    let metrics = mlflow
+        .get_metric_history(&run_id, &key, None, None)
+        .map(|(metrics, _)| metrics);
     assert_that!(metrics).is_ok().has_length(2);
     assert_that!(metrics).is_ok().contains(mlflow_api::Metric {
         key: key.clone(),
@@ -256,17 +250,13 @@ fn can_log_batch() {
         .expect("time went strange there")
         .as_millis() as u64;
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
 
     let id = mlflow.create_experiment(&experiment_name, None);
     assert_that!(id).is_ok();
     let id = id.unwrap();
 
-    let run = mlflow.create_run(&id, Some(ts_0), None);
+    let run = mlflow.create_run(&id, Some(ts_0), None, None);
     assert_that!(run).is_ok();
     let run_id = run.unwrap().info.run_id;
 
@@ -393,11 +383,7 @@ fn can_list_artifacts() {
     let file_name: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
     let content: String = thread_rng().sample_iter(&Alphanumeric).take(30).collect();
 
-    let mlflow = mlflow_api::MLflowAPI::new(
-        &std::env::var("MLFLOW_TRACKING_URL")
-            .unwrap_or_else(|_| "http://127.0.0.1:5000".to_string()),
-    )
-    .unwrap();
+    let mlflow = mlflow_api::MLflowAPI::new(&common::tracking_url()).unwrap();
     let storage = std::env::var("MLFLOW_PATH").unwrap_or_else(|_| "/tmp/mlruns".to_string());
 
     let id = mlflow.create_experiment(&experiment_name, None);
@@ -413,6 +399,7 @@ fn can_list_artifacts() {
                 .as_millis() as u64,
         ),
         None,
+        None,
     );
     assert_that!(run).is_ok();
     let run_id = run.unwrap().info.run_id;